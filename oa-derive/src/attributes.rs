@@ -0,0 +1,204 @@
+use proc_macro2::TokenTree;
+use syn::{meta::ParseNestedMeta, Attribute, Expr, Lit, LitStr, Meta, Token};
+
+/// The serde attributes of a container, field or variant that change how it is serialized.
+#[derive(Default)]
+pub(crate) struct SerdeAttributes {
+    pub(crate) rename: Option<String>,
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) skip: bool,
+    pub(crate) skip_serializing_if: bool,
+    pub(crate) flatten: bool,
+    pub(crate) default: bool,
+    pub(crate) transparent: bool,
+    pub(crate) tag: Option<String>,
+    pub(crate) content: Option<String>,
+    pub(crate) untagged: bool,
+}
+
+impl SerdeAttributes {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut attributes = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    attributes.rename = Some(serialized_value(&meta)?);
+                } else if meta.path.is_ident("rename_all") {
+                    let rule = serialized_value(&meta)?;
+                    attributes.rename_all =
+                        Some(RenameRule::from_str(&rule).ok_or_else(|| {
+                            meta.error(format!("unknown rename rule `{}`", rule))
+                        })?);
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    attributes.skip = true;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    attributes.skip_serializing_if = true;
+                    ignore(&meta)?;
+                } else if meta.path.is_ident("flatten") {
+                    attributes.flatten = true;
+                } else if meta.path.is_ident("default") {
+                    attributes.default = true;
+                    ignore(&meta)?;
+                } else if meta.path.is_ident("transparent") {
+                    attributes.transparent = true;
+                } else if meta.path.is_ident("tag") {
+                    attributes.tag = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("content") {
+                    attributes.content = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("untagged") {
+                    attributes.untagged = true;
+                } else {
+                    ignore(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(attributes)
+    }
+}
+
+/// The value of `name = "value"`, or of `serialize` in `name(serialize = "value", deserialize = "other")`.
+fn serialized_value(meta: &ParseNestedMeta) -> syn::Result<String> {
+    if meta.input.peek(Token![=]) {
+        return Ok(meta.value()?.parse::<LitStr>()?.value());
+    }
+
+    let mut value = None;
+    meta.parse_nested_meta(|nested| {
+        let literal = nested.value()?.parse::<LitStr>()?;
+        if nested.path.is_ident("serialize") {
+            value = Some(literal.value());
+        }
+        Ok(())
+    })?;
+    value.ok_or_else(|| meta.error("expected a `serialize` value"))
+}
+
+/// Skips the value of an attribute that doesn't change the schema.
+fn ignore(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+        meta.input.parse::<TokenTree>()?;
+    }
+    Ok(())
+}
+
+/// The doc comments in `attrs`, joined into a description.
+pub(crate) fn docs(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(doc) => Some(doc.value()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let docs = lines.join("\n").trim().to_string();
+    if docs.is_empty() {
+        None
+    } else {
+        Some(docs)
+    }
+}
+
+/// The case conversions of serde's `rename_all`.
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Renames a field, whose name is in snake case.
+    pub(crate) fn apply_to_field(self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal => pascal_case(field),
+            Self::Camel => {
+                let pascal = pascal_case(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+
+    /// Renames a variant, whose name is in Pascal case.
+    pub(crate) fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            Self::Pascal => variant.to_string(),
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Camel => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                self.apply_to_field(&snake)
+            }
+        }
+    }
+}
+
+fn pascal_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,27 @@
+//! Derive and attribute macros for [oa](https://docs.rs/oa), which re-exports them with its `derive` feature.
+
+mod attributes;
+mod operation;
+mod to_schema;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+/// Derives `oa::v3::ToSchema` for a struct or an enum, honoring its serde attributes.
+#[proc_macro_derive(ToSchema, attributes(serde))]
+pub fn derive_to_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_schema::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `oa::v3::Handler` for a type named after the function, describing the operation it serves.
+#[proc_macro_attribute]
+pub fn operation(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as operation::OperationArgs);
+    let function = parse_macro_input!(item as ItemFn);
+    operation::expand(args, function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,334 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    FnArg, Ident, ItemFn, LitInt, LitStr, Meta, Pat, ReturnType, Token, Type,
+};
+
+use crate::{attributes::docs, to_schema::type_argument};
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// The arguments of `#[operation]`: the method, the path template, and optionally `tag`, `operation_id` and `status`.
+pub(crate) struct OperationArgs {
+    method: Ident,
+    path: LitStr,
+    tags: Vec<LitStr>,
+    operation_id: Option<LitStr>,
+    status: Option<u16>,
+}
+
+impl Parse for OperationArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let method: Ident = input.parse()?;
+        if !METHODS.contains(&method.to_string().as_str()) {
+            return Err(syn::Error::new(
+                method.span(),
+                format!("expected one of {}", METHODS.join(", ")),
+            ));
+        }
+        input.parse::<Token![,]>()?;
+
+        let mut args = Self {
+            method,
+            path: input.parse()?,
+            tags: Vec::new(),
+            operation_id: None,
+            status: None,
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if key == "tag" {
+                args.tags.push(input.parse()?);
+            } else if key == "operation_id" {
+                args.operation_id = Some(input.parse()?);
+            } else if key == "status" {
+                let status: LitInt = input.parse()?;
+                let code = status.base10_parse()?;
+                if !(100..=599).contains(&code) {
+                    return Err(syn::Error::new(status.span(), "expected a status code"));
+                }
+                args.status = Some(code);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `tag`, `operation_id` or `status`",
+                ));
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// Where an argument of the function goes in the request.
+#[derive(Clone, Copy, PartialEq)]
+enum Location {
+    Path,
+    Query,
+    Header,
+    Cookie,
+    Body,
+}
+
+impl Location {
+    const ATTRIBUTES: [(&'static str, Location); 5] = [
+        ("path", Location::Path),
+        ("query", Location::Query),
+        ("header", Location::Header),
+        ("cookie", Location::Cookie),
+        ("body", Location::Body),
+    ];
+
+    fn parameter_location(self) -> TokenStream {
+        match self {
+            Location::Path => quote!(::oa::v3::ParameterLocation::Path),
+            Location::Query => quote!(::oa::v3::ParameterLocation::Query),
+            Location::Header => quote!(::oa::v3::ParameterLocation::Header),
+            Location::Cookie => quote!(::oa::v3::ParameterLocation::Cookie),
+            Location::Body => unreachable!("the body isn't a parameter"),
+        }
+    }
+}
+
+pub(crate) fn expand(args: OperationArgs, mut function: ItemFn) -> syn::Result<TokenStream> {
+    if !function.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &function.sig.generics,
+            "`#[operation]` doesn't apply to generic functions",
+        ));
+    }
+
+    let variables = template_variables(&args.path.value());
+    // The types the operation is made of, whose component schemas are registered along with it.
+    let mut types = Vec::new();
+    let mut path_parameters = Vec::new();
+    let mut parameters = Vec::new();
+    let mut body = None;
+
+    for input in &mut function.sig.inputs {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "`#[operation]` only applies to free functions",
+                ))
+            }
+        };
+
+        // The location attributes are removed, as they mean nothing to the compiler
+        let mut annotation = None;
+        let mut attrs = Vec::new();
+        for attr in input.attrs.drain(..) {
+            let location = Location::ATTRIBUTES
+                .iter()
+                .find(|(name, _)| attr.path().is_ident(name));
+            match location {
+                Some(_) if annotation.is_some() => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "an argument can only be in one location",
+                    ))
+                }
+                Some((_, location)) => {
+                    let name = match &attr.meta {
+                        Meta::Path(_) => None,
+                        _ => Some(attr.parse_args::<LitStr>()?.value()),
+                    };
+                    annotation = Some((*location, name));
+                }
+                None => attrs.push(attr),
+            }
+        }
+        input.attrs = attrs;
+
+        let argument = match &*input.pat {
+            Pat::Ident(pat) => Some(pat.ident.unraw().to_string()),
+            _ => None,
+        };
+        let (location, name) = match annotation {
+            Some((location, name)) => (location, name.or(argument)),
+            None => match argument {
+                Some(argument) if variables.contains(&argument) => (Location::Path, Some(argument)),
+                _ => continue,
+            },
+        };
+
+        let (ty, required) = match type_argument(&input.ty, "Option") {
+            Some(ty) => (ty, false),
+            None => (&*input.ty, true),
+        };
+        types.push(ty.clone());
+        let schema = quote!(<#ty as ::oa::v3::ToSchema>::reference_or_schema());
+
+        if location == Location::Body {
+            if body.is_some() {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "only one argument can be the body",
+                ));
+            }
+            body = Some(quote!(::oa::v3::__private::json_body(#schema, #required)));
+            continue;
+        }
+
+        let name = name.ok_or_else(|| {
+            syn::Error::new_spanned(&input.pat, "name the parameter, e.g. `#[query(\"limit\")]`")
+        })?;
+        let parameter_location = location.parameter_location();
+        if location == Location::Path {
+            if !variables.contains(&name) {
+                return Err(syn::Error::new_spanned(
+                    &input.pat,
+                    format!("`{}` isn't a variable of the path", name),
+                ));
+            }
+            path_parameters.push((
+                name.clone(),
+                quote!(::oa::v3::__private::parameter(#parameter_location, #name, true, #schema)),
+            ));
+        } else {
+            parameters.push(quote! {
+                ::oa::v3::__private::parameter(#parameter_location, #name, #required, #schema)
+            });
+        }
+    }
+
+    // Path parameters come first in the order of the template, those without an argument being strings
+    let parameters: Vec<TokenStream> = variables
+        .iter()
+        .map(|variable| {
+            match path_parameters
+                .iter()
+                .position(|(name, _)| name == variable)
+            {
+                Some(index) => path_parameters.remove(index).1,
+                None => quote! {
+                    ::oa::v3::__private::parameter(
+                        ::oa::v3::ParameterLocation::Path,
+                        #variable,
+                        true,
+                        <::std::string::String as ::oa::v3::ToSchema>::reference_or_schema(),
+                    )
+                },
+            }
+        })
+        .chain(parameters)
+        .collect();
+    let parameters = if parameters.is_empty() {
+        quote!(::std::option::Option::None)
+    } else {
+        quote!(::std::option::Option::Some(::std::vec![#(#parameters),*]))
+    };
+    let body = match body {
+        Some(body) => quote!(::std::option::Option::Some(#body)),
+        None => quote!(::std::option::Option::None),
+    };
+
+    let output = match &function.sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => match type_argument(ty, "Result").unwrap_or(ty) {
+            Type::Tuple(tuple) if tuple.elems.is_empty() => None,
+            ty => Some(ty.clone()),
+        },
+    };
+    let status = args
+        .status
+        .unwrap_or(if output.is_some() { 200 } else { 204 });
+    let response = match &output {
+        Some(ty) => {
+            quote!(::std::option::Option::Some(<#ty as ::oa::v3::ToSchema>::reference_or_schema()))
+        }
+        None => quote!(::std::option::Option::None),
+    };
+    types.extend(output);
+
+    let docs = docs(&function.attrs).unwrap_or_default();
+    let mut paragraphs = docs.splitn(2, "\n\n");
+    let summary = optional(paragraphs.next());
+    let description = optional(paragraphs.next());
+    let deprecated = function
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("deprecated"));
+
+    let tags = if args.tags.is_empty() {
+        quote!(::std::option::Option::None)
+    } else {
+        let tags = &args.tags;
+        quote!(::std::option::Option::Some(
+            ::std::vec![#(#tags.to_string()),*]
+        ))
+    };
+    let ident = &function.sig.ident;
+    let operation_id = args
+        .operation_id
+        .map(|operation_id| operation_id.value())
+        .unwrap_or_else(|| ident.unraw().to_string());
+    let method = format_ident!("{}", args.method.to_string().to_uppercase());
+    let path = &args.path;
+    let vis = &function.vis;
+
+    Ok(quote! {
+        #function
+
+        #[allow(dead_code, non_camel_case_types)]
+        #[doc(hidden)]
+        #vis struct #ident {}
+
+        impl ::oa::v3::Handler for #ident {
+            fn method() -> ::oa::v3::__private::Method {
+                ::oa::v3::__private::Method::#method
+            }
+
+            fn path() -> &'static str {
+                #path
+            }
+
+            fn operation() -> ::oa::v3::OperationObject {
+                ::oa::v3::OperationObject {
+                    tags: #tags,
+                    summary: #summary,
+                    description: #description,
+                    operation_id: ::std::option::Option::Some(#operation_id.to_string()),
+                    parameters: #parameters,
+                    request_body: #body,
+                    responses: ::oa::v3::__private::json_response(#status, #response),
+                    deprecated: #deprecated,
+                    ..::std::default::Default::default()
+                }
+            }
+
+            fn register(components: &mut ::oa::v3::ComponentsObject) {
+                #(<#types as ::oa::v3::ToSchema>::register(components);)*
+            }
+        }
+    })
+}
+
+/// The names of the variables of a path template, e.g. `id` in `/users/{id}`.
+fn template_variables(template: &str) -> Vec<String> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}'))
+        .map(|(variable, _)| variable.to_string())
+        .collect()
+}
+
+fn optional(value: Option<&str>) -> TokenStream {
+    match value.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => quote!(::std::option::Option::Some(#value.to_string())),
+        None => quote!(::std::option::Option::None),
+    }
+}
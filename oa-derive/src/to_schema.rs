@@ -0,0 +1,239 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_quote, Data, DataEnum, DeriveInput, Fields, GenericArgument,
+    PathArguments, Type,
+};
+
+use crate::attributes::{docs, SerdeAttributes};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let container = SerdeAttributes::parse(&input.attrs)?;
+
+    // The types the schema is made of, whose component schemas are registered along with it.
+    let mut types = Vec::new();
+    let schema = match &input.data {
+        Data::Struct(data) => fields_schema(&data.fields, &container, &mut types)?,
+        Data::Enum(data) => enum_schema(data, &container, &mut types)?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "`ToSchema` can't be derived for unions",
+            ))
+        }
+    };
+    let schema = match docs(&input.attrs) {
+        Some(description) => quote! {
+            ::oa::v3::SchemaObject {
+                description: ::std::option::Option::Some(#description.to_string()),
+                ..#schema
+            }
+        },
+        None => schema,
+    };
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::oa::v3::ToSchema));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Generic types are inlined, as every instantiation has its own schema.
+    let named = if input.generics.type_params().next().is_none() {
+        let name = container
+            .rename
+            .clone()
+            .unwrap_or_else(|| input.ident.unraw().to_string());
+        quote! {
+            fn schema_name() -> ::std::option::Option<::std::string::String> {
+                ::std::option::Option::Some(#name.to_string())
+            }
+
+            fn register(components: &mut ::oa::v3::ComponentsObject) {
+                if ::oa::v3::__private::insert_schema(
+                    components,
+                    #name.to_string(),
+                    <Self as ::oa::v3::ToSchema>::schema,
+                ) {
+                    #(<#types as ::oa::v3::ToSchema>::register(components);)*
+                }
+            }
+        }
+    } else {
+        quote! {
+            fn register(components: &mut ::oa::v3::ComponentsObject) {
+                #(<#types as ::oa::v3::ToSchema>::register(components);)*
+            }
+        }
+    };
+
+    let ident = &input.ident;
+    Ok(quote! {
+        impl #impl_generics ::oa::v3::ToSchema for #ident #ty_generics #where_clause {
+            #named
+
+            fn schema() -> ::oa::v3::SchemaObject {
+                #schema
+            }
+        }
+    })
+}
+
+/// The schema of the fields of a struct or variant, as an expression of type `SchemaObject`.
+fn fields_schema<'f>(
+    fields: &'f Fields,
+    container: &SerdeAttributes,
+    types: &mut Vec<&'f Type>,
+) -> syn::Result<TokenStream> {
+    // The schemas of all the fields, and the properties and flattened schemas of named ones.
+    let mut items = Vec::new();
+    let mut properties = Vec::new();
+    let mut flattened = Vec::new();
+
+    for field in fields {
+        let attributes = SerdeAttributes::parse(&field.attrs)?;
+        if attributes.skip {
+            continue;
+        }
+
+        let ty = &field.ty;
+        types.push(ty);
+        let schema = quote!(<#ty as ::oa::v3::ToSchema>::reference_or_schema());
+        items.push(schema.clone());
+
+        let ident = match &field.ident {
+            Some(ident) => ident,
+            None => continue,
+        };
+        if attributes.flatten {
+            flattened.push(schema);
+            continue;
+        }
+
+        let name = match (attributes.rename, container.rename_all) {
+            (Some(name), _) => name,
+            (None, Some(rule)) => rule.apply_to_field(&ident.unraw().to_string()),
+            (None, None) => ident.unraw().to_string(),
+        };
+        let schema = match docs(&field.attrs) {
+            Some(description) => quote!(::oa::v3::__private::described(#schema, #description)),
+            None => schema,
+        };
+        let required = !is_option(ty)
+            && !attributes.default
+            && !attributes.skip_serializing_if
+            && !container.default;
+        properties.push(quote!((#name, #schema, #required)));
+    }
+
+    Ok(match fields {
+        Fields::Named(_) if container.transparent && items.len() == 1 => {
+            let item = &items[0];
+            quote!(::oa::v3::__private::inline(#item))
+        }
+        Fields::Named(_) => quote! {
+            ::oa::v3::__private::flattened(
+                ::oa::v3::__private::object(::std::vec![#(#properties),*]),
+                ::std::vec![#(#flattened),*],
+            )
+        },
+        Fields::Unnamed(_) if items.len() == 1 => {
+            let item = &items[0];
+            quote!(::oa::v3::__private::inline(#item))
+        }
+        Fields::Unnamed(_) => quote!(::oa::v3::__private::tuple(::std::vec![#(#items),*])),
+        Fields::Unit => quote!(::oa::v3::__private::unit()),
+    })
+}
+
+fn enum_schema<'f>(
+    data: &'f DataEnum,
+    container: &SerdeAttributes,
+    types: &mut Vec<&'f Type>,
+) -> syn::Result<TokenStream> {
+    let mut names = Vec::new();
+    let mut variants = Vec::new();
+    let mut all_unit = true;
+
+    for variant in &data.variants {
+        let attributes = SerdeAttributes::parse(&variant.attrs)?;
+        if attributes.skip {
+            continue;
+        }
+
+        let name = match (attributes.rename.clone(), container.rename_all) {
+            (Some(name), _) => name,
+            (None, Some(rule)) => rule.apply_to_variant(&variant.ident.unraw().to_string()),
+            (None, None) => variant.ident.unraw().to_string(),
+        };
+
+        let content = match &variant.fields {
+            Fields::Unit => quote!(::std::option::Option::None),
+            // Newtype variants hold their content as is, so it can be referenced
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                all_unit = false;
+                let ty = &fields.unnamed[0].ty;
+                types.push(ty);
+                quote! {
+                    ::std::option::Option::Some(<#ty as ::oa::v3::ToSchema>::reference_or_schema())
+                }
+            }
+            fields => {
+                all_unit = false;
+                let schema = fields_schema(fields, &attributes, types)?;
+                quote! {
+                    ::std::option::Option::Some(::oa::v3::ReferenceObjectOr::Object(#schema))
+                }
+            }
+        };
+
+        let schema = match (&container.tag, &container.content) {
+            _ if container.untagged => quote!(::oa::v3::__private::untagged(#content)),
+            (Some(tag), Some(content_name)) => quote! {
+                ::oa::v3::__private::adjacently_tagged(#tag, #content_name, #name, #content)
+            },
+            (Some(tag), None) => {
+                quote!(::oa::v3::__private::internally_tagged(#tag, #name, #content))
+            }
+            (None, _) => quote!(::oa::v3::__private::externally_tagged(#name, #content)),
+        };
+        let schema = match docs(&variant.attrs) {
+            Some(description) => quote!(::oa::v3::__private::described(#schema, #description)),
+            None => schema,
+        };
+
+        names.push(name);
+        variants.push(schema);
+    }
+
+    if all_unit && container.tag.is_none() && !container.untagged {
+        return Ok(quote!(::oa::v3::__private::string_enum(&[#(#names),*])));
+    }
+    Ok(quote!(::oa::v3::__private::one_of(
+        ::std::vec![#(#variants),*]
+    )))
+}
+
+/// Whether `ty` is an `Option`, which serde allows to be missing.
+fn is_option(ty: &Type) -> bool {
+    type_argument(ty, "Option").is_some()
+}
+
+/// The first type argument of `ty` if it is named `name`, e.g. `T` for `Option<T>` and `"Option"`.
+pub(crate) fn type_argument<'t>(ty: &'t Type, name: &str) -> Option<&'t Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
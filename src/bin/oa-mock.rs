@@ -0,0 +1,266 @@
+//! Serves mock responses for the operations of an OpenAPI document.
+//!
+//! ```text
+//! oa-mock <document.yaml|document.json> [--port 4010] [--seed 42] [--no-validation]
+//! ```
+
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, HeaderValue, Request, Response, StatusCode};
+use oa::v3::{mock::MockServer, OpenAPIObject};
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    process,
+    time::Duration,
+};
+
+struct Args {
+    document: String,
+    port: u16,
+    seed: Option<u64>,
+    validation: bool,
+}
+
+/// The largest body read, larger ones are rejected before being read.
+const MAX_BODY_LENGTH: usize = 16 * 1024 * 1024;
+
+/// The longest request line read, including the line break.
+const MAX_REQUEST_LINE_LENGTH: usize = 8 * 1024;
+
+/// The most bytes read for all the header lines together.
+const MAX_HEADERS_LENGTH: usize = 64 * 1024;
+
+/// How long a connection may stay idle before it is dropped, so that one client can't block the others.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const USAGE: &str = "usage: oa-mock <document> [--port <port>] [--seed <seed>] [--no-validation]";
+
+fn parse_args() -> Result<Args, String> {
+    let mut document = None;
+    let mut port = 4010;
+    let mut seed = None;
+    let mut validation = true;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                let value = args.next().ok_or("missing value for --port")?;
+                port = value
+                    .parse()
+                    .map_err(|_| format!("invalid port `{}`", value))?;
+            }
+            "--seed" => {
+                let value = args.next().ok_or("missing value for --seed")?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed `{}`", value))?,
+                );
+            }
+            "--no-validation" => validation = false,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if document.is_none() && !arg.starts_with('-') => document = Some(arg),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+
+    Ok(Args {
+        document: document.ok_or(USAGE)?,
+        port,
+        seed,
+        validation,
+    })
+}
+
+fn load(path: &str) -> Result<OpenAPIObject, String> {
+    let source = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+    if path.ends_with(".json") {
+        serde_json::from_str(&source).map_err(|error| format!("{}: {}", path, error))
+    } else {
+        serde_yaml::from_str(&source).map_err(|error| format!("{}: {}", path, error))
+    }
+}
+
+fn main() {
+    let result = parse_args().and_then(|args| {
+        let document = load(&args.document)?;
+        serve(&document, &args).map_err(|error| error.to_string())
+    });
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn serve(document: &OpenAPIObject, args: &Args) -> io::Result<()> {
+    let mut server = MockServer::new(document);
+    if let Some(seed) = args.seed {
+        server = server.with_seed(seed);
+    }
+    if !args.validation {
+        server = server.without_validation();
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", args.port))?;
+    eprintln!("listening on http://{}", listener.local_addr()?);
+
+    // Connections are answered one at a time and closed after a single response
+    for stream in listener.incoming() {
+        let result = stream.and_then(|mut stream| {
+            stream.set_read_timeout(Some(READ_TIMEOUT))?;
+            let response = match read_request(&mut stream)? {
+                Incoming::Request(request) => {
+                    let response = server.handle(&request);
+                    eprintln!(
+                        "{} {} -> {}",
+                        request.method(),
+                        request.uri(),
+                        response.status().as_u16()
+                    );
+                    response
+                }
+                Incoming::Rejected(response) => {
+                    eprintln!("malformed request -> {}", response.status().as_u16());
+                    response
+                }
+                Incoming::Closed => return Ok(()),
+            };
+            write_response(&mut stream, &response)
+        });
+        if let Err(error) = result {
+            eprintln!("{}", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// What was read from a connection.
+enum Incoming {
+    Request(Request<Bytes>),
+    /// A malformed request, answered with this response.
+    Rejected(Response<Bytes>),
+    /// The connection was closed before sending anything.
+    Closed,
+}
+
+fn rejected(status: StatusCode, message: &str) -> Incoming {
+    let body = serde_json::json!({ "errors": [{ "message": message }] });
+    let mut response = Response::new(Bytes::from(body.to_string()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Incoming::Rejected(response)
+}
+
+/// Reads a line of at most `limit` bytes into `line`, returning `None` if the line is longer.
+fn read_line(
+    reader: &mut impl BufRead,
+    line: &mut String,
+    limit: usize,
+) -> io::Result<Option<usize>> {
+    line.clear();
+    let read = reader.by_ref().take(limit as u64).read_line(line)?;
+    if read == limit && !line.ends_with('\n') {
+        Ok(None)
+    } else {
+        Ok(Some(read))
+    }
+}
+
+/// Reads an HTTP/1.1 request whose body, if any, is delimited by `Content-Length` and at most [`MAX_BODY_LENGTH`] bytes long.
+///
+/// The request line and the headers are capped at [`MAX_REQUEST_LINE_LENGTH`] and [`MAX_HEADERS_LENGTH`] bytes.
+fn read_request(stream: &mut TcpStream) -> io::Result<Incoming> {
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    match read_line(&mut reader, &mut line, MAX_REQUEST_LINE_LENGTH)? {
+        Some(0) => return Ok(Incoming::Closed),
+        Some(_) => {}
+        None => {
+            return Ok(rejected(
+                StatusCode::BAD_REQUEST,
+                "the request line is too long",
+            ))
+        }
+    }
+    let mut parts = line.split_whitespace();
+    let (method, uri) = match (parts.next(), parts.next()) {
+        (Some(method), Some(uri)) => (method.to_string(), uri.to_string()),
+        _ => return Ok(rejected(StatusCode::BAD_REQUEST, "malformed request line")),
+    };
+
+    let mut builder = Request::builder().method(method.as_str()).uri(uri.as_str());
+    let mut length = 0;
+    let mut remaining = MAX_HEADERS_LENGTH;
+    loop {
+        match read_line(&mut reader, &mut line, remaining)? {
+            Some(0) => break,
+            Some(read) => remaining -= read,
+            None => {
+                return Ok(rejected(
+                    StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                    "the headers are too large",
+                ))
+            }
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = match header.split_once(':') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => return Ok(rejected(StatusCode::BAD_REQUEST, "malformed header")),
+        };
+        if name.eq_ignore_ascii_case("content-length") {
+            length = match value.parse() {
+                Ok(length) if length <= MAX_BODY_LENGTH => length,
+                Ok(_) => {
+                    return Ok(rejected(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        "the body is too large",
+                    ))
+                }
+                Err(_) => return Ok(rejected(StatusCode::BAD_REQUEST, "invalid Content-Length")),
+            };
+        }
+        if let Ok(value) = HeaderValue::from_str(value) {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(match builder.body(Bytes::from(body)) {
+        Ok(request) => Incoming::Request(request),
+        Err(error) => rejected(StatusCode::BAD_REQUEST, &error.to_string()),
+    })
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response<Bytes>) -> io::Result<()> {
+    let status = response.status();
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        status.as_str(),
+        status.canonical_reason().unwrap_or_default()
+    );
+    for (name, value) in response.headers() {
+        if let Ok(value) = value.to_str() {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body().len()
+    ));
+
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(response.body())?;
+    stream.flush()
+}
@@ -3,3 +3,58 @@
 pub(crate) const fn default_true() -> bool {
     true
 }
+
+/// Appends `segment` to a JSON Pointer, escaping it as required by [RFC6901](https://tools.ietf.org/html/rfc6901#section-3).
+pub(crate) fn push_pointer(pointer: &str, segment: &str) -> String {
+    format!(
+        "{}/{}",
+        pointer,
+        segment.replace('~', "~0").replace('/', "~1")
+    )
+}
+
+/// Converts an identifier like `get_user-by id` or `getUserById` to `GetUserById`, dropping every character that is not alphanumeric.
+pub(crate) fn pascal_case(identifier: &str) -> String {
+    identifier
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// Converts an identifier like `petType`, `HTTPCode` or `created-at` to `pet_type`, `http_code` and `created_at`, dropping every character that is not alphanumeric.
+pub(crate) fn snake_case(identifier: &str) -> String {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut snake = String::with_capacity(identifier.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            continue;
+        }
+
+        if c.is_ascii_uppercase() {
+            let previous = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let boundary = previous
+                .is_some_and(|previous| previous.is_ascii_lowercase() || previous.is_ascii_digit())
+                || (previous.is_some_and(|previous| previous.is_ascii_uppercase())
+                    && next.is_some_and(|next| next.is_ascii_lowercase()));
+
+            if boundary && !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+
+    snake.trim_end_matches('_').to_string()
+}
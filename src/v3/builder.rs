@@ -0,0 +1,513 @@
+use http::{Method, StatusCode};
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::fmt;
+
+use super::{
+    ComponentsObject, ContentMap, InfoObject, MediaType, MediaTypeObject, OpenAPIObject,
+    OperationObject, ParameterLocation, ParameterObject, ParameterObjectFixedFields,
+    PathsItemObject, ReferenceObjectOr, RequestBodyObject, ResponseKey, ResponseObject,
+    SchemaObject, SchemaOrContentObject, SchemaType, ServerObject, TagObject, ToSchema,
+};
+
+/// The error returned by builders when an object can't be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// A required field wasn't given, e.g. `info.title`.
+    MissingField(&'static str),
+    /// A response was given a status code outside of 100 to 599.
+    InvalidStatus(u16),
+    /// An operation was given a method a path item can't describe, e.g. `CONNECT`.
+    UnsupportedMethod(Method),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingField(field) => write!(f, "`{}` is required", field),
+            BuildError::InvalidStatus(status) => {
+                write!(f, "`{}` is not a valid HTTP status code", status)
+            }
+            BuildError::UnsupportedMethod(method) => {
+                write!(f, "a path item can't describe {} operations", method)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+pub(crate) fn json_content(schema: ReferenceObjectOr<SchemaObject>) -> ContentMap {
+    let media_type: MediaType = "application/json".parse().unwrap();
+    let mut content = ContentMap::new();
+    content.insert(
+        media_type,
+        MediaTypeObject {
+            schema: Some(schema),
+            example: None,
+            examples: None,
+            encoding: None,
+        },
+    );
+    content
+}
+
+impl OpenAPIObject {
+    pub fn builder() -> OpenAPIObjectBuilder {
+        OpenAPIObjectBuilder::default()
+    }
+}
+
+/// Builds a document, for version 3.0.3 of the specification unless [told otherwise](OpenAPIObjectBuilder::openapi).
+/// The title and version of the API are required.
+#[derive(Debug, Default)]
+pub struct OpenAPIObjectBuilder {
+    openapi: Option<String>,
+    title: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    servers: Vec<ServerObject>,
+    operations: Vec<(String, Method, OperationObjectBuilder)>,
+    components: ComponentsObject,
+    tags: Vec<TagObject>,
+}
+
+impl OpenAPIObjectBuilder {
+    pub fn openapi(mut self, openapi: impl Into<String>) -> Self {
+        self.openapi = Some(openapi.into());
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(ServerObject {
+            url: url.into(),
+            description: None,
+            variables: None,
+            extensions: Default::default(),
+        });
+        self
+    }
+
+    pub fn tag(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.tags.push(TagObject {
+            name: name.into(),
+            description: Some(description.into()),
+            external_docs: None,
+            extensions: Default::default(),
+        });
+        self
+    }
+
+    /// Adds `operation` on `path`, replacing the one with the same method if any.
+    pub fn operation(
+        mut self,
+        method: Method,
+        path: impl Into<String>,
+        operation: OperationObjectBuilder,
+    ) -> Self {
+        self.operations.push((path.into(), method, operation));
+        self
+    }
+
+    pub fn get(self, path: impl Into<String>, operation: OperationObjectBuilder) -> Self {
+        self.operation(Method::GET, path, operation)
+    }
+
+    pub fn put(self, path: impl Into<String>, operation: OperationObjectBuilder) -> Self {
+        self.operation(Method::PUT, path, operation)
+    }
+
+    pub fn post(self, path: impl Into<String>, operation: OperationObjectBuilder) -> Self {
+        self.operation(Method::POST, path, operation)
+    }
+
+    pub fn delete(self, path: impl Into<String>, operation: OperationObjectBuilder) -> Self {
+        self.operation(Method::DELETE, path, operation)
+    }
+
+    pub fn patch(self, path: impl Into<String>, operation: OperationObjectBuilder) -> Self {
+        self.operation(Method::PATCH, path, operation)
+    }
+
+    /// Adds the component schema `name`, replacing the one with the same name if any.
+    pub fn schema(mut self, name: impl Into<String>, schema: SchemaObject) -> Self {
+        self.components
+            .schemas
+            .get_or_insert_with(Default::default)
+            .insert(name.into(), ReferenceObjectOr::Object(schema));
+        self
+    }
+
+    /// Adds the component schemas of `T` and of the types it is made of.
+    pub fn register<T: ToSchema + ?Sized>(mut self) -> Self {
+        self.components.register::<T>();
+        self
+    }
+
+    pub fn build(self) -> Result<OpenAPIObject, BuildError> {
+        let info = InfoObject {
+            title: self.title.ok_or(BuildError::MissingField("info.title"))?,
+            description: self.description,
+            terms_of_service: None,
+            contact: None,
+            license: None,
+            version: self
+                .version
+                .ok_or(BuildError::MissingField("info.version"))?,
+            extensions: Default::default(),
+        };
+
+        let mut paths: IndexMap<String, PathsItemObject> = IndexMap::new();
+        for (path, method, operation) in self.operations {
+            let path_item = paths.entry(path).or_default();
+            let slot = path_item
+                .operation_entry(&method)
+                .ok_or(BuildError::UnsupportedMethod(method))?;
+            *slot = Some(operation.build()?);
+        }
+
+        Ok(OpenAPIObject {
+            openapi: self.openapi.unwrap_or_else(|| "3.0.3".to_string()),
+            info,
+            servers: self.servers,
+            paths: paths
+                .into_iter()
+                .map(|(path, path_item)| (path, ReferenceObjectOr::Object(path_item)))
+                .collect(),
+            components: if self.components.is_empty() {
+                None
+            } else {
+                Some(self.components)
+            },
+            security: None,
+            tags: if self.tags.is_empty() {
+                None
+            } else {
+                Some(self.tags)
+            },
+            external_docs: None,
+            extensions: Default::default(),
+        })
+    }
+}
+
+impl OperationObject {
+    pub fn builder() -> OperationObjectBuilder {
+        OperationObjectBuilder::default()
+    }
+}
+
+/// Builds an operation, which must have at least one response.
+#[derive(Debug, Default)]
+pub struct OperationObjectBuilder {
+    operation: OperationObject,
+    parameters: Vec<ParameterObjectBuilder>,
+    responses: Vec<(u16, ResponseObject)>,
+}
+
+impl OperationObjectBuilder {
+    pub fn operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation.operation_id = Some(operation_id.into());
+        self
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.operation.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.operation.description = Some(description.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.operation
+            .tags
+            .get_or_insert_with(Vec::new)
+            .push(tag.into());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.operation.deprecated = true;
+        self
+    }
+
+    pub fn parameter(mut self, parameter: ParameterObjectBuilder) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Sets the request body to JSON with the given schema.
+    pub fn json_body(
+        mut self,
+        schema: impl Into<ReferenceObjectOr<SchemaObject>>,
+        required: bool,
+    ) -> Self {
+        self.operation.request_body = Some(ReferenceObjectOr::Object(RequestBodyObject {
+            description: None,
+            content: json_content(schema.into()),
+            required,
+        }));
+        self
+    }
+
+    /// Adds the response with `status`, holding JSON with the given schema.
+    pub fn response(
+        self,
+        status: u16,
+        description: impl Into<String>,
+        schema: impl Into<ReferenceObjectOr<SchemaObject>>,
+    ) -> Self {
+        self.response_with_content(status, description, Some(json_content(schema.into())))
+    }
+
+    /// Adds the response with `status`, which has no content.
+    pub fn empty_response(self, status: u16, description: impl Into<String>) -> Self {
+        self.response_with_content(status, description, None)
+    }
+
+    fn response_with_content(
+        mut self,
+        status: u16,
+        description: impl Into<String>,
+        content: Option<ContentMap>,
+    ) -> Self {
+        self.responses.push((
+            status,
+            ResponseObject {
+                description: description.into(),
+                headers: None,
+                content,
+                links: None,
+            },
+        ));
+        self
+    }
+
+    pub fn build(self) -> Result<OperationObject, BuildError> {
+        let mut operation = self.operation;
+
+        if !self.parameters.is_empty() {
+            let parameters = self
+                .parameters
+                .into_iter()
+                .map(|parameter| parameter.build().map(ReferenceObjectOr::Object))
+                .collect::<Result<_, _>>()?;
+            operation.parameters = Some(parameters);
+        }
+
+        if self.responses.is_empty() {
+            return Err(BuildError::MissingField("responses"));
+        }
+        for (status, response) in self.responses {
+            let status = StatusCode::from_u16(status)
+                .ok()
+                .filter(|status| (100..=599).contains(&status.as_u16()))
+                .ok_or(BuildError::InvalidStatus(status))?;
+            operation.responses.responses.insert(
+                ResponseKey::Status(status),
+                ReferenceObjectOr::Object(response),
+            );
+        }
+
+        Ok(operation)
+    }
+}
+
+impl ParameterObject {
+    /// A path parameter, which is always required.
+    pub fn path(name: impl Into<String>) -> ParameterObjectBuilder {
+        ParameterObjectBuilder::new(ParameterLocation::Path, name).required()
+    }
+
+    pub fn query(name: impl Into<String>) -> ParameterObjectBuilder {
+        ParameterObjectBuilder::new(ParameterLocation::Query, name)
+    }
+
+    pub fn header(name: impl Into<String>) -> ParameterObjectBuilder {
+        ParameterObjectBuilder::new(ParameterLocation::Header, name)
+    }
+
+    pub fn cookie(name: impl Into<String>) -> ParameterObjectBuilder {
+        ParameterObjectBuilder::new(ParameterLocation::Cookie, name)
+    }
+}
+
+/// Builds a parameter, which must have a schema.
+#[derive(Debug)]
+pub struct ParameterObjectBuilder {
+    location: ParameterLocation,
+    name: String,
+    description: Option<String>,
+    required: bool,
+    deprecated: bool,
+    schema: Option<ReferenceObjectOr<SchemaObject>>,
+}
+
+impl ParameterObjectBuilder {
+    pub(crate) fn new(location: ParameterLocation, name: impl Into<String>) -> Self {
+        Self {
+            location,
+            name: name.into(),
+            description: None,
+            required: false,
+            deprecated: false,
+            schema: None,
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    pub fn schema(mut self, schema: impl Into<ReferenceObjectOr<SchemaObject>>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    pub fn build(self) -> Result<ParameterObject, BuildError> {
+        let fixed_fields = ParameterObjectFixedFields {
+            name: self.name,
+            description: self.description,
+            required: self.required,
+            deprecated: self.deprecated,
+            explode: None,
+            schema: SchemaOrContentObject::Schema(Box::new(
+                self.schema.ok_or(BuildError::MissingField("schema"))?,
+            )),
+            example: None,
+            examples: None,
+        };
+
+        Ok(match self.location {
+            ParameterLocation::Path => ParameterObject::Path { fixed_fields },
+            ParameterLocation::Query => ParameterObject::Query {
+                fixed_fields,
+                allow_empty_value: false,
+                allow_reserved: false,
+            },
+            ParameterLocation::Header => ParameterObject::Header { fixed_fields },
+            ParameterLocation::Cookie => ParameterObject::Cookie { fixed_fields },
+        })
+    }
+}
+
+/// Schemas are built by chaining these methods on one of their constructors, e.g. `SchemaObject::object().required_property("id", SchemaObject::integer())`.
+impl SchemaObject {
+    fn typed(schema_type: SchemaType) -> Self {
+        SchemaObject {
+            schema_type: Some(schema_type),
+            ..Default::default()
+        }
+    }
+
+    pub fn object() -> Self {
+        Self::typed(SchemaType::Object)
+    }
+
+    pub fn string() -> Self {
+        Self::typed(SchemaType::String)
+    }
+
+    pub fn integer() -> Self {
+        Self::typed(SchemaType::Integer)
+    }
+
+    pub fn number() -> Self {
+        Self::typed(SchemaType::Number)
+    }
+
+    pub fn boolean() -> Self {
+        Self::typed(SchemaType::Boolean)
+    }
+
+    pub fn array(items: impl Into<ReferenceObjectOr<SchemaObject>>) -> Self {
+        SchemaObject {
+            items: Some(Box::new(items.into())),
+            ..Self::typed(SchemaType::Array)
+        }
+    }
+
+    /// A reference to the component schema `name`.
+    pub fn reference(name: impl Into<String>) -> ReferenceObjectOr<SchemaObject> {
+        ReferenceObjectOr::component(name)
+    }
+
+    pub fn property(
+        mut self,
+        name: impl Into<String>,
+        schema: impl Into<ReferenceObjectOr<SchemaObject>>,
+    ) -> Self {
+        self.properties
+            .get_or_insert_with(Default::default)
+            .insert(name.into(), schema.into());
+        self
+    }
+
+    pub fn required_property(
+        self,
+        name: impl Into<String>,
+        schema: impl Into<ReferenceObjectOr<SchemaObject>>,
+    ) -> Self {
+        let name = name.into();
+        let mut schema = self.property(name.clone(), schema);
+        let required = schema.required.get_or_insert_with(Vec::new);
+        if !required.contains(&name) {
+            required.push(name);
+        }
+        schema
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn enumeration(mut self, values: impl IntoIterator<Item = Value>) -> Self {
+        self.enumeration = Some(values.into_iter().collect());
+        self
+    }
+
+    pub fn example(mut self, example: Value) -> Self {
+        self.example = Some(example);
+        self
+    }
+
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+}
@@ -0,0 +1,448 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::collections::HashMap;
+
+use super::{
+    indent, literal,
+    operation::{operations, Operation},
+    Types,
+};
+use crate::v3::{OpenAPIObject, ParameterLocation};
+
+/// The types every client defines.
+const CLIENT_TYPES: &[&str] = &["Client", "ClientError", "ResponseFuture", "Transport"];
+
+/// The variables of the methods, which arguments can't shadow.
+const VARIABLES: &[&str] = &["cookies", "query", "request", "response", "url", "value"];
+
+/// Everything but the unreserved characters of RFC 3986, like the generated `encode`.
+const ENCODED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const TRANSPORT: &str = r#"/// The future returned by [`Transport::send`].
+pub type ResponseFuture<'t, E> = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<http::Response<Vec<u8>>, E>> + Send + 't>,
+>;
+
+/// Sends the requests of a [`Client`], e.g. with `hyper` or `reqwest`.
+pub trait Transport {
+    type Error;
+
+    fn send(&self, request: http::Request<Vec<u8>>) -> ResponseFuture<'_, Self::Error>;
+}
+
+/// The error returned by the methods of [`Client`].
+#[derive(Debug)]
+pub enum ClientError<E> {
+    /// The transport failed to send the request.
+    Transport(E),
+    /// The request could not be built, e.g. because a header value is invalid.
+    Http(http::Error),
+    /// A body could not be serialized or deserialized.
+    Json(serde_json::Error),
+    /// The server answered with a status code the operation doesn't declare.
+    UnexpectedStatus(http::Response<Vec<u8>>),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ClientError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Transport(error) => write!(f, "failed to send the request: {}", error),
+            ClientError::Http(error) => write!(f, "invalid request: {}", error),
+            ClientError::Json(error) => write!(f, "invalid JSON body: {}", error),
+            ClientError::UnexpectedStatus(response) => {
+                write!(f, "unexpected status {}", response.status())
+            }
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for ClientError<E> {}
+
+impl<E> From<http::Error> for ClientError<E> {
+    fn from(error: http::Error) -> Self {
+        ClientError::Http(error)
+    }
+}
+
+impl<E> From<serde_json::Error> for ClientError<E> {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Json(error)
+    }
+}
+"#;
+
+const CLIENT: &str = r#"/// A client for the operations of the API.
+#[derive(Debug, Clone)]
+pub struct Client<T> {
+    base_url: String,
+    transport: T,
+}
+"#;
+
+const PARAMETER_VALUES: &str = r#"/// The values of a parameter: the items of an array, or the value itself.
+fn parameter_values<P: serde::Serialize>(value: &P) -> Result<Vec<String>, serde_json::Error> {
+    let values = match serde_json::to_value(value)? {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            serde_json::Value::String(value) => value,
+            value => value.to_string(),
+        })
+        .collect())
+}
+"#;
+
+const ENCODE: &str = r#"/// Percent-encodes everything but the unreserved characters of RFC 3986.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+"#;
+
+/// Generates a typed client for the operations of a document, along with the types of its component schemas.
+///
+/// `Client` has an async method per operation, named after its `operationId`, or its method and path if it has none, taking its path, query, header and cookie parameters and its request body as arguments.
+/// Each method returns an enum with a variant per response of the operation, holding the body deserialized if it is JSON and as bytes otherwise. Variants of ranges like `2XX` and of the `default` response hold the status code too.
+/// Requests are `http::Request`s sent by an implementation of the generated `Transport` trait, so that any HTTP library can be used.
+#[derive(Debug)]
+pub struct ClientGenerator<'d> {
+    document: &'d OpenAPIObject,
+    derives: Vec<String>,
+}
+
+impl<'d> ClientGenerator<'d> {
+    pub fn new(document: &'d OpenAPIObject) -> Self {
+        Self {
+            document,
+            derives: Vec::new(),
+        }
+    }
+
+    /// Adds `derives` to every type generated for a schema, like [`TypesGenerator::with_derives`](super::TypesGenerator::with_derives).
+    pub fn with_derives<T: Into<String>>(mut self, derives: impl IntoIterator<Item = T>) -> Self {
+        self.derives.extend(derives.into_iter().map(Into::into));
+        self
+    }
+
+    /// Generates the types followed by the client.
+    pub fn generate(&self) -> String {
+        let mut types = Types::new(self.document, &self.derives, CLIENT_TYPES);
+        types.define_components();
+        let operations = operations(self.document, &mut types, VARIABLES);
+
+        let mut items = vec![types.finish(), TRANSPORT.to_string(), CLIENT.to_string()];
+
+        let mut implementation = String::from("impl<T: Transport> Client<T> {\n");
+        implementation.push_str(
+            "    /// Creates a client sending requests to `base_url`, e.g. `https://example.com/v1`, with `transport`.\n",
+        );
+        implementation.push_str(
+            "    pub fn new(base_url: impl Into<String>, transport: T) -> Self {\n        let mut base_url = base_url.into();\n        while base_url.ends_with('/') {\n            base_url.pop();\n        }\n\n        Self {\n            base_url,\n            transport,\n        }\n    }\n",
+        );
+        for operation in &operations {
+            implementation.push('\n');
+            implementation.push_str(&method(operation));
+        }
+        implementation.push_str("}\n");
+        items.push(implementation);
+
+        for operation in &operations {
+            items.push(operation.responses_enum(&format!("Client::{}", operation.function)));
+        }
+
+        let parameters = || {
+            operations
+                .iter()
+                .flat_map(|operation| &operation.parameters)
+        };
+        if parameters().next().is_some() {
+            items.push(PARAMETER_VALUES.to_string());
+        }
+        if parameters().any(|parameter| {
+            matches!(
+                parameter.object.location(),
+                ParameterLocation::Path | ParameterLocation::Query | ParameterLocation::Cookie
+            )
+        }) {
+            items.push(ENCODE.to_string());
+        }
+
+        items.retain(|item| !item.is_empty());
+        items.join("\n")
+    }
+}
+
+/// The method performing `operation`.
+fn method(operation: &Operation) -> String {
+    let mut signature = vec!["&self".to_string()];
+    let mut path = HashMap::new();
+    let mut query = String::new();
+    let mut headers = String::new();
+    let mut cookies = String::new();
+    let mut mutable_request = false;
+
+    for parameter in &operation.parameters {
+        let name = parameter.object.name();
+        let argument = &parameter.argument;
+        let required = parameter.object.is_required();
+
+        if required {
+            signature.push(format!("{}: {}", argument, parameter.type_name));
+        } else {
+            signature.push(format!("{}: Option<{}>", argument, parameter.type_name));
+        }
+        let value = if required {
+            format!("&{}", argument)
+        } else {
+            argument.clone()
+        };
+
+        match parameter.object.location() {
+            ParameterLocation::Path => {
+                path.insert(
+                    name,
+                    format!("encode(&parameter_values({})?.join(\",\"))", value),
+                );
+            }
+            ParameterLocation::Query => {
+                let key = utf8_percent_encode(name, ENCODED).to_string();
+                let statement = format!(
+                    "for value in parameter_values({})? {{\n    query.push(format!({}, encode(&value)));\n}}\n",
+                    value,
+                    literal(&format!("{}={{}}", key)),
+                );
+                query.push_str(&optional(required, argument, &statement));
+            }
+            ParameterLocation::Header if required => {
+                headers.push_str(&format!(
+                    "let request = request.header({}, parameter_values({})?.join(\",\"));\n",
+                    literal(name),
+                    value,
+                ));
+            }
+            ParameterLocation::Header => {
+                mutable_request = true;
+                let statement = format!(
+                    "request = request.header({}, parameter_values({})?.join(\",\"));\n",
+                    literal(name),
+                    value,
+                );
+                headers.push_str(&optional(required, argument, &statement));
+            }
+            ParameterLocation::Cookie => {
+                // Cookie values can't hold spaces, commas or semicolons
+                let key = utf8_percent_encode(name, ENCODED).to_string();
+                let statement = format!(
+                    "let values: Vec<String> = parameter_values({})?\n    .iter()\n    .map(|value| encode(value))\n    .collect();\ncookies.push(format!({}, values.join(\",\")));\n",
+                    value,
+                    literal(&format!("{}={{}}", key)),
+                );
+                cookies.push_str(&optional(required, argument, &statement));
+            }
+        }
+    }
+
+    let mut output = String::new();
+    if !query.is_empty() {
+        output.push_str("let mut query = Vec::new();\n");
+        output.push_str(&query);
+    }
+    output.push_str(&format!(
+        "let {}url = {};\n",
+        if query.is_empty() { "" } else { "mut " },
+        url(operation.template, &path),
+    ));
+    if !query.is_empty() {
+        output.push_str(
+            "if !query.is_empty() {\n    url.push('?');\n    url.push_str(&query.join(\"&\"));\n}\n",
+        );
+    }
+
+    output.push_str(&format!(
+        "let {}request = http::Request::builder()\n    .method(http::Method::{})\n    .uri(url);\n",
+        if mutable_request || !cookies.is_empty() {
+            "mut "
+        } else {
+            ""
+        },
+        operation.method.as_str(),
+    ));
+    output.push_str(&headers);
+    if !cookies.is_empty() {
+        output.push_str("let mut cookies = Vec::new();\n");
+        output.push_str(&cookies);
+        output.push_str(
+            "if !cookies.is_empty() {\n    request = request.header(\"cookie\", cookies.join(\"; \"));\n}\n",
+        );
+    }
+    output.push_str(&request_body(operation, &mut signature));
+
+    output.push_str(
+        "let response = self\n    .transport\n    .send(request)\n    .await\n    .map_err(ClientError::Transport)?;\n",
+    );
+    output.push_str(&responses(operation));
+
+    let mut method = operation.attributes(signature.len());
+    method.push_str(&format!("    pub async fn {}(", operation.function));
+    if signature.len() == 1 {
+        method.push_str("&self");
+    } else {
+        method.push('\n');
+        for argument in &signature {
+            method.push_str(&format!("        {},\n", argument));
+        }
+        method.push_str("    ");
+    }
+    method.push_str(&format!(
+        ") -> Result<{}, ClientError<T::Error>> {{\n",
+        operation.responses_type
+    ));
+    method.push_str(&indent(&output, "        "));
+    method.push_str("    }\n");
+    method
+}
+
+/// The statement building the request with its body, adding the body to the arguments.
+fn request_body(operation: &Operation, signature: &mut Vec<String>) -> String {
+    let body = match &operation.body {
+        Some(body) => body,
+        None => return "let request = request.body(Vec::new())?;\n".to_string(),
+    };
+
+    let argument = &body.argument;
+    let (type_name, value) = if body.content.json {
+        (
+            format!("&{}", body.content.type_name),
+            format!("serde_json::to_vec({})?", argument),
+        )
+    } else {
+        (body.content.type_name.clone(), argument.clone())
+    };
+    let with_body = format!(
+        "request\n    .header(\"content-type\", {})\n    .body({})?",
+        literal(&body.content.media_type),
+        value,
+    );
+
+    if body.required {
+        signature.push(format!("{}: {}", argument, type_name));
+        format!("let request = {};\n", with_body)
+    } else {
+        signature.push(format!("{}: Option<{}>", argument, type_name));
+        format!(
+            "let request = match {} {{\n    Some({}) => {},\n    None => request.body(Vec::new())?,\n}};\n",
+            argument,
+            argument,
+            indent(&with_body, "    ").trim(),
+        )
+    }
+}
+
+/// The expression matching the response to the variants of the enum of the responses.
+fn responses(operation: &Operation) -> String {
+    let mut arms = String::new();
+    let mut has_default = false;
+
+    for response in operation.responses_by_precedence() {
+        let mut values = Vec::new();
+        if response.has_status() {
+            values.push("response.status()");
+        }
+        match &response.content {
+            Some(content) if content.json => {
+                values.push("serde_json::from_slice(response.body())?")
+            }
+            Some(_) => values.push("response.into_body()"),
+            None => {}
+        }
+
+        let variant = format!("{}::{}", operation.responses_type, response.variant);
+        if values.is_empty() {
+            arms.push_str(&format!("{} => Ok({}),\n", response.pattern(), variant));
+        } else {
+            arms.push_str(&format!(
+                "{} => Ok({}({})),\n",
+                response.pattern(),
+                variant,
+                values.join(", ")
+            ));
+        }
+        has_default |= response.key.is_none();
+    }
+    if !has_default {
+        arms.push_str("_ => Err(ClientError::UnexpectedStatus(response)),\n");
+    }
+
+    format!(
+        "match response.status().as_u16() {{\n{}}}\n",
+        indent(&arms, "    "),
+    )
+}
+
+/// Wraps `statement` in an `if let` binding the value of `argument` if it is optional.
+fn optional(required: bool, argument: &str, statement: &str) -> String {
+    if required {
+        statement.to_string()
+    } else {
+        format!(
+            "if let Some({}) = &{} {{\n{}}}\n",
+            argument,
+            argument,
+            indent(statement, "    ")
+        )
+    }
+}
+
+/// The expression formatting the URL of `template`, with the path parameters in `path`.
+fn url(template: &str, path: &HashMap<&str, String>) -> String {
+    let mut format = "{}".to_string();
+    let mut arguments = vec!["self.base_url".to_string()];
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        format.push_str(&escape(&rest[..start]));
+        let after = &rest[start + 1..];
+
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match path.get(name) {
+                    Some(value) => {
+                        format.push_str("{}");
+                        arguments.push(value.clone());
+                    }
+                    None => format.push_str(&escape(&rest[start..start + end + 2])),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                format.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    format.push_str(&escape(rest));
+
+    format!("format!({}, {})", literal(&format), arguments.join(", "))
+}
+
+/// Escapes the braces of `value` for a format string.
+fn escape(value: &str) -> String {
+    value.replace('{', "{{").replace('}', "}}")
+}
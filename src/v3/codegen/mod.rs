@@ -0,0 +1,79 @@
+//! Generation of Rust source code from a document, meant to be run from a build script and [included](include) in a crate depending on `serde` and `serde_json`, and `http` for clients and servers.
+
+mod client;
+mod operation;
+mod server;
+mod types;
+
+pub use client::*;
+pub use server::*;
+pub use types::*;
+
+use crate::utils::{pascal_case, snake_case};
+
+/// Names used by the generated code, which types can't shadow.
+const RESERVED_TYPES: &[&str] = &["Box", "HashMap", "Option", "Result", "String", "Vec"];
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// A Rust type name for `name`, which may not be unique.
+fn type_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    match name.chars().next() {
+        None => "Unnamed".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("_{}", name),
+        Some(_) => name,
+    }
+}
+
+/// A Rust field, variable or function name for `name`, which may not be unique.
+fn field_name(name: &str) -> String {
+    let name = snake_case(name);
+
+    match name.as_str() {
+        "" => "value".to_string(),
+        "crate" | "self" | "super" => format!("{}_", name),
+        _ if KEYWORDS.contains(&name.as_str()) => format!("r#{}", name),
+        _ if name.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", name),
+        _ => name,
+    }
+}
+
+/// Formats `docs` as doc comments, each line starting with `indent`.
+fn doc_comments(docs: Option<&str>, indent: &str) -> String {
+    docs.into_iter()
+        .flat_map(str::lines)
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}///\n", indent)
+            } else {
+                format!("{}/// {}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+/// A string literal for `value`.
+fn literal(value: &str) -> String {
+    format!("{:?}", value)
+}
+
+/// Indents every non-empty line of `code` with `indent`.
+fn indent(code: &str, indent: &str) -> String {
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("{}{}\n", indent, line)
+            }
+        })
+        .collect()
+}
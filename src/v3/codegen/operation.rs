@@ -0,0 +1,331 @@
+use http::Method;
+use indexmap::IndexMap;
+
+use super::{doc_comments, field_name, type_name, Fields, Types};
+use crate::utils::snake_case;
+use crate::v3::{
+    ContentMap, MediaType, OpenAPIObject, OperationObject, ParameterLocation, ParameterObject,
+    PathsItemObject, ResponseKey, ResponseObject, SchemaOrContentObject, StatusRange,
+};
+
+/// An operation along with the Rust names and types of its parts.
+pub(super) struct Operation<'d> {
+    pub(super) template: &'d str,
+    pub(super) method: Method,
+    pub(super) object: &'d OperationObject,
+    /// The name of the method performing or handling the operation.
+    pub(super) function: String,
+    /// The name of the enum of the responses.
+    pub(super) responses_type: String,
+    pub(super) parameters: Vec<Parameter<'d>>,
+    pub(super) body: Option<Body>,
+    pub(super) responses: Vec<Response<'d>>,
+}
+
+pub(super) struct Parameter<'d> {
+    pub(super) object: &'d ParameterObject,
+    pub(super) argument: String,
+    pub(super) type_name: String,
+}
+
+pub(super) struct Body {
+    pub(super) argument: String,
+    pub(super) content: Content,
+    pub(super) required: bool,
+}
+
+/// The content of a request or response in its preferred media type: the first JSON one if there is one, and the first one otherwise.
+pub(super) struct Content {
+    /// The type of the deserialized content if it is JSON, and `Vec<u8>` otherwise.
+    pub(super) type_name: String,
+    pub(super) media_type: String,
+    pub(super) json: bool,
+}
+
+pub(super) struct Response<'d> {
+    /// The key of the response, or `None` for the `default` one.
+    pub(super) key: Option<ResponseKey>,
+    pub(super) variant: String,
+    pub(super) description: &'d str,
+    pub(super) content: Option<Content>,
+}
+
+impl Response<'_> {
+    /// Whether the variant holds the status code, as it isn't implied by the key.
+    pub(super) fn has_status(&self) -> bool {
+        !matches!(self.key, Some(ResponseKey::Status(_)))
+    }
+
+    /// The pattern matching the status codes of the response as a `u16`.
+    pub(super) fn pattern(&self) -> String {
+        match self.key {
+            Some(ResponseKey::Status(status)) => status.as_u16().to_string(),
+            Some(ResponseKey::Range(range)) => match range {
+                StatusRange::Informational => "100..=199",
+                StatusRange::Success => "200..=299",
+                StatusRange::Redirection => "300..=399",
+                StatusRange::ClientError => "400..=499",
+                StatusRange::ServerError => "500..=599",
+            }
+            .to_string(),
+            None => "_".to_string(),
+        }
+    }
+}
+
+impl<'d> Operation<'d> {
+    /// The responses in the order they must be matched: exact status codes, then ranges, then `default`.
+    pub(super) fn responses_by_precedence(&self) -> Vec<&Response<'d>> {
+        let mut responses: Vec<&Response> = self.responses.iter().collect();
+        responses.sort_by_key(|response| match response.key {
+            Some(ResponseKey::Status(_)) => 0,
+            Some(ResponseKey::Range(_)) => 1,
+            None => 2,
+        });
+        responses
+    }
+
+    /// The doc comments and attributes of the method of the operation, which takes `arguments`.
+    pub(super) fn attributes(&self, arguments: usize) -> String {
+        let docs = [
+            self.object.summary.as_deref(),
+            self.object.description.as_deref(),
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+        let mut attributes = String::new();
+        if !docs.is_empty() {
+            attributes.push_str(&doc_comments(Some(&docs), "    "));
+        }
+        if self.object.deprecated {
+            attributes.push_str("    #[deprecated]\n");
+        }
+        if arguments > 7 {
+            attributes.push_str("    #[allow(clippy::too_many_arguments)]\n");
+        }
+        attributes
+    }
+
+    /// The enum of the responses, documented as returned by `method`, e.g. `Client::list_pets`.
+    pub(super) fn responses_enum(&self, method: &str) -> String {
+        let mut variants = String::new();
+        for response in &self.responses {
+            variants.push_str(&doc_comments(Some(response.description), "    "));
+
+            let fields: Vec<&str> = response
+                .has_status()
+                .then_some("http::StatusCode")
+                .into_iter()
+                .chain(
+                    response
+                        .content
+                        .as_ref()
+                        .map(|content| content.type_name.as_str()),
+                )
+                .collect();
+            if fields.is_empty() {
+                variants.push_str(&format!("    {},\n", response.variant));
+            } else {
+                variants.push_str(&format!(
+                    "    {}({}),\n",
+                    response.variant,
+                    fields.join(", ")
+                ));
+            }
+        }
+
+        format!(
+            "/// The responses of [`{}`].\n#[derive(Debug)]\npub enum {} {{\n{}}}\n",
+            method, self.responses_type, variants,
+        )
+    }
+}
+
+/// Collects the operations of `document`, defining the types they need. Arguments can't be named any of `reserved`.
+pub(super) fn operations<'d>(
+    document: &'d OpenAPIObject,
+    types: &mut Types<'d>,
+    reserved: &[&str],
+) -> Vec<Operation<'d>> {
+    let mut functions = Fields::default();
+    let mut operations = Vec::new();
+
+    for (template, path_item) in &document.paths {
+        let path_item = match path_item.as_object() {
+            Some(path_item) => path_item,
+            None => continue,
+        };
+
+        for (method, object) in path_item.operations() {
+            let name = match &object.operation_id {
+                Some(operation_id) => operation_id.clone(),
+                None => format!("{} {}", method.as_str().to_lowercase(), template),
+            };
+            let prefix = type_name(&name);
+
+            let mut arguments = Fields::default();
+            for name in reserved {
+                arguments.unique(name.to_string());
+            }
+
+            let mut parameters = Vec::new();
+            for object in effective_parameters(document, path_item, object) {
+                let fixed_fields = object.fixed_fields();
+                let hint = format!("{}{}", prefix, type_name(&fixed_fields.name));
+                parameters.push(Parameter {
+                    object,
+                    argument: arguments.unique(field_name(&fixed_fields.name)),
+                    type_name: parameter_type(types, &fixed_fields.schema, &hint),
+                });
+            }
+
+            let body = object
+                .request_body
+                .as_ref()
+                .and_then(|request_body| request_body.resolve(document).ok())
+                .and_then(|request_body| {
+                    let content =
+                        content(types, &request_body.content, &format!("{}Request", prefix))?;
+                    Some(Body {
+                        argument: arguments.unique("body".to_string()),
+                        content,
+                        required: request_body.required,
+                    })
+                });
+
+            let responses_type = types.unique(format!("{}Response", prefix));
+            let mut variants = Fields::default();
+            let mut responses = Vec::new();
+            let declared = object
+                .responses
+                .responses
+                .iter()
+                .map(|(key, response)| (Some(*key), response))
+                .chain(
+                    object
+                        .responses
+                        .default
+                        .iter()
+                        .map(|response| (None, response)),
+                );
+            for (key, response) in declared {
+                if let Ok(response) = response.resolve(document) {
+                    let variant = variants.unique(variant_name(key));
+                    let hint = format!("{}{}Body", prefix, variant);
+                    responses.push(Response {
+                        key,
+                        content: response_content(types, response, &hint),
+                        variant,
+                        description: &response.description,
+                    });
+                }
+            }
+
+            operations.push(Operation {
+                template,
+                method,
+                object,
+                function: functions.unique(field_name(&name)),
+                responses_type,
+                parameters,
+                body,
+                responses,
+            });
+        }
+    }
+
+    operations
+}
+
+/// The parameters of the path item and operation, the latter overriding the former.
+fn effective_parameters<'d>(
+    document: &'d OpenAPIObject,
+    path_item: &'d PathsItemObject,
+    operation: &'d OperationObject,
+) -> Vec<&'d ParameterObject> {
+    let mut parameters: IndexMap<(ParameterLocation, &str), &ParameterObject> = IndexMap::new();
+
+    let declared = path_item
+        .parameters
+        .iter()
+        .flatten()
+        .chain(operation.parameters.iter().flatten());
+    for parameter in declared {
+        if let Ok(parameter) = parameter.resolve(document) {
+            parameters.insert((parameter.location(), parameter.name()), parameter);
+        }
+    }
+
+    parameters
+        .into_iter()
+        .map(|(_, parameter)| parameter)
+        .collect()
+}
+
+fn parameter_type(types: &mut Types, schema: &SchemaOrContentObject, hint: &str) -> String {
+    let schema = match schema {
+        SchemaOrContentObject::Schema(schema) => Some(&**schema),
+        SchemaOrContentObject::Content(content) => content
+            .values()
+            .next()
+            .and_then(|media_type| media_type.schema.as_ref()),
+    };
+
+    match schema {
+        Some(schema) => types.type_of(schema, hint, None, true),
+        None => "serde_json::Value".to_string(),
+    }
+}
+
+fn response_content(types: &mut Types, response: &ResponseObject, hint: &str) -> Option<Content> {
+    content(types, response.content.as_ref()?, hint)
+}
+
+fn content(types: &mut Types, content: &ContentMap, hint: &str) -> Option<Content> {
+    let (media_type, json) = preferred_media_type(content)?;
+
+    let type_name = if json {
+        match &content[media_type].schema {
+            Some(schema) => types.type_of(schema, hint, None, true),
+            None => "serde_json::Value".to_string(),
+        }
+    } else {
+        "Vec<u8>".to_string()
+    };
+
+    Some(Content {
+        type_name,
+        media_type: media_type.to_string(),
+        json,
+    })
+}
+
+fn preferred_media_type(content: &ContentMap) -> Option<(&MediaType, bool)> {
+    content
+        .keys()
+        .find(|media_type| media_type.is_json())
+        .map(|media_type| (media_type, true))
+        .or_else(|| content.keys().next().map(|media_type| (media_type, false)))
+}
+
+fn variant_name(key: Option<ResponseKey>) -> String {
+    match key {
+        Some(ResponseKey::Status(status)) => match status.canonical_reason() {
+            Some(reason) => type_name(&snake_case(reason)),
+            None => format!("Status{}", status.as_u16()),
+        },
+        Some(ResponseKey::Range(range)) => match range {
+            StatusRange::Informational => "Informational",
+            StatusRange::Success => "Success",
+            StatusRange::Redirection => "Redirection",
+            StatusRange::ClientError => "ClientError",
+            StatusRange::ServerError => "ServerError",
+        }
+        .to_string(),
+        None => "Default".to_string(),
+    }
+}
@@ -0,0 +1,567 @@
+use std::collections::HashMap;
+
+use super::{
+    field_name, indent, literal,
+    operation::{operations, Operation},
+    Fields, Types,
+};
+use crate::v3::{OpenAPIObject, ParameterLocation};
+
+/// The types every server defines.
+const SERVER_TYPES: &[&str] = &["HandlerFuture", "Server"];
+
+/// The variables of `handle` and the handlers, which arguments can't shadow.
+const VARIABLES: &[&str] = &["request", "result", "segments", "server"];
+
+const SERVER: &str = r#"/// The future returned by the methods of [`Server`].
+pub type HandlerFuture<'s, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 's>>;
+"#;
+
+const DECODE: &str = r#"/// Percent-decodes `value`.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+"#;
+
+const RESPONSE: &str = r#"/// A response with `status` and `body`, of `content_type` if there is one.
+fn response(status: u16, content_type: Option<&'static str>, body: Vec<u8>) -> http::Response<Vec<u8>> {
+    let mut response = http::Response::new(body);
+    *response.status_mut() =
+        http::StatusCode::from_u16(status).unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = content_type {
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(content_type),
+        );
+    }
+    response
+}
+"#;
+
+const JSON_RESPONSE: &str = r#"/// A response with `status` and `body` serialized to JSON, or `500 Internal Server Error` if it can't be.
+fn json_response<T: serde::Serialize>(
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) -> http::Response<Vec<u8>> {
+    match serde_json::to_vec(body) {
+        Ok(body) => response(status, Some(content_type), body),
+        Err(error) => response(500, Some("text/plain"), error.to_string().into_bytes()),
+    }
+}
+"#;
+
+const QUERY_VALUES: &str = r#"/// The values of the query parameter `name`.
+fn query_values(request: &http::Request<Vec<u8>>, name: &str) -> Vec<String> {
+    request
+        .uri()
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .filter(|(key, _)| decode(&key.replace('+', " ")) == name)
+        .map(|(_, value)| decode(&value.replace('+', " ")))
+        .collect()
+}
+"#;
+
+const HEADER_VALUES: &str = r#"/// The values of the header `name`.
+fn header_values(request: &http::Request<Vec<u8>>, name: &str) -> Vec<String> {
+    request
+        .headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .map(str::to_string)
+        .collect()
+}
+"#;
+
+const COOKIE_VALUES: &str = r#"/// The values of the cookie `name`.
+fn cookie_values(request: &http::Request<Vec<u8>>, name: &str) -> Vec<String> {
+    request
+        .headers()
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .filter(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .collect()
+}
+"#;
+
+const PARAMETER: &str = r#"/// Deserializes the required parameter `name` from its values.
+fn parameter<T: serde::de::DeserializeOwned>(values: Vec<String>, name: &str) -> Result<T, String> {
+    optional_parameter(values, name)?.ok_or_else(|| format!("missing parameter `{}`", name))
+}
+"#;
+
+const OPTIONAL_PARAMETER: &str = r#"/// Deserializes the parameter `name` from its values, each being either JSON or a plain string. Arrays may be comma-separated.
+fn optional_parameter<T: serde::de::DeserializeOwned>(
+    values: Vec<String>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    let json = |value: &str| {
+        serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()))
+    };
+
+    let mut candidates = Vec::new();
+    let items: Vec<String> = match values.as_slice() {
+        [] => return Ok(None),
+        [value] => {
+            candidates.push(json(value));
+            candidates.push(serde_json::Value::String(value.clone()));
+            value.split(',').map(str::to_string).collect()
+        }
+        _ => values,
+    };
+    candidates.push(serde_json::Value::Array(
+        items.iter().map(|item| json(item)).collect(),
+    ));
+    candidates.push(serde_json::Value::Array(
+        items.into_iter().map(serde_json::Value::String).collect(),
+    ));
+
+    let mut error = None;
+    for candidate in candidates {
+        match serde_json::from_value(candidate) {
+            Ok(value) => return Ok(Some(value)),
+            Err(candidate_error) => error = error.or(Some(candidate_error)),
+        }
+    }
+    Err(match error {
+        Some(error) => format!("invalid parameter `{}`: {}", name, error),
+        None => format!("invalid parameter `{}`", name),
+    })
+}
+"#;
+
+/// Generates a trait for the operations of a document to be implemented by a service, along with the types of its component schemas.
+///
+/// `Server` has a method per operation, named after its `operationId`, or its method and path if it has none, taking its path, query, header and cookie parameters and its request body as arguments.
+/// Each method returns an enum with a variant per response of the operation, holding the status code for ranges like `2XX` and the `default` response, and the body if there is one.
+///
+/// The generated `handle` function routes an `http::Request` to the method of its path and HTTP method, deserializing its parameters and body, and serializes the response with its status code and `Content-Type`.
+/// Requests with invalid parameters or bodies are answered with `400 Bad Request`, those to unknown paths with `404 Not Found` and those with unknown methods with `405 Method Not Allowed`.
+/// Path parameters must span a whole segment of the path, like `/pets/{petId}` and unlike `/pets/{petId}.json`.
+#[derive(Debug)]
+pub struct ServerGenerator<'d> {
+    document: &'d OpenAPIObject,
+    derives: Vec<String>,
+}
+
+impl<'d> ServerGenerator<'d> {
+    pub fn new(document: &'d OpenAPIObject) -> Self {
+        Self {
+            document,
+            derives: Vec::new(),
+        }
+    }
+
+    /// Adds `derives` to every type generated for a schema, like [`TypesGenerator::with_derives`](super::TypesGenerator::with_derives).
+    pub fn with_derives<T: Into<String>>(mut self, derives: impl IntoIterator<Item = T>) -> Self {
+        self.derives.extend(derives.into_iter().map(Into::into));
+        self
+    }
+
+    /// Generates the types followed by the server.
+    pub fn generate(&self) -> String {
+        let mut types = Types::new(self.document, &self.derives, SERVER_TYPES);
+        types.define_components();
+        let operations = operations(self.document, &mut types, VARIABLES);
+
+        let mut items = vec![types.finish(), SERVER.to_string()];
+
+        let mut server = String::from(
+            "/// The operations of the API, called by [`handle`] for the requests it routes.\npub trait Server {\n",
+        );
+        for (i, operation) in operations.iter().enumerate() {
+            if i > 0 {
+                server.push('\n');
+            }
+            server.push_str(&method(operation));
+        }
+        server.push_str("}\n");
+        items.push(server);
+
+        for operation in &operations {
+            items.push(operation.responses_enum(&format!("Server::{}", operation.function)));
+            items.push(into_response(operation));
+        }
+
+        items.push(handle(&operations));
+        for operation in &operations {
+            items.push(handler(operation));
+        }
+
+        let parameters = || {
+            operations
+                .iter()
+                .flat_map(|operation| &operation.parameters)
+        };
+        let uses = |location: ParameterLocation| {
+            parameters().any(|parameter| parameter.object.location() == location)
+        };
+        items.push(DECODE.to_string());
+        items.push(RESPONSE.to_string());
+        if operations
+            .iter()
+            .flat_map(|operation| &operation.responses)
+            .any(|response| {
+                response
+                    .content
+                    .as_ref()
+                    .is_some_and(|content| content.json)
+            })
+        {
+            items.push(JSON_RESPONSE.to_string());
+        }
+        if uses(ParameterLocation::Query) {
+            items.push(QUERY_VALUES.to_string());
+        }
+        if uses(ParameterLocation::Header) {
+            items.push(HEADER_VALUES.to_string());
+        }
+        if uses(ParameterLocation::Cookie) {
+            items.push(COOKIE_VALUES.to_string());
+        }
+        if parameters().any(|parameter| parameter.object.is_required()) {
+            items.push(PARAMETER.to_string());
+        }
+        if parameters().next().is_some() {
+            items.push(OPTIONAL_PARAMETER.to_string());
+        }
+
+        items.retain(|item| !item.is_empty());
+        items.join("\n")
+    }
+}
+
+/// The arguments of the method of `operation`, which are owned.
+fn arguments(operation: &Operation) -> Vec<String> {
+    let mut arguments = vec!["&self".to_string()];
+
+    for parameter in &operation.parameters {
+        if parameter.object.is_required() {
+            arguments.push(format!("{}: {}", parameter.argument, parameter.type_name));
+        } else {
+            arguments.push(format!(
+                "{}: Option<{}>",
+                parameter.argument, parameter.type_name
+            ));
+        }
+    }
+    if let Some(body) = &operation.body {
+        if body.required {
+            arguments.push(format!("{}: {}", body.argument, body.content.type_name));
+        } else {
+            arguments.push(format!(
+                "{}: Option<{}>",
+                body.argument, body.content.type_name
+            ));
+        }
+    }
+
+    arguments
+}
+
+/// The method of `Server` handling `operation`.
+fn method(operation: &Operation) -> String {
+    let arguments = arguments(operation);
+
+    let mut method = operation.attributes(arguments.len());
+    method.push_str(&format!("    fn {}(", operation.function));
+    if arguments.len() == 1 {
+        method.push_str("&self");
+    } else {
+        method.push('\n');
+        for argument in &arguments {
+            method.push_str(&format!("        {},\n", argument));
+        }
+        method.push_str("    ");
+    }
+    method.push_str(&format!(
+        ") -> HandlerFuture<'_, {}>;\n",
+        operation.responses_type
+    ));
+    method
+}
+
+/// The conversion of the enum of the responses of `operation` to an HTTP response.
+fn into_response(operation: &Operation) -> String {
+    let mut arms = String::new();
+
+    for response in &operation.responses {
+        let mut bindings = Vec::new();
+        let status = if response.has_status() {
+            bindings.push("status");
+            "status.as_u16()".to_string()
+        } else {
+            response.pattern()
+        };
+
+        let value = match &response.content {
+            Some(content) if content.json => {
+                bindings.push("body");
+                format!(
+                    "json_response({}, {}, &body)",
+                    status,
+                    literal(&content.media_type)
+                )
+            }
+            Some(content) => {
+                bindings.push("body");
+                format!(
+                    "response({}, Some({}), body)",
+                    status,
+                    literal(&content.media_type)
+                )
+            }
+            None => format!("response({}, None, Vec::new())", status),
+        };
+
+        let variant = format!("{}::{}", operation.responses_type, response.variant);
+        if bindings.is_empty() {
+            arms.push_str(&format!("{} => {},\n", variant, value));
+        } else {
+            arms.push_str(&format!(
+                "{}({}) => {},\n",
+                variant,
+                bindings.join(", "),
+                value
+            ));
+        }
+    }
+
+    let body = if operation.responses.is_empty() {
+        "match self {}\n".to_string()
+    } else {
+        format!("match self {{\n{}}}\n", indent(&arms, "    "))
+    };
+
+    format!(
+        "impl {} {{\n    /// Converts the response to an HTTP response.\n    pub fn into_response(self) -> http::Response<Vec<u8>> {{\n{}    }}\n}}\n",
+        operation.responses_type,
+        indent(&body, "        "),
+    )
+}
+
+/// The function routing requests to the handlers of `operations`.
+fn handle(operations: &[Operation]) -> String {
+    // Paths are routed by their segments, those with fewer parameters first
+    let mut routes: Vec<(Vec<&str>, Vec<&Operation>)> = Vec::new();
+    for operation in operations {
+        let segments: Vec<&str> = operation.template.split('/').skip(1).collect();
+        match routes.iter_mut().find(|(route, _)| *route == segments) {
+            Some((_, operations)) => operations.push(operation),
+            None => routes.push((segments, vec![operation])),
+        }
+    }
+    routes.sort_by_key(|(segments, _)| {
+        segments
+            .iter()
+            .filter(|segment| parameter_name(segment).is_some())
+            .count()
+    });
+
+    let mut arms = String::new();
+    for (segments, operations) in &routes {
+        // Parameters are bound to a variable if some operation declares them
+        let mut variables = Fields::default();
+        for variable in VARIABLES {
+            variables.unique(variable.to_string());
+        }
+        let mut bindings = HashMap::new();
+        let pattern: Vec<String> = segments
+            .iter()
+            .map(|segment| match parameter_name(segment) {
+                Some(name)
+                    if operations
+                        .iter()
+                        .any(|operation| path_argument(operation, name).is_some()) =>
+                {
+                    let binding = variables.unique(field_name(name));
+                    bindings.insert(name, binding.clone());
+                    binding
+                }
+                Some(_) => "_".to_string(),
+                None => literal(segment),
+            })
+            .collect();
+
+        let mut methods = String::new();
+        for operation in operations {
+            let mut arguments = vec!["server", "&request"];
+            for parameter in &operation.parameters {
+                if parameter.object.location() == ParameterLocation::Path {
+                    arguments.push(
+                        bindings
+                            .get(parameter.object.name())
+                            .map_or("\"\"", String::as_str),
+                    );
+                }
+            }
+            methods.push_str(&format!(
+                "{} => handle_{}({}).await,\n",
+                literal(operation.method.as_str()),
+                operation.function,
+                arguments.join(", "),
+            ));
+        }
+        methods.push_str(
+            "_ => Ok(response(405, Some(\"text/plain\"), b\"Method Not Allowed\".to_vec())),\n",
+        );
+
+        arms.push_str(&format!(
+            "[{}] => match request.method().as_str() {{\n{}}},\n",
+            pattern.join(", "),
+            indent(&methods, "    "),
+        ));
+    }
+    arms.push_str("_ => Ok(response(404, Some(\"text/plain\"), b\"Not Found\".to_vec())),\n");
+
+    format!(
+        "/// Answers `request` with the method of `server` for its path and method.\n///\n/// Requests with invalid parameters or bodies are answered with `400 Bad Request`, those to unknown paths with `404 Not Found` and those with unknown methods with `405 Method Not Allowed`.\npub async fn handle<S: Server + ?Sized>(\n    server: &S,\n    request: http::Request<Vec<u8>>,\n) -> http::Response<Vec<u8>> {{\n    let segments: Vec<String> = request.uri().path().split('/').skip(1).map(decode).collect();\n    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();\n\n    let result = match segments.as_slice() {{\n{}    }};\n    result.unwrap_or_else(|message| response(400, Some(\"text/plain\"), message.into_bytes()))\n}}\n",
+        indent(&arms, "        "),
+    )
+}
+
+/// The function deserializing the parameters and body of a request for `operation` and calling its method.
+fn handler(operation: &Operation) -> String {
+    let uses_request = operation.body.is_some()
+        || operation
+            .parameters
+            .iter()
+            .any(|parameter| parameter.object.location() != ParameterLocation::Path);
+    let mut signature = vec![
+        "server: &S".to_string(),
+        format!(
+            "{}: &http::Request<Vec<u8>>",
+            if uses_request { "request" } else { "_request" }
+        ),
+    ];
+    let mut output = String::new();
+    let mut call = Vec::new();
+
+    for parameter in &operation.parameters {
+        let name = parameter.object.name();
+        let argument = &parameter.argument;
+        let values = match parameter.object.location() {
+            ParameterLocation::Path => {
+                signature.push(format!("{}: &str", argument));
+                format!("vec![{}.to_string()]", argument)
+            }
+            ParameterLocation::Query => format!("query_values(request, {})", literal(name)),
+            ParameterLocation::Header => format!("header_values(request, {})", literal(name)),
+            ParameterLocation::Cookie => format!("cookie_values(request, {})", literal(name)),
+        };
+
+        if parameter.object.is_required() {
+            output.push_str(&format!(
+                "let {}: {} = parameter({}, {})?;\n",
+                argument,
+                parameter.type_name,
+                values,
+                literal(name)
+            ));
+        } else {
+            output.push_str(&format!(
+                "let {}: Option<{}> = optional_parameter({}, {})?;\n",
+                argument,
+                parameter.type_name,
+                values,
+                literal(name)
+            ));
+        }
+        call.push(argument.as_str());
+    }
+
+    if let Some(body) = &operation.body {
+        let value = if body.content.json {
+            "serde_json::from_slice(request.body())\n    .map_err(|error| format!(\"invalid body: {}\", error))?"
+        } else {
+            "request.body().clone()"
+        };
+
+        if body.required {
+            output.push_str(&format!(
+                "let {}: {} = {};\n",
+                body.argument, body.content.type_name, value
+            ));
+        } else {
+            output.push_str(&format!(
+                "let {}: Option<{}> = if request.body().is_empty() {{\n    None\n}} else {{\n    Some({})\n}};\n",
+                body.argument,
+                body.content.type_name,
+                indent(value, "    ").trim(),
+            ));
+        }
+        call.push(&body.argument);
+    }
+
+    output.push_str(&format!(
+        "Ok(server.{}({}).await.into_response())\n",
+        operation.function,
+        call.join(", ")
+    ));
+
+    let mut function = String::new();
+    if operation.object.deprecated {
+        function.push_str("#[allow(deprecated)]\n");
+    }
+    if signature.len() > 7 {
+        function.push_str("#[allow(clippy::too_many_arguments)]\n");
+    }
+    function.push_str(&format!(
+        "async fn handle_{}<S: Server + ?Sized>(\n",
+        operation.function
+    ));
+    for argument in &signature {
+        function.push_str(&format!("    {},\n", argument));
+    }
+    function.push_str(") -> Result<http::Response<Vec<u8>>, String> {\n");
+    function.push_str(&indent(&output, "    "));
+    function.push_str("}\n");
+    function
+}
+
+/// The name of the parameter `segment` is made of, if it is one.
+fn parameter_name(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+/// The argument of the path parameter `name` of `operation`, if it declares it.
+fn path_argument(operation: &Operation, name: &str) -> Option<String> {
+    operation
+        .parameters
+        .iter()
+        .find(|parameter| {
+            parameter.object.location() == ParameterLocation::Path
+                && parameter.object.name() == name
+        })
+        .map(|parameter| parameter.argument.clone())
+}
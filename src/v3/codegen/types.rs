@@ -0,0 +1,570 @@
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+
+use super::{doc_comments, field_name, literal, type_name, RESERVED_TYPES};
+use crate::v3::{
+    AdditionalProperties, ComponentGraph, ComponentKind, ComponentRef, ComponentsObject,
+    OpenAPIObject, ReferenceObjectOr, SchemaObject, SchemaType,
+};
+
+const DERIVES: &[&str] = &[
+    "Debug",
+    "Clone",
+    "PartialEq",
+    "serde::Serialize",
+    "serde::Deserialize",
+];
+
+/// Generates Rust types for the component schemas of a document.
+///
+/// Objects become structs whose fields are `Option`s unless they are required and not `nullable`, `enum`s of strings become enums, `oneOf` and `anyOf` become enums tagged by the discriminator if there is one and untagged otherwise, and `allOf` becomes a struct flattening its members.
+/// Other schemas become type aliases, or newtypes when they are recursive. References closing a cycle are boxed.
+/// Inline schemas needing a type of their own are named after where they appear, like `PetOwner` for the `owner` property of `Pet`.
+///
+/// Structs that are variants of a tagged enum don't have a field for the discriminating property, as the enum reads and writes it.
+#[derive(Debug)]
+pub struct TypesGenerator<'d> {
+    document: &'d OpenAPIObject,
+    derives: Vec<String>,
+}
+
+impl<'d> TypesGenerator<'d> {
+    pub fn new(document: &'d OpenAPIObject) -> Self {
+        Self {
+            document,
+            derives: Vec::new(),
+        }
+    }
+
+    /// Adds `derives`, e.g. `Eq` or `Hash`, to every type, which already derive `Debug`, `Clone`, `PartialEq`, `Serialize` and `Deserialize`.
+    pub fn with_derives<T: Into<String>>(mut self, derives: impl IntoIterator<Item = T>) -> Self {
+        self.derives.extend(derives.into_iter().map(Into::into));
+        self
+    }
+
+    /// Generates the types, with the types they depend on first.
+    pub fn generate(&self) -> String {
+        let mut types = Types::new(self.document, &self.derives, &[]);
+        types.define_components();
+        types.finish()
+    }
+}
+
+/// The types generated for a document so far.
+pub(super) struct Types<'d> {
+    schemas: Option<&'d IndexMap<String, ReferenceObjectOr<SchemaObject>>>,
+    graph: ComponentGraph,
+    /// The names of the types of component schemas.
+    names: HashMap<String, String>,
+    taken: HashSet<String>,
+    derive: String,
+    /// The discriminating property of the component schemas that are variants of a tagged enum.
+    tags: HashMap<String, String>,
+    items: Vec<String>,
+}
+
+impl<'d> Types<'d> {
+    /// Creates the types of `document`, whose names can't be any of `reserved`.
+    pub(super) fn new(document: &'d OpenAPIObject, derives: &[String], reserved: &[&str]) -> Self {
+        let schemas = document
+            .components
+            .as_ref()
+            .and_then(|components| components.schemas.as_ref());
+        let graph = match &document.components {
+            Some(components) => ComponentGraph::new(components),
+            None => ComponentGraph::new(&ComponentsObject::default()),
+        };
+        let derives: Vec<&str> = DERIVES
+            .iter()
+            .copied()
+            .chain(derives.iter().map(String::as_str))
+            .collect();
+
+        let mut types = Self {
+            schemas,
+            graph,
+            names: HashMap::new(),
+            taken: RESERVED_TYPES
+                .iter()
+                .chain(reserved)
+                .map(|name| name.to_string())
+                .collect(),
+            derive: format!("#[derive({})]\n", derives.join(", ")),
+            tags: HashMap::new(),
+            items: Vec::new(),
+        };
+
+        for (name, schema) in schemas.into_iter().flatten() {
+            let rust_name = types.unique(type_name(name));
+            types.names.insert(name.clone(), rust_name);
+
+            if let ReferenceObjectOr::Object(SchemaObject {
+                discriminator: Some(discriminator),
+                one_of: Some(variants),
+                ..
+            }) = schema
+            {
+                for variant in variants.iter().filter_map(ReferenceObjectOr::as_reference) {
+                    if let Some(variant) = schema_component(variant) {
+                        types
+                            .tags
+                            .insert(variant.name, discriminator.property_name.clone());
+                    }
+                }
+            }
+        }
+
+        types
+    }
+
+    pub(super) fn define_components(&mut self) {
+        let components: Vec<String> = self
+            .graph
+            .topological_order()
+            .into_iter()
+            .filter(|component| component.kind == ComponentKind::Schemas)
+            .map(|component| component.name.clone())
+            .collect();
+
+        for component in components {
+            let schema = match self.schemas.and_then(|schemas| schemas.get(&component)) {
+                Some(schema) => schema,
+                None => continue,
+            };
+            let name = self.names[&component].clone();
+
+            match schema {
+                ReferenceObjectOr::Object(schema) => self.define(&name, schema, Some(&component)),
+                ReferenceObjectOr::ReferenceObject { reference } => {
+                    let target = self.reference_type(reference, Some(&component), false);
+                    self.items
+                        .push(format!("pub type {} = {};\n", name, target));
+                }
+            }
+        }
+    }
+
+    pub(super) fn finish(self) -> String {
+        self.items.join("\n")
+    }
+
+    /// The type of `schema`, defining the types it needs. `hint` names the type of an inline schema needing its own type, and `owner` is the component the schema belongs to, if any.
+    ///
+    /// References to a component in the same cycle as `owner` are boxed unless `indirect`, when the type is already behind a pointer like a `Vec`.
+    pub(super) fn type_of(
+        &mut self,
+        schema: &ReferenceObjectOr<SchemaObject>,
+        hint: &str,
+        owner: Option<&str>,
+        indirect: bool,
+    ) -> String {
+        match schema {
+            ReferenceObjectOr::ReferenceObject { reference } => {
+                self.reference_type(reference, owner, indirect)
+            }
+            ReferenceObjectOr::Object(schema) if needs_definition(schema) => {
+                let name = self.unique(type_name(hint));
+                self.define(&name, schema, owner);
+                name
+            }
+            ReferenceObjectOr::Object(schema) => self.primitive_type(schema, hint, owner),
+        }
+    }
+
+    /// Like [`Types::type_of`], wrapped in an `Option` if the inline schema is `nullable`.
+    fn nullable_type_of(
+        &mut self,
+        schema: &ReferenceObjectOr<SchemaObject>,
+        hint: &str,
+        owner: Option<&str>,
+    ) -> String {
+        let type_name = self.type_of(schema, hint, owner, true);
+
+        match schema {
+            ReferenceObjectOr::Object(schema) if schema.nullable => {
+                format!("Option<{}>", type_name)
+            }
+            _ => type_name,
+        }
+    }
+
+    fn reference_type(&self, reference: &str, owner: Option<&str>, indirect: bool) -> String {
+        let target = match schema_component(reference) {
+            Some(target) => target,
+            None => return "serde_json::Value".to_string(),
+        };
+        let name = match self.names.get(&target.name) {
+            Some(name) => name,
+            None => return "serde_json::Value".to_string(),
+        };
+
+        let cyclic = owner.is_some_and(|owner| {
+            let owner = ComponentRef::new(ComponentKind::Schemas, owner);
+            self.graph.in_same_cycle(&owner, &target)
+        });
+        if cyclic && !indirect {
+            format!("Box<{}>", name)
+        } else {
+            name.clone()
+        }
+    }
+
+    fn primitive_type(&mut self, schema: &SchemaObject, hint: &str, owner: Option<&str>) -> String {
+        let format = schema.format.as_deref();
+
+        match schema.schema_type {
+            Some(SchemaType::String) => "String".to_string(),
+            Some(SchemaType::Integer) if format == Some("int32") => "i32".to_string(),
+            Some(SchemaType::Integer) => "i64".to_string(),
+            Some(SchemaType::Number) if format == Some("float") => "f32".to_string(),
+            Some(SchemaType::Number) => "f64".to_string(),
+            Some(SchemaType::Boolean) => "bool".to_string(),
+            Some(SchemaType::Array) => self.array_type(schema, hint, owner),
+            Some(SchemaType::Object) => self.map_type(schema, hint, owner),
+            None if schema.items.is_some() => self.array_type(schema, hint, owner),
+            None if matches!(
+                schema.additional_properties,
+                Some(AdditionalProperties::Schema(_))
+            ) =>
+            {
+                self.map_type(schema, hint, owner)
+            }
+            None => "serde_json::Value".to_string(),
+        }
+    }
+
+    fn array_type(&mut self, schema: &SchemaObject, hint: &str, owner: Option<&str>) -> String {
+        match &schema.items {
+            Some(items) => format!(
+                "Vec<{}>",
+                self.nullable_type_of(items, &format!("{}Item", hint), owner)
+            ),
+            None => "Vec<serde_json::Value>".to_string(),
+        }
+    }
+
+    fn map_type(&mut self, schema: &SchemaObject, hint: &str, owner: Option<&str>) -> String {
+        match &schema.additional_properties {
+            Some(AdditionalProperties::Schema(values)) => format!(
+                "std::collections::HashMap<String, {}>",
+                self.nullable_type_of(values, &format!("{}Value", hint), owner)
+            ),
+            _ => "serde_json::Map<String, serde_json::Value>".to_string(),
+        }
+    }
+
+    /// Defines a type called `name` for `schema`.
+    fn define(&mut self, name: &str, schema: &SchemaObject, owner: Option<&str>) {
+        let docs = doc_comments(
+            schema.description.as_deref().or(schema.title.as_deref()),
+            "",
+        );
+
+        let variants = schema
+            .one_of
+            .as_ref()
+            .or(schema.any_of.as_ref())
+            .filter(|variants| !variants.is_empty());
+        let item = if let Some(variants) = variants {
+            self.union(name, schema, variants, owner)
+        } else if needs_struct(schema) {
+            self.structure(name, schema, owner)
+        } else if is_string_enum(schema) {
+            self.enumeration(name, schema)
+        } else {
+            let type_name = self.primitive_type(schema, name, owner);
+            let recursive = owner.is_some_and(|owner| {
+                self.graph
+                    .is_recursive(&ComponentRef::new(ComponentKind::Schemas, owner))
+            });
+
+            if recursive {
+                format!("{}pub struct {}(pub {});\n", self.derive, name, type_name)
+            } else {
+                format!("pub type {} = {};\n", name, type_name)
+            }
+        };
+
+        self.items.push(format!("{}{}", docs, item));
+    }
+
+    fn structure(&mut self, name: &str, schema: &SchemaObject, owner: Option<&str>) -> String {
+        let mut fields = Fields::default();
+        let omitted = owner.and_then(|owner| self.tags.get(owner)).cloned();
+
+        self.push_fields(&mut fields, name, schema, owner, omitted.as_deref());
+
+        format!(
+            "{}pub struct {} {{\n{}}}\n",
+            self.derive, name, fields.output
+        )
+    }
+
+    fn push_fields(
+        &mut self,
+        fields: &mut Fields,
+        name: &str,
+        schema: &SchemaObject,
+        owner: Option<&str>,
+        omitted: Option<&str>,
+    ) {
+        for member in schema.all_of.iter().flatten() {
+            match member {
+                ReferenceObjectOr::ReferenceObject { reference } => {
+                    let type_name = self.reference_type(reference, owner, false);
+                    let target = schema_component(reference)
+                        .and_then(|target| self.names.get(&target.name))
+                        .map_or("value", String::as_str);
+                    let field = fields.unique(field_name(target));
+                    fields.output.push_str(&format!(
+                        "    #[serde(flatten)]\n    pub {}: {},\n",
+                        field, type_name
+                    ));
+                }
+                ReferenceObjectOr::Object(member) => {
+                    self.push_fields(fields, name, member, owner, omitted)
+                }
+            }
+        }
+
+        let required = schema.required.as_deref().unwrap_or_default();
+        for (property, property_schema) in schema.properties.iter().flatten() {
+            if Some(property.as_str()) == omitted {
+                continue;
+            }
+
+            let hint = format!("{}{}", name, type_name(property));
+            let mut type_name = self.type_of(property_schema, &hint, owner, false);
+            let nullable =
+                matches!(property_schema, ReferenceObjectOr::Object(schema) if schema.nullable);
+            let optional = !required.contains(property) || nullable;
+            if optional {
+                type_name = format!("Option<{}>", type_name);
+            }
+
+            let field = fields.unique(field_name(property));
+            let mut attributes = Vec::new();
+            if field.trim_start_matches("r#") != property {
+                attributes.push(format!("rename = {}", literal(property)));
+            }
+            if optional {
+                attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+
+            if let ReferenceObjectOr::Object(property_schema) = property_schema {
+                fields.output.push_str(&doc_comments(
+                    property_schema
+                        .description
+                        .as_deref()
+                        .or(property_schema.title.as_deref()),
+                    "    ",
+                ));
+            }
+            if !attributes.is_empty() {
+                fields
+                    .output
+                    .push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+            }
+            fields
+                .output
+                .push_str(&format!("    pub {}: {},\n", field, type_name));
+        }
+
+        if let Some(AdditionalProperties::Schema(values)) = &schema.additional_properties {
+            let values = self.nullable_type_of(values, &format!("{}Value", name), owner);
+            let field = fields.unique("additional_properties".to_string());
+            fields.output.push_str(&format!(
+                "    #[serde(flatten)]\n    pub {}: std::collections::HashMap<String, {}>,\n",
+                field, values
+            ));
+        }
+    }
+
+    fn enumeration(&mut self, name: &str, schema: &SchemaObject) -> String {
+        let mut variants = Fields::default();
+
+        for value in schema.enumeration.iter().flatten() {
+            if let Some(value) = value.as_str() {
+                let variant = if value.is_empty() {
+                    variants.unique("Empty".to_string())
+                } else {
+                    variants.unique(type_name(value))
+                };
+
+                variants.output.push_str(&format!(
+                    "    #[serde(rename = {})]\n    {},\n",
+                    literal(value),
+                    variant
+                ));
+            }
+        }
+
+        format!(
+            "{}pub enum {} {{\n{}}}\n",
+            self.derive, name, variants.output
+        )
+    }
+
+    fn union(
+        &mut self,
+        name: &str,
+        schema: &SchemaObject,
+        members: &[ReferenceObjectOr<SchemaObject>],
+        owner: Option<&str>,
+    ) -> String {
+        let mut variants = Fields::default();
+        let discriminator = schema.discriminator.as_ref();
+
+        for (i, member) in members.iter().enumerate() {
+            let hint = format!("{}Variant{}", name, i + 1);
+            let type_name = self.type_of(member, &hint, owner, false);
+
+            let (variant, tag) = match member {
+                ReferenceObjectOr::ReferenceObject { reference } => {
+                    let target = schema_component(reference);
+                    let variant = target
+                        .as_ref()
+                        .and_then(|target| self.names.get(&target.name))
+                        .cloned()
+                        .unwrap_or_else(|| format!("Variant{}", i + 1));
+                    let tag = match (discriminator, target) {
+                        (Some(discriminator), Some(target)) => discriminator
+                            .mapping
+                            .iter()
+                            .find(|(_, reference)| {
+                                **reference == target.name
+                                    || schema_component(reference).as_ref() == Some(&target)
+                            })
+                            .map_or(target.name.clone(), |(value, _)| value.clone()),
+                        _ => variant.clone(),
+                    };
+
+                    (variant, tag)
+                }
+                ReferenceObjectOr::Object(_) => {
+                    let variant =
+                        variant_name(&type_name).unwrap_or_else(|| format!("Variant{}", i + 1));
+                    (variant.clone(), variant)
+                }
+            };
+
+            let variant = variants.unique(variant);
+            if discriminator.is_some() {
+                variants
+                    .output
+                    .push_str(&format!("    #[serde(rename = {})]\n", literal(&tag)));
+            }
+            variants
+                .output
+                .push_str(&format!("    {}({}),\n", variant, type_name));
+        }
+
+        let tagging = match discriminator {
+            Some(discriminator) => format!(
+                "#[serde(tag = {})]\n",
+                literal(&discriminator.property_name)
+            ),
+            None => "#[serde(untagged)]\n".to_string(),
+        };
+
+        format!(
+            "{}{}pub enum {} {{\n{}}}\n",
+            self.derive, tagging, name, variants.output
+        )
+    }
+
+    /// Reserves a type name based on `name`, with a numeric suffix if it is taken.
+    pub(super) fn unique(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while self.taken.contains(&unique) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+
+        self.taken.insert(unique.clone());
+        unique
+    }
+}
+
+/// The fields or variants of a type being generated.
+#[derive(Default)]
+pub(super) struct Fields {
+    taken: HashSet<String>,
+    pub(super) output: String,
+}
+
+impl Fields {
+    pub(super) fn unique(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while self.taken.contains(&unique) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+
+        self.taken.insert(unique.clone());
+        unique
+    }
+}
+
+fn schema_component(reference: &str) -> Option<ComponentRef> {
+    ComponentRef::parse(reference).filter(|component| component.kind == ComponentKind::Schemas)
+}
+
+fn needs_definition(schema: &SchemaObject) -> bool {
+    needs_struct(schema)
+        || is_string_enum(schema)
+        || schema
+            .one_of
+            .as_ref()
+            .is_some_and(|variants| !variants.is_empty())
+        || schema
+            .any_of
+            .as_ref()
+            .is_some_and(|variants| !variants.is_empty())
+}
+
+fn needs_struct(schema: &SchemaObject) -> bool {
+    schema
+        .properties
+        .as_ref()
+        .is_some_and(|properties| !properties.is_empty())
+        || schema
+            .all_of
+            .as_ref()
+            .is_some_and(|members| !members.is_empty())
+}
+
+fn is_string_enum(schema: &SchemaObject) -> bool {
+    let values = match &schema.enumeration {
+        Some(values) => values,
+        None => return false,
+    };
+
+    matches!(schema.schema_type, None | Some(SchemaType::String))
+        && values.iter().any(|value| value.is_string())
+        && values
+            .iter()
+            .all(|value| value.is_string() || value.is_null())
+}
+
+/// The name of the variant of an enum holding an inline schema of type `type_name`, unless it's a generated type.
+fn variant_name(type_name: &str) -> Option<String> {
+    let name = match type_name {
+        "String" => "String",
+        "bool" => "Boolean",
+        "i32" | "i64" => "Integer",
+        "f32" | "f64" => "Number",
+        "serde_json::Value" => "Value",
+        _ if type_name.starts_with("Vec<") => "Array",
+        _ if type_name.starts_with("std::collections::HashMap<")
+            || type_name.starts_with("serde_json::Map<") =>
+        {
+            "Object"
+        }
+        _ => return None,
+    };
+
+    Some(name.to_string())
+}
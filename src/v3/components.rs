@@ -1,12 +1,13 @@
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use super::{
-    ExampleObject, Extensions, LinkObject, ParameterObject, ReferenceObjectOr, RequestBodyObject,
-    ResponseObject, SchemaObject, SecuritySchemeObject,
+    CallbackObject, ExampleObject, Extensions, HeaderObject, LinkObject, ParameterObject,
+    ReferenceObjectOr, RequestBodyObject, ResponseObject, SchemaObject, SecuritySchemeObject,
 };
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct ComponentsObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schemas: Option<IndexMap<String, ReferenceObjectOr<SchemaObject>>>,
@@ -18,14 +19,273 @@ pub struct ComponentsObject {
     pub examples: Option<IndexMap<String, ReferenceObjectOr<ExampleObject>>>,
     #[serde(rename = "requestBodies", skip_serializing_if = "Option::is_none")]
     pub request_bodies: Option<IndexMap<String, ReferenceObjectOr<RequestBodyObject>>>,
-    // TODO:  headers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<IndexMap<String, ReferenceObjectOr<HeaderObject>>>,
     /// An object to hold reusable [Security Scheme Objects](SecuritySchemeObject).
-    #[serde(rename = "securitySchemes")]
+    #[serde(
+        default,
+        rename = "securitySchemes",
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
     pub security_schemes: IndexMap<String, ReferenceObjectOr<SecuritySchemeObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub links: Option<IndexMap<String, ReferenceObjectOr<LinkObject>>>,
-    // TODO: callbacks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callbacks: Option<IndexMap<String, ReferenceObjectOr<CallbackObject>>>,
     #[serde(flatten)]
     pub extensions: Extensions,
     // TODO
 }
+
+impl ComponentsObject {
+    /// Every component held, grouped by kind and in declaration order.
+    pub fn component_refs(&self) -> Vec<ComponentRef> {
+        let mut components = Vec::new();
+
+        push_refs(&mut components, ComponentKind::Schemas, &self.schemas);
+        push_refs(&mut components, ComponentKind::Responses, &self.responses);
+        push_refs(&mut components, ComponentKind::Parameters, &self.parameters);
+        push_refs(&mut components, ComponentKind::Examples, &self.examples);
+        push_refs(
+            &mut components,
+            ComponentKind::RequestBodies,
+            &self.request_bodies,
+        );
+        push_refs(&mut components, ComponentKind::Headers, &self.headers);
+        components.extend(
+            self.security_schemes
+                .keys()
+                .map(|name| ComponentRef::new(ComponentKind::SecuritySchemes, name.as_str())),
+        );
+        push_refs(&mut components, ComponentKind::Links, &self.links);
+        push_refs(&mut components, ComponentKind::Callbacks, &self.callbacks);
+
+        components
+    }
+
+    pub fn contains(&self, component: &ComponentRef) -> bool {
+        let name = component.name.as_str();
+
+        match component.kind {
+            ComponentKind::Schemas => contains(&self.schemas, name),
+            ComponentKind::Responses => contains(&self.responses, name),
+            ComponentKind::Parameters => contains(&self.parameters, name),
+            ComponentKind::Examples => contains(&self.examples, name),
+            ComponentKind::RequestBodies => contains(&self.request_bodies, name),
+            ComponentKind::Headers => contains(&self.headers, name),
+            ComponentKind::SecuritySchemes => self.security_schemes.contains_key(name),
+            ComponentKind::Links => contains(&self.links, name),
+            ComponentKind::Callbacks => contains(&self.callbacks, name),
+        }
+    }
+
+    /// Removes the components for which `keep` returns `false`, dropping the maps left empty.
+    pub fn retain(&mut self, mut keep: impl FnMut(&ComponentRef) -> bool) {
+        retain(&mut self.schemas, ComponentKind::Schemas, &mut keep);
+        retain(&mut self.responses, ComponentKind::Responses, &mut keep);
+        retain(&mut self.parameters, ComponentKind::Parameters, &mut keep);
+        retain(&mut self.examples, ComponentKind::Examples, &mut keep);
+        retain(
+            &mut self.request_bodies,
+            ComponentKind::RequestBodies,
+            &mut keep,
+        );
+        retain(&mut self.headers, ComponentKind::Headers, &mut keep);
+        self.security_schemes.retain(|name, _| {
+            keep(&ComponentRef::new(
+                ComponentKind::SecuritySchemes,
+                name.as_str(),
+            ))
+        });
+        retain(&mut self.links, ComponentKind::Links, &mut keep);
+        retain(&mut self.callbacks, ComponentKind::Callbacks, &mut keep);
+    }
+
+    /// Whether there are neither components nor extensions.
+    pub fn is_empty(&self) -> bool {
+        self.component_refs().is_empty() && self.extensions.is_empty()
+    }
+}
+
+fn push_refs<T>(
+    components: &mut Vec<ComponentRef>,
+    kind: ComponentKind,
+    map: &Option<IndexMap<String, T>>,
+) {
+    components.extend(
+        map.iter()
+            .flat_map(IndexMap::keys)
+            .map(|name| ComponentRef::new(kind, name.as_str())),
+    );
+}
+
+fn contains<T>(map: &Option<IndexMap<String, T>>, name: &str) -> bool {
+    map.as_ref().is_some_and(|map| map.contains_key(name))
+}
+
+fn retain<T>(
+    map: &mut Option<IndexMap<String, T>>,
+    kind: ComponentKind,
+    keep: &mut impl FnMut(&ComponentRef) -> bool,
+) {
+    if let Some(components) = map {
+        components.retain(|name, _| keep(&ComponentRef::new(kind, name.as_str())));
+
+        if components.is_empty() {
+            *map = None;
+        }
+    }
+}
+
+/// The kinds of reusable objects that can be held by a [`ComponentsObject`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ComponentKind {
+    Schemas,
+    Responses,
+    Parameters,
+    Examples,
+    RequestBodies,
+    Headers,
+    SecuritySchemes,
+    Links,
+    Callbacks,
+}
+
+impl ComponentKind {
+    /// The name of the field holding this kind of component, as it appears in a document.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentKind::Schemas => "schemas",
+            ComponentKind::Responses => "responses",
+            ComponentKind::Parameters => "parameters",
+            ComponentKind::Examples => "examples",
+            ComponentKind::RequestBodies => "requestBodies",
+            ComponentKind::Headers => "headers",
+            ComponentKind::SecuritySchemes => "securitySchemes",
+            ComponentKind::Links => "links",
+            ComponentKind::Callbacks => "callbacks",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        Some(match kind {
+            "schemas" => ComponentKind::Schemas,
+            "responses" => ComponentKind::Responses,
+            "parameters" => ComponentKind::Parameters,
+            "examples" => ComponentKind::Examples,
+            "requestBodies" => ComponentKind::RequestBodies,
+            "headers" => ComponentKind::Headers,
+            "securitySchemes" => ComponentKind::SecuritySchemes,
+            "links" => ComponentKind::Links,
+            "callbacks" => ComponentKind::Callbacks,
+            _ => return None,
+        })
+    }
+}
+
+/// A parsed local reference to a component, e.g. `#/components/schemas/Pet`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ComponentRef {
+    pub kind: ComponentKind,
+    pub name: String,
+}
+
+impl ComponentRef {
+    pub fn new(kind: ComponentKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+        }
+    }
+
+    /// Parses a `$ref` value, returning `None` if it does not point to a component of this document.
+    pub fn parse(reference: &str) -> Option<Self> {
+        let mut segments = reference.strip_prefix("#/components/")?.splitn(2, '/');
+        let kind = ComponentKind::parse(segments.next()?)?;
+        let name = segments.next()?;
+
+        if name.is_empty() || name.contains('/') {
+            return None;
+        }
+
+        Some(Self::new(kind, name.replace("~1", "/").replace("~0", "~")))
+    }
+
+    /// Formats this component back as a `$ref` value.
+    pub fn to_reference(&self) -> String {
+        format!("#{}", self.to_pointer())
+    }
+
+    /// The [JSON Pointer](https://tools.ietf.org/html/rfc6901) to this component in its document, e.g. `/components/schemas/Pet`.
+    pub fn to_pointer(&self) -> String {
+        format!(
+            "/components/{}/{}",
+            self.kind.as_str(),
+            self.name.replace('~', "~0").replace('/', "~1")
+        )
+    }
+}
+
+impl fmt::Display for ComponentRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_reference())
+    }
+}
+
+/// Objects that can be stored in a [`ComponentsObject`] and therefore be the target of a reference.
+pub trait Component: Sized {
+    const KIND: ComponentKind;
+
+    fn components(
+        components: &ComponentsObject,
+    ) -> Option<&IndexMap<String, ReferenceObjectOr<Self>>>;
+
+    fn components_mut(
+        components: &mut ComponentsObject,
+    ) -> Option<&mut IndexMap<String, ReferenceObjectOr<Self>>>;
+}
+
+macro_rules! impl_component {
+    ($object:ty, $kind:ident, $field:ident) => {
+        impl Component for $object {
+            const KIND: ComponentKind = ComponentKind::$kind;
+
+            fn components(
+                components: &ComponentsObject,
+            ) -> Option<&IndexMap<String, ReferenceObjectOr<Self>>> {
+                components.$field.as_ref()
+            }
+
+            fn components_mut(
+                components: &mut ComponentsObject,
+            ) -> Option<&mut IndexMap<String, ReferenceObjectOr<Self>>> {
+                components.$field.as_mut()
+            }
+        }
+    };
+}
+
+impl_component!(SchemaObject, Schemas, schemas);
+impl_component!(ResponseObject, Responses, responses);
+impl_component!(ParameterObject, Parameters, parameters);
+impl_component!(ExampleObject, Examples, examples);
+impl_component!(RequestBodyObject, RequestBodies, request_bodies);
+impl_component!(HeaderObject, Headers, headers);
+impl_component!(LinkObject, Links, links);
+impl_component!(CallbackObject, Callbacks, callbacks);
+
+impl Component for SecuritySchemeObject {
+    const KIND: ComponentKind = ComponentKind::SecuritySchemes;
+
+    fn components(
+        components: &ComponentsObject,
+    ) -> Option<&IndexMap<String, ReferenceObjectOr<Self>>> {
+        Some(&components.security_schemes)
+    }
+
+    fn components_mut(
+        components: &mut ComponentsObject,
+    ) -> Option<&mut IndexMap<String, ReferenceObjectOr<Self>>> {
+        Some(&mut components.security_schemes)
+    }
+}
@@ -4,7 +4,7 @@ use url::Url;
 use super::Extensions;
 
 /// Contact information for the exposed API.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct ContactObject {
     /// The identifying name of the contact person/organization.
     #[serde(skip_serializing_if = "Option::is_none")]
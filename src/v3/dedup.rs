@@ -0,0 +1,252 @@
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    rename::rename_references, visit::visit_schemas_mut, AdditionalProperties, ComponentKind,
+    ComponentRef, OpenAPIObject, ReferenceObjectOr, SchemaObject,
+};
+
+/// How schemas are compared when [deduplicating](OpenAPIObject::deduplicate_schemas) them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaComparison {
+    /// Schemas must be equal in every field.
+    #[default]
+    Strict,
+    /// Schemas, and the schemas they hold, may differ in their `title` and `description`.
+    IgnoreDocumentation,
+}
+
+impl OpenAPIObject {
+    /// Collapses the component schemas that are structurally equal to a single one and rewrites references to the others, returning the name of every removed schema along with the name of the schema it was replaced with.
+    ///
+    /// The schema declared first is kept. Schemas referencing duplicates are compared again once references are rewritten, so schemas that only differ by which of two duplicates they reference are collapsed too.
+    /// Schemas that are only equal assuming they are equal to each other, like two identical recursive schemas, are kept.
+    /// A `oneOf` or `anyOf` listing several duplicates keeps a single reference to the schema replacing them, since a value can't match two identical `oneOf` branches.
+    pub fn deduplicate_schemas(
+        &mut self,
+        comparison: SchemaComparison,
+    ) -> IndexMap<String, String> {
+        let mut replaced: IndexMap<String, String> = IndexMap::new();
+
+        while let Some(schemas) = self
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.as_mut())
+        {
+            let normalized: Vec<ReferenceObjectOr<SchemaObject>> = schemas
+                .values()
+                .map(|schema| normalize(schema, comparison))
+                .collect();
+            let names: Vec<&String> = schemas.keys().collect();
+
+            // Maps every duplicate to the first schema equal to it
+            let mut removed: IndexMap<String, String> = IndexMap::new();
+            for (i, schema) in normalized.iter().enumerate() {
+                if removed.contains_key(names[i]) {
+                    continue;
+                }
+
+                for (j, other) in normalized.iter().enumerate().skip(i + 1) {
+                    if other == schema && !removed.contains_key(names[j]) {
+                        removed.insert(names[j].clone(), names[i].clone());
+                    }
+                }
+            }
+
+            if removed.is_empty() {
+                break;
+            }
+            removed.sort_by(|a, _, b, _| schemas.get_index_of(a).cmp(&schemas.get_index_of(b)));
+
+            schemas.retain(|name, _| !removed.contains_key(name));
+            let renames: HashMap<ComponentRef, String> = removed
+                .iter()
+                .map(|(duplicate, canonical)| {
+                    (
+                        ComponentRef::new(ComponentKind::Schemas, duplicate.as_str()),
+                        canonical.clone(),
+                    )
+                })
+                .collect();
+            rename_references(self, &renames);
+
+            let canonical: HashSet<String> = removed
+                .values()
+                .map(|name| ComponentRef::new(ComponentKind::Schemas, name.as_str()).to_reference())
+                .collect();
+            remove_duplicate_branches(self, &canonical);
+
+            // Schemas replaced in earlier rounds may point to one that was just removed
+            for canonical in replaced.values_mut() {
+                if let Some(name) = removed.get(canonical) {
+                    *canonical = name.clone();
+                }
+            }
+            replaced.extend(removed);
+        }
+
+        replaced
+    }
+}
+
+fn normalize(
+    schema: &ReferenceObjectOr<SchemaObject>,
+    comparison: SchemaComparison,
+) -> ReferenceObjectOr<SchemaObject> {
+    let mut schema = schema.clone();
+    if comparison == SchemaComparison::IgnoreDocumentation {
+        strip_documentation(&mut schema);
+    }
+    schema
+}
+
+fn strip_documentation(schema: &mut ReferenceObjectOr<SchemaObject>) {
+    let schema = match schema {
+        ReferenceObjectOr::Object(schema) => schema,
+        ReferenceObjectOr::ReferenceObject { .. } => return,
+    };
+
+    schema.title = None;
+    schema.description = None;
+
+    nested_schemas(schema).for_each(strip_documentation);
+}
+
+/// Removes the `oneOf` and `anyOf` branches of every schema of `document` that are references to one of the `canonical` schemas listed earlier in the same keyword.
+fn remove_duplicate_branches(document: &mut OpenAPIObject, canonical: &HashSet<String>) {
+    fn remove(schema: &mut ReferenceObjectOr<SchemaObject>, canonical: &HashSet<String>) {
+        let schema = match schema {
+            ReferenceObjectOr::Object(schema) => schema,
+            ReferenceObjectOr::ReferenceObject { .. } => return,
+        };
+
+        for branches in schema.one_of.iter_mut().chain(&mut schema.any_of) {
+            let mut kept: Vec<ReferenceObjectOr<SchemaObject>> = Vec::with_capacity(branches.len());
+            for branch in branches.drain(..) {
+                let duplicate = match &branch {
+                    ReferenceObjectOr::ReferenceObject { reference } => {
+                        canonical.contains(reference) && kept.contains(&branch)
+                    }
+                    ReferenceObjectOr::Object(_) => false,
+                };
+                if !duplicate {
+                    kept.push(branch);
+                }
+            }
+            *branches = kept;
+        }
+
+        for schema in nested_schemas(schema) {
+            remove(schema, canonical);
+        }
+    }
+
+    if let Some(schemas) = document
+        .components
+        .as_mut()
+        .and_then(|components| components.schemas.as_mut())
+    {
+        for schema in schemas.values_mut() {
+            remove(schema, canonical);
+        }
+    }
+    visit_schemas_mut(document, &mut |_, schema| remove(schema, canonical));
+}
+
+/// The schemas held by `schema`.
+fn nested_schemas(
+    schema: &mut SchemaObject,
+) -> impl Iterator<Item = &mut ReferenceObjectOr<SchemaObject>> {
+    let additional = match &mut schema.additional_properties {
+        Some(AdditionalProperties::Schema(schema)) => Some(schema.as_mut()),
+        _ => None,
+    };
+
+    schema
+        .all_of
+        .iter_mut()
+        .chain(&mut schema.one_of)
+        .chain(&mut schema.any_of)
+        .flatten()
+        .chain(
+            schema
+                .properties
+                .iter_mut()
+                .flat_map(|properties| properties.values_mut()),
+        )
+        .chain(schema.not.as_deref_mut())
+        .chain(schema.items.as_deref_mut())
+        .chain(additional)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn duplicate_branches() {
+        let mut document: OpenAPIObject = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Addresses, version: "1"}
+servers: []
+paths:
+  /addresses:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              anyOf:
+                - {$ref: "#/components/schemas/PostalAddress"}
+                - {$ref: "#/components/schemas/Address"}
+                - {type: string}
+                - {type: string}
+      responses: {"204": {description: Created}}
+components:
+  schemas:
+    Address: {type: object, properties: {street: {type: string}}}
+    PostalAddress: {type: object, description: Postal, properties: {street: {type: string}}}
+    Location:
+      oneOf:
+        - {$ref: "#/components/schemas/Address"}
+        - {$ref: "#/components/schemas/Coordinates"}
+        - {$ref: "#/components/schemas/PostalAddress"}
+    Coordinates: {type: array, items: {type: number}}
+"##,
+        )
+        .unwrap();
+
+        let replaced = document.deduplicate_schemas(SchemaComparison::IgnoreDocumentation);
+        assert_eq!(
+            replaced.into_iter().collect::<Vec<_>>(),
+            [("PostalAddress".to_string(), "Address".to_string())]
+        );
+
+        let document = serde_json::to_value(&document).unwrap();
+        assert_eq!(
+            document["components"]["schemas"]["Location"]["oneOf"],
+            json!([
+                { "$ref": "#/components/schemas/Address" },
+                { "$ref": "#/components/schemas/Coordinates" },
+            ])
+        );
+        // Only the branches made identical by the rewrite are removed
+        let branches = document["paths"]["/addresses"]["post"]["requestBody"]["content"]
+            ["application/json"]["schema"]["anyOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|branch| branch.get("$ref").unwrap_or(&branch["type"]).clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            branches,
+            [
+                json!("#/components/schemas/Address"),
+                json!("string"),
+                json!("string")
+            ]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use http::Method;
+use serde_json::Value;
+use std::fmt;
+
+use crate::v3::{
+    MediaType, ParameterLocation, ResolveError, SchemaType, SecurityRequirementObject,
+};
+
+/// Every change found between two documents, in the order they appear in the old document followed by additions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Whether any change may break existing clients.
+    pub fn is_breaking(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.severity == Severity::Breaking)
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.with_severity(Severity::Breaking)
+    }
+
+    pub fn non_breaking(&self) -> impl Iterator<Item = &Change> {
+        self.with_severity(Severity::NonBreaking)
+    }
+
+    /// Changes whose impact can't be determined statically, e.g. a modified `pattern`.
+    pub fn unknown(&self) -> impl Iterator<Item = &Change> {
+        self.with_severity(Severity::Unknown)
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.severity == severity)
+    }
+}
+
+/// A single difference between two documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// A [JSON Pointer](https://tools.ietf.org/html/rfc6901) to the changed value, in the new document for additions and in the old document otherwise.
+    pub pointer: String,
+    /// The templated path of the affected operation, as it appears in the old document.
+    pub path: Option<String>,
+    /// The method of the affected operation.
+    pub method: Option<Method>,
+    pub kind: ChangeKind,
+    pub severity: Severity,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.method, &self.path) {
+            (Some(method), Some(path)) => write!(f, "{} {}: ", method, path)?,
+            (None, Some(path)) => write!(f, "{}: ", path)?,
+            _ => {}
+        }
+
+        write!(f, "{} ({})", self.kind, self.severity)
+    }
+}
+
+/// How a change affects clients written against the old document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Breaking,
+    NonBreaking,
+    /// The change may or may not break clients, e.g. a different `pattern`.
+    Unknown,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Breaking => "breaking",
+            Severity::NonBreaking => "non-breaking",
+            Severity::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which side of an exchange a schema describes.
+///
+/// Restricting what is accepted breaks clients sending requests, while restricting what is returned is safe for clients reading responses, and the other way around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Request,
+    Response,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    PathAdded,
+    PathRemoved,
+    OperationAdded,
+    OperationRemoved,
+    OperationDeprecated,
+    ParameterAdded {
+        location: ParameterLocation,
+        name: String,
+        required: bool,
+    },
+    ParameterRemoved {
+        location: ParameterLocation,
+        name: String,
+    },
+    /// An optional parameter became required.
+    ParameterRequired {
+        location: ParameterLocation,
+        name: String,
+    },
+    /// A required parameter became optional.
+    ParameterOptional {
+        location: ParameterLocation,
+        name: String,
+    },
+    RequestBodyAdded {
+        required: bool,
+    },
+    RequestBodyRemoved,
+    RequestBodyRequired,
+    RequestBodyOptional,
+    ResponseAdded(String),
+    ResponseRemoved(String),
+    HeaderAdded {
+        name: String,
+        required: bool,
+    },
+    HeaderRemoved(String),
+    HeaderRequired(String),
+    HeaderOptional(String),
+    MediaTypeAdded(MediaType),
+    MediaTypeRemoved(MediaType),
+    /// The `type` of a schema changed to an incompatible one.
+    TypeChanged {
+        old: Option<SchemaType>,
+        new: Option<SchemaType>,
+    },
+    EnumValuesAdded(Vec<Value>),
+    EnumValuesRemoved(Vec<Value>),
+    /// A keyword now accepts fewer values, e.g. a lower `maxLength`.
+    ConstraintTightened {
+        keyword: &'static str,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    /// A keyword now accepts more values, e.g. a higher `maxLength`.
+    ConstraintLoosened {
+        keyword: &'static str,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    /// A keyword changed in a way that is neither strictly tighter nor looser, e.g. a different `pattern`.
+    ConstraintChanged {
+        keyword: &'static str,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    PropertyAdded(String),
+    PropertyRemoved(String),
+    PropertyRequired(String),
+    PropertyOptional(String),
+    SecurityRequirementAdded(SecurityRequirementObject),
+    SecurityRequirementRemoved(SecurityRequirementObject),
+    SecuritySchemeAdded(String),
+    SecuritySchemeRemoved(String),
+    SecuritySchemeChanged(String),
+    /// A reference of either document can't be resolved, so what it points to wasn't compared.
+    UnresolvedReference(ResolveError),
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::PathAdded => f.write_str("path added"),
+            ChangeKind::PathRemoved => f.write_str("path removed"),
+            ChangeKind::OperationAdded => f.write_str("operation added"),
+            ChangeKind::OperationRemoved => f.write_str("operation removed"),
+            ChangeKind::OperationDeprecated => f.write_str("operation deprecated"),
+            ChangeKind::ParameterAdded {
+                location,
+                name,
+                required,
+            } => write!(
+                f,
+                "{} {} parameter `{}` added",
+                if *required { "required" } else { "optional" },
+                location.as_str(),
+                name
+            ),
+            ChangeKind::ParameterRemoved { location, name } => {
+                write!(f, "{} parameter `{}` removed", location.as_str(), name)
+            }
+            ChangeKind::ParameterRequired { location, name } => {
+                write!(
+                    f,
+                    "{} parameter `{}` is now required",
+                    location.as_str(),
+                    name
+                )
+            }
+            ChangeKind::ParameterOptional { location, name } => {
+                write!(
+                    f,
+                    "{} parameter `{}` is now optional",
+                    location.as_str(),
+                    name
+                )
+            }
+            ChangeKind::RequestBodyAdded { required } => write!(
+                f,
+                "{} request body added",
+                if *required { "required" } else { "optional" }
+            ),
+            ChangeKind::RequestBodyRemoved => f.write_str("request body removed"),
+            ChangeKind::RequestBodyRequired => f.write_str("request body is now required"),
+            ChangeKind::RequestBodyOptional => f.write_str("request body is now optional"),
+            ChangeKind::ResponseAdded(status) => write!(f, "response `{}` added", status),
+            ChangeKind::ResponseRemoved(status) => write!(f, "response `{}` removed", status),
+            ChangeKind::HeaderAdded { name, required } => write!(
+                f,
+                "{} header `{}` added",
+                if *required { "required" } else { "optional" },
+                name
+            ),
+            ChangeKind::HeaderRemoved(name) => write!(f, "header `{}` removed", name),
+            ChangeKind::HeaderRequired(name) => write!(f, "header `{}` is now required", name),
+            ChangeKind::HeaderOptional(name) => write!(f, "header `{}` is now optional", name),
+            ChangeKind::MediaTypeAdded(media_type) => {
+                write!(f, "media type `{}` added", media_type)
+            }
+            ChangeKind::MediaTypeRemoved(media_type) => {
+                write!(f, "media type `{}` removed", media_type)
+            }
+            ChangeKind::TypeChanged { old, new } => write!(
+                f,
+                "type changed from `{}` to `{}`",
+                old.map_or("any", |old| old.as_str()),
+                new.map_or("any", |new| new.as_str())
+            ),
+            ChangeKind::EnumValuesAdded(values) => {
+                write!(f, "enum values added: {}", join(values))
+            }
+            ChangeKind::EnumValuesRemoved(values) => {
+                write!(f, "enum values removed: {}", join(values))
+            }
+            ChangeKind::ConstraintTightened { keyword, old, new }
+            | ChangeKind::ConstraintLoosened { keyword, old, new }
+            | ChangeKind::ConstraintChanged { keyword, old, new } => match (old, new) {
+                (None, Some(new)) => write!(f, "`{}` set to {}", keyword, new),
+                (Some(old), None) => write!(f, "`{}` {} removed", keyword, old),
+                (Some(old), Some(new)) => {
+                    write!(f, "`{}` changed from {} to {}", keyword, old, new)
+                }
+                (None, None) => write!(f, "`{}` changed", keyword),
+            },
+            ChangeKind::PropertyAdded(name) => write!(f, "property `{}` added", name),
+            ChangeKind::PropertyRemoved(name) => write!(f, "property `{}` removed", name),
+            ChangeKind::PropertyRequired(name) => {
+                write!(f, "property `{}` is now required", name)
+            }
+            ChangeKind::PropertyOptional(name) => {
+                write!(f, "property `{}` is now optional", name)
+            }
+            ChangeKind::SecurityRequirementAdded(requirement) => {
+                write!(
+                    f,
+                    "security requirement {} added",
+                    requirement_names(requirement)
+                )
+            }
+            ChangeKind::SecurityRequirementRemoved(requirement) => {
+                write!(
+                    f,
+                    "security requirement {} removed",
+                    requirement_names(requirement)
+                )
+            }
+            ChangeKind::SecuritySchemeAdded(name) => {
+                write!(f, "security scheme `{}` added", name)
+            }
+            ChangeKind::SecuritySchemeRemoved(name) => {
+                write!(f, "security scheme `{}` removed", name)
+            }
+            ChangeKind::SecuritySchemeChanged(name) => {
+                write!(f, "security scheme `{}` changed", name)
+            }
+            ChangeKind::UnresolvedReference(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+fn join(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn requirement_names(requirement: &SecurityRequirementObject) -> String {
+    if requirement.is_empty() {
+        return String::from("`{}`");
+    }
+
+    requirement
+        .keys()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
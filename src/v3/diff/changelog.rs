@@ -0,0 +1,322 @@
+use http::Method;
+use std::fmt::Write;
+
+use super::{normalize_template, Change, Diff, Severity};
+use crate::utils::push_pointer;
+use crate::v3::{OpenAPIObject, OperationObject};
+
+/// The changes of a [`Diff`] grouped by tag and operation, ready to be rendered as release notes.
+///
+/// Operations are looked up in the new document first and in the old one for removed operations, an operation appears under its first tag.
+/// Tags are ordered as declared in the documents, followed by untagged operations and by changes that don't affect a single operation, like security schemes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changelog<'a> {
+    pub tags: Vec<TagChanges<'a>>,
+    /// Changes to operations without tags.
+    pub untagged: Vec<OperationChanges<'a>>,
+    /// Changes outside of the paths of the documents.
+    pub general: Vec<&'a Change>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagChanges<'a> {
+    pub name: &'a str,
+    pub operations: Vec<OperationChanges<'a>>,
+}
+
+/// The changes to an operation, or to a whole path item when `method` is `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationChanges<'a> {
+    pub path: &'a str,
+    pub method: Option<Method>,
+    pub summary: Option<&'a str>,
+    pub operation_id: Option<&'a str>,
+    /// Breaking changes come first, followed by changes of unknown impact and non-breaking ones.
+    pub changes: Vec<&'a Change>,
+}
+
+impl OperationChanges<'_> {
+    /// The summary of the operation, or its id, or its method and path.
+    pub fn title(&self) -> String {
+        match (self.summary, self.operation_id) {
+            (Some(summary), _) => summary.to_string(),
+            (None, Some(operation_id)) => operation_id.to_string(),
+            (None, None) => self.endpoint(),
+        }
+    }
+
+    /// The method and path, e.g. `GET /users/{id}`.
+    pub fn endpoint(&self) -> String {
+        match &self.method {
+            Some(method) => format!("{} {}", method, self.path),
+            None => self.path.to_string(),
+        }
+    }
+}
+
+impl<'a> Changelog<'a> {
+    pub fn new(diff: &'a Diff, old: &'a OpenAPIObject, new: &'a OpenAPIObject) -> Self {
+        let mut changelog = Changelog {
+            tags: Vec::new(),
+            untagged: Vec::new(),
+            general: Vec::new(),
+        };
+        for name in declared_tags(new).chain(declared_tags(old)) {
+            if !changelog.tags.iter().any(|tag| tag.name == name) {
+                changelog.tags.push(TagChanges {
+                    name,
+                    operations: Vec::new(),
+                });
+            }
+        }
+
+        for change in &diff.changes {
+            let path = match &change.path {
+                Some(path) => path.as_str(),
+                None => {
+                    changelog.general.push(change);
+                    continue;
+                }
+            };
+
+            let (template, operation) = find_operation(new, path, change.method.as_ref())
+                .or_else(|| find_operation(old, path, change.method.as_ref()))
+                .unwrap_or((path, None));
+
+            let tag = operation.and_then(|operation| operation.tags.as_ref()?.first());
+            let operations = match tag {
+                Some(tag) => {
+                    let index = match changelog.tags.iter().position(|t| t.name == tag) {
+                        Some(index) => index,
+                        None => {
+                            changelog.tags.push(TagChanges {
+                                name: tag,
+                                operations: Vec::new(),
+                            });
+                            changelog.tags.len() - 1
+                        }
+                    };
+                    &mut changelog.tags[index].operations
+                }
+                None => &mut changelog.untagged,
+            };
+
+            match operations
+                .iter_mut()
+                .find(|entry| entry.path == template && entry.method == change.method)
+            {
+                Some(entry) => entry.changes.push(change),
+                None => operations.push(OperationChanges {
+                    path: template,
+                    method: change.method.clone(),
+                    summary: operation.and_then(|operation| operation.summary.as_deref()),
+                    operation_id: operation.and_then(|operation| operation.operation_id.as_deref()),
+                    changes: vec![change],
+                }),
+            }
+        }
+
+        changelog.tags.retain(|tag| !tag.operations.is_empty());
+        for operation in changelog
+            .tags
+            .iter_mut()
+            .flat_map(|tag| tag.operations.iter_mut())
+            .chain(changelog.untagged.iter_mut())
+        {
+            operation
+                .changes
+                .sort_by_key(|change| rank(change.severity));
+        }
+        changelog
+            .general
+            .sort_by_key(|change| rank(change.severity));
+
+        changelog
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.untagged.is_empty() && self.general.is_empty()
+    }
+
+    /// Renders the changelog as Markdown, with a second level heading per tag and a third level heading per operation.
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+
+        if self.is_empty() {
+            output.push_str("No changes.\n");
+            return output;
+        }
+
+        for (name, operations) in self.sections() {
+            let _ = writeln!(output, "## {}\n", name);
+
+            for operation in operations {
+                if operation.summary.is_some() || operation.operation_id.is_some() {
+                    let _ = writeln!(
+                        output,
+                        "### {} (`{}`)\n",
+                        operation.title(),
+                        operation.endpoint()
+                    );
+                } else {
+                    let _ = writeln!(output, "### `{}`\n", operation.endpoint());
+                }
+                markdown_list(&mut output, &operation.changes);
+            }
+        }
+
+        if !self.general.is_empty() {
+            output.push_str("## General\n\n");
+            markdown_list(&mut output, &self.general);
+        }
+
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+        output
+    }
+
+    /// Renders the changelog as plain text, with underlined tags and indented changes.
+    pub fn to_text(&self) -> String {
+        let mut output = String::new();
+
+        if self.is_empty() {
+            output.push_str("No changes.\n");
+            return output;
+        }
+
+        for (name, operations) in self.sections() {
+            let _ = writeln!(output, "{}\n{}\n", name, "=".repeat(name.chars().count()));
+
+            for operation in operations {
+                if operation.summary.is_some() || operation.operation_id.is_some() {
+                    let _ = writeln!(output, "{} ({})", operation.title(), operation.endpoint());
+                } else {
+                    let _ = writeln!(output, "{}", operation.endpoint());
+                }
+                text_list(&mut output, &operation.changes);
+            }
+        }
+
+        if !self.general.is_empty() {
+            output.push_str("General\n=======\n\n");
+            text_list(&mut output, &self.general);
+        }
+
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+        output
+    }
+
+    fn sections(&self) -> impl Iterator<Item = (&str, &[OperationChanges<'a>])> {
+        let untagged = if self.untagged.is_empty() {
+            None
+        } else {
+            Some(("Other", self.untagged.as_slice()))
+        };
+
+        self.tags
+            .iter()
+            .map(|tag| (tag.name, tag.operations.as_slice()))
+            .chain(untagged)
+    }
+}
+
+impl Diff {
+    /// Shorthand for rendering a [`Changelog`] as Markdown.
+    pub fn to_markdown(&self, old: &OpenAPIObject, new: &OpenAPIObject) -> String {
+        Changelog::new(self, old, new).to_markdown()
+    }
+
+    /// Shorthand for rendering a [`Changelog`] as plain text.
+    pub fn to_text(&self, old: &OpenAPIObject, new: &OpenAPIObject) -> String {
+        Changelog::new(self, old, new).to_text()
+    }
+}
+
+fn markdown_list(output: &mut String, changes: &[&Change]) {
+    for change in changes {
+        let prefix = match change.severity {
+            Severity::Breaking => "**Breaking:** ",
+            Severity::Unknown => "**Possibly breaking:** ",
+            Severity::NonBreaking => "",
+        };
+        let _ = write!(output, "- {}{}", prefix, change.kind);
+        if let Some(location) = location(change) {
+            let _ = write!(output, " (`{}`)", location);
+        }
+        output.push('\n');
+    }
+    output.push('\n');
+}
+
+fn text_list(output: &mut String, changes: &[&Change]) {
+    for change in changes {
+        let prefix = match change.severity {
+            Severity::Breaking => "[BREAKING] ",
+            Severity::Unknown => "[POSSIBLY BREAKING] ",
+            Severity::NonBreaking => "",
+        };
+        let _ = write!(output, "  - {}{}", prefix, change.kind);
+        if let Some(location) = location(change) {
+            let _ = write!(output, " at {}", location);
+        }
+        output.push('\n');
+    }
+    output.push('\n');
+}
+
+/// The pointer of a change relative to its operation, e.g. `/responses/200`, since the heading already names the operation.
+fn location(change: &Change) -> Option<&str> {
+    let (path, method) = match (&change.path, &change.method) {
+        (Some(path), Some(method)) => (path, method),
+        _ => return None,
+    };
+    let operation = push_pointer(
+        &push_pointer("/paths", path),
+        &method.as_str().to_lowercase(),
+    );
+
+    change
+        .pointer
+        .strip_prefix(operation.as_str())
+        .filter(|location| !location.is_empty())
+}
+
+fn rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Breaking => 0,
+        Severity::Unknown => 1,
+        Severity::NonBreaking => 2,
+    }
+}
+
+fn declared_tags(document: &OpenAPIObject) -> impl Iterator<Item = &str> {
+    document.tags.iter().flatten().map(|tag| tag.name.as_str())
+}
+
+/// Finds the path item matching `path` regardless of the names of its variables, along with the operation for `method`.
+/// Changes to whole path items use their first operation.
+fn find_operation<'d>(
+    document: &'d OpenAPIObject,
+    path: &str,
+    method: Option<&Method>,
+) -> Option<(&'d str, Option<&'d OperationObject>)> {
+    let normalized = normalize_template(path);
+    let (template, item) = document
+        .paths
+        .iter()
+        .filter_map(|(template, item)| Some((template, item.as_object()?)))
+        .find(|(template, _)| normalize_template(template) == normalized)?;
+
+    let operation = match method {
+        Some(method) => item.operation(method),
+        None => item.operations().next().map(|(_, operation)| operation),
+    };
+
+    // Removed operations are only found in the old document
+    if method.is_some() && operation.is_none() {
+        return None;
+    }
+
+    Some((template, operation))
+}
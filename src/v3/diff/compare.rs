@@ -0,0 +1,1406 @@
+use http::Method;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use super::{Change, ChangeKind, Diff, Direction, Severity};
+use crate::utils::push_pointer;
+use crate::v3::{
+    AdditionalProperties, Component, ContentMap, HeaderObject, OpenAPIObject, OperationObject,
+    ParameterLocation, ParameterObject, PathsItemObject, ReferenceObjectOr, ResolveError,
+    ResponseObject, SchemaObject, SchemaOrContentObject, SchemaType, SecurityRequirementObject,
+};
+
+/// Compares two versions of a document, classifying every change by its impact on clients written against `old`.
+///
+/// Paths are matched by their template regardless of the names of their variables, so renaming `/users/{id}` to `/users/{userId}` is not a change.
+/// References are resolved against the document they belong to, those that can't be resolved are reported as [`ChangeKind::UnresolvedReference`].
+/// Schemas are compared according to the [`Direction`] of the data they describe.
+pub fn diff(old: &OpenAPIObject, new: &OpenAPIObject) -> Diff {
+    let mut differ = Differ::new(old, new);
+
+    differ.compare_paths();
+    differ.compare_global_security();
+    differ.compare_security_schemes();
+
+    Diff {
+        changes: differ.changes,
+    }
+}
+
+/// Compares two schemas describing the same data, `old` belonging to `old_document` and `new` to `new_document`.
+///
+/// Pointers in the resulting changes are relative to the schemas.
+pub fn diff_schemas(
+    old_document: &OpenAPIObject,
+    old: &ReferenceObjectOr<SchemaObject>,
+    new_document: &OpenAPIObject,
+    new: &ReferenceObjectOr<SchemaObject>,
+    direction: Direction,
+) -> Diff {
+    let mut differ = Differ::new(old_document, new_document);
+
+    differ.compare_schema("", old, new, direction);
+
+    Diff {
+        changes: differ.changes,
+    }
+}
+
+struct Differ<'a> {
+    old: &'a OpenAPIObject,
+    new: &'a OpenAPIObject,
+    /// The operation being compared, attached to every change.
+    path: Option<String>,
+    method: Option<Method>,
+    /// The pairs of schema references being compared, to stop at recursive schemas.
+    references: Vec<(&'a str, &'a str)>,
+    changes: Vec<Change>,
+}
+
+type Parameters<'a> = IndexMap<(ParameterLocation, String), (String, &'a ParameterObject)>;
+
+impl<'a> Differ<'a> {
+    fn new(old: &'a OpenAPIObject, new: &'a OpenAPIObject) -> Self {
+        Self {
+            old,
+            new,
+            path: None,
+            method: None,
+            references: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, pointer: &str, kind: ChangeKind, severity: Severity) {
+        self.changes.push(Change {
+            pointer: pointer.to_string(),
+            path: self.path.clone(),
+            method: self.method.clone(),
+            kind,
+            severity,
+        });
+    }
+
+    /// Resolves `object` against `document`, reporting the reference at `pointer` when it can't be resolved.
+    fn resolve<T: Component>(
+        &mut self,
+        pointer: &str,
+        object: &'a ReferenceObjectOr<T>,
+        document: &'a OpenAPIObject,
+    ) -> Option<&'a T> {
+        match object.resolve(document) {
+            Ok(object) => Some(object),
+            Err(error) => {
+                self.unresolved(pointer, error);
+                None
+            }
+        }
+    }
+
+    /// Reports a reference that can't be resolved, once even if it's broken in both documents.
+    fn unresolved(&mut self, pointer: &str, error: ResolveError) {
+        let kind = ChangeKind::UnresolvedReference(error);
+        let reported = self
+            .changes
+            .iter()
+            .any(|change| change.pointer == pointer && change.kind == kind);
+        if !reported {
+            self.push(pointer, kind, Severity::Unknown);
+        }
+    }
+
+    fn compare_paths(&mut self) {
+        let new_paths: IndexMap<String, (&'a str, &'a PathsItemObject)> = self
+            .new
+            .paths
+            .iter()
+            .filter_map(|(template, item)| {
+                Some((
+                    normalize_template(template),
+                    (template.as_str(), item.as_object()?),
+                ))
+            })
+            .collect();
+        let mut matched = Vec::new();
+
+        for (template, old_item) in &self.old.paths {
+            self.path = Some(template.clone());
+            self.method = None;
+            let pointer = push_pointer("/paths", template);
+
+            // Path items can only reference external documents
+            let old_item = match old_item {
+                ReferenceObjectOr::Object(item) => item,
+                ReferenceObjectOr::ReferenceObject { reference } => {
+                    self.unresolved(&pointer, ResolveError::Unsupported(reference.clone()));
+                    continue;
+                }
+            };
+            let normalized = normalize_template(template);
+
+            match new_paths.get(&normalized) {
+                Some((new_template, new_item)) => {
+                    // Renaming a path variable is not a change, only their position matters
+                    let renames: IndexMap<&str, &str> =
+                        variables(template).zip(variables(new_template)).collect();
+                    self.compare_path_item(&pointer, &renames, old_item, new_item);
+                    matched.push(normalized);
+                }
+                None => self.push(&pointer, ChangeKind::PathRemoved, Severity::Breaking),
+            }
+        }
+
+        for (template, item) in &self.new.paths {
+            if let Some(reference) = item.as_reference() {
+                self.path = Some(template.clone());
+                self.method = None;
+                self.unresolved(
+                    &push_pointer("/paths", template),
+                    ResolveError::Unsupported(reference.to_string()),
+                );
+            }
+        }
+
+        for (normalized, (template, _)) in &new_paths {
+            if !matched.contains(normalized) {
+                self.path = Some(template.to_string());
+                self.method = None;
+                self.push(
+                    &push_pointer("/paths", template),
+                    ChangeKind::PathAdded,
+                    Severity::NonBreaking,
+                );
+            }
+        }
+
+        self.path = None;
+        self.method = None;
+    }
+
+    fn compare_path_item(
+        &mut self,
+        pointer: &str,
+        renames: &IndexMap<&str, &str>,
+        old_item: &'a PathsItemObject,
+        new_item: &'a PathsItemObject,
+    ) {
+        for (method, old_operation) in old_item.operations() {
+            let operation_pointer = push_pointer(pointer, &method.as_str().to_lowercase());
+            self.method = Some(method.clone());
+
+            match new_item.operation(&method) {
+                Some(new_operation) => self.compare_operation(
+                    &operation_pointer,
+                    renames,
+                    (old_item, old_operation),
+                    (new_item, new_operation),
+                ),
+                None => self.push(
+                    &operation_pointer,
+                    ChangeKind::OperationRemoved,
+                    Severity::Breaking,
+                ),
+            }
+        }
+
+        for (method, _) in new_item.operations() {
+            if old_item.operation(&method).is_none() {
+                let operation_pointer = push_pointer(pointer, &method.as_str().to_lowercase());
+                self.method = Some(method);
+                self.push(
+                    &operation_pointer,
+                    ChangeKind::OperationAdded,
+                    Severity::NonBreaking,
+                );
+            }
+        }
+    }
+
+    fn compare_operation(
+        &mut self,
+        pointer: &str,
+        renames: &IndexMap<&str, &str>,
+        (old_item, old): (&'a PathsItemObject, &'a OperationObject),
+        (new_item, new): (&'a PathsItemObject, &'a OperationObject),
+    ) {
+        if !old.deprecated && new.deprecated {
+            self.push(
+                pointer,
+                ChangeKind::OperationDeprecated,
+                Severity::NonBreaking,
+            );
+        }
+
+        // The path item is the parent of the operation
+        let item_pointer = &pointer[..pointer.rfind('/').unwrap_or(0)];
+        let old_parameters = self
+            .parameters(self.old, item_pointer, old_item, pointer, old)
+            .into_iter()
+            .map(|((location, name), parameter)| match location {
+                ParameterLocation::Path => {
+                    let name = renames
+                        .get(name.as_str())
+                        .map_or(name, |new| new.to_string());
+                    ((location, name), parameter)
+                }
+                _ => ((location, name), parameter),
+            })
+            .collect();
+        let new_parameters = self.parameters(self.new, item_pointer, new_item, pointer, new);
+        self.compare_parameters(&old_parameters, &new_parameters);
+
+        self.compare_request_body(pointer, old, new);
+        self.compare_responses(pointer, old, new);
+        self.compare_security(pointer, old, new);
+    }
+
+    fn compare_parameters(&mut self, old: &Parameters<'a>, new: &Parameters<'a>) {
+        for ((location, name), (pointer, old_parameter)) in old {
+            let location = *location;
+            let new_parameter = match new.get(&(location, name.clone())) {
+                Some((_, parameter)) => parameter,
+                None => {
+                    self.push(
+                        pointer,
+                        ChangeKind::ParameterRemoved {
+                            location,
+                            name: old_parameter.name().to_string(),
+                        },
+                        Severity::NonBreaking,
+                    );
+                    continue;
+                }
+            };
+
+            match (old_parameter.is_required(), new_parameter.is_required()) {
+                (false, true) => self.push(
+                    pointer,
+                    ChangeKind::ParameterRequired {
+                        location,
+                        name: new_parameter.name().to_string(),
+                    },
+                    Severity::Breaking,
+                ),
+                (true, false) => self.push(
+                    pointer,
+                    ChangeKind::ParameterOptional {
+                        location,
+                        name: new_parameter.name().to_string(),
+                    },
+                    Severity::NonBreaking,
+                ),
+                _ => {}
+            }
+
+            self.compare_schema_or_content(
+                pointer,
+                &old_parameter.fixed_fields().schema,
+                &new_parameter.fixed_fields().schema,
+                Direction::Request,
+            );
+        }
+
+        for (key, (pointer, new_parameter)) in new {
+            if !old.contains_key(key) {
+                let required = new_parameter.is_required();
+                self.push(
+                    pointer,
+                    ChangeKind::ParameterAdded {
+                        location: key.0,
+                        name: new_parameter.name().to_string(),
+                        required,
+                    },
+                    if required {
+                        Severity::Breaking
+                    } else {
+                        Severity::NonBreaking
+                    },
+                );
+            }
+        }
+    }
+
+    fn compare_request_body(
+        &mut self,
+        pointer: &str,
+        old: &'a OperationObject,
+        new: &'a OperationObject,
+    ) {
+        let pointer = push_pointer(pointer, "requestBody");
+        let old_body = match &old.request_body {
+            Some(body) => match self.resolve(&pointer, body, self.old) {
+                Some(body) => Some(body),
+                None => return,
+            },
+            None => None,
+        };
+        let new_body = match &new.request_body {
+            Some(body) => match self.resolve(&pointer, body, self.new) {
+                Some(body) => Some(body),
+                None => return,
+            },
+            None => None,
+        };
+
+        match (old_body, new_body) {
+            (None, None) => {}
+            (None, Some(new_body)) => self.push(
+                &pointer,
+                ChangeKind::RequestBodyAdded {
+                    required: new_body.required,
+                },
+                if new_body.required {
+                    Severity::Breaking
+                } else {
+                    Severity::NonBreaking
+                },
+            ),
+            (Some(_), None) => {
+                self.push(&pointer, ChangeKind::RequestBodyRemoved, Severity::Breaking)
+            }
+            (Some(old_body), Some(new_body)) => {
+                match (old_body.required, new_body.required) {
+                    (false, true) => self.push(
+                        &pointer,
+                        ChangeKind::RequestBodyRequired,
+                        Severity::Breaking,
+                    ),
+                    (true, false) => self.push(
+                        &pointer,
+                        ChangeKind::RequestBodyOptional,
+                        Severity::NonBreaking,
+                    ),
+                    _ => {}
+                }
+
+                self.compare_content(
+                    &push_pointer(&pointer, "content"),
+                    Some(&old_body.content),
+                    Some(&new_body.content),
+                    Direction::Request,
+                );
+            }
+        }
+    }
+
+    fn compare_responses(
+        &mut self,
+        pointer: &str,
+        old: &'a OperationObject,
+        new: &'a OperationObject,
+    ) {
+        let pointer = push_pointer(pointer, "responses");
+        let old_responses = self.responses(&pointer, self.old, old);
+        let new_responses = self.responses(&pointer, self.new, new);
+
+        for (status, old_response) in &old_responses {
+            let response_pointer = push_pointer(&pointer, status);
+
+            match new_responses.get(status) {
+                Some(new_response) => {
+                    self.compare_response(&response_pointer, old_response, new_response)
+                }
+                None => self.push(
+                    &response_pointer,
+                    ChangeKind::ResponseRemoved(status.clone()),
+                    Severity::Breaking,
+                ),
+            }
+        }
+
+        for status in new_responses.keys() {
+            if !old_responses.contains_key(status) {
+                self.push(
+                    &push_pointer(&pointer, status),
+                    ChangeKind::ResponseAdded(status.clone()),
+                    Severity::NonBreaking,
+                );
+            }
+        }
+    }
+
+    fn compare_response(
+        &mut self,
+        pointer: &str,
+        old: &'a ResponseObject,
+        new: &'a ResponseObject,
+    ) {
+        let old_headers = self.headers(pointer, self.old, old);
+        let new_headers = self.headers(pointer, self.new, new);
+
+        for (name, (original, old_header)) in &old_headers {
+            let header_pointer = push_pointer(&push_pointer(pointer, "headers"), original);
+
+            let new_header = match new_headers.get(name) {
+                Some((_, header)) => header,
+                None => {
+                    self.push(
+                        &header_pointer,
+                        ChangeKind::HeaderRemoved(original.to_string()),
+                        Severity::Breaking,
+                    );
+                    continue;
+                }
+            };
+
+            match (old_header.required, new_header.required) {
+                (false, true) => self.push(
+                    &header_pointer,
+                    ChangeKind::HeaderRequired(original.to_string()),
+                    Severity::NonBreaking,
+                ),
+                (true, false) => self.push(
+                    &header_pointer,
+                    ChangeKind::HeaderOptional(original.to_string()),
+                    Severity::Breaking,
+                ),
+                _ => {}
+            }
+
+            self.compare_schema_or_content(
+                &header_pointer,
+                &old_header.schema,
+                &new_header.schema,
+                Direction::Response,
+            );
+        }
+
+        for (name, (original, new_header)) in &new_headers {
+            if !old_headers.contains_key(name) {
+                self.push(
+                    &push_pointer(&push_pointer(pointer, "headers"), original),
+                    ChangeKind::HeaderAdded {
+                        name: original.to_string(),
+                        required: new_header.required,
+                    },
+                    Severity::NonBreaking,
+                );
+            }
+        }
+
+        self.compare_content(
+            &push_pointer(pointer, "content"),
+            old.content.as_ref(),
+            new.content.as_ref(),
+            Direction::Response,
+        );
+    }
+
+    /// Compares the security requirements of an operation, unless both versions inherit the ones of the document which are compared once by [`Self::compare_global_security`].
+    fn compare_security(
+        &mut self,
+        pointer: &str,
+        old: &'a OperationObject,
+        new: &'a OperationObject,
+    ) {
+        if old.security.is_none() && new.security.is_none() {
+            return;
+        }
+
+        let pointer = if new.security.is_some() {
+            push_pointer(pointer, "security")
+        } else {
+            String::from("/security")
+        };
+        self.compare_requirements(&pointer, security(self.old, old), security(self.new, new));
+    }
+
+    /// Compares the security requirements of the documents, which apply to every operation that doesn't declare its own.
+    fn compare_global_security(&mut self) {
+        self.compare_requirements(
+            "/security",
+            self.old.security.as_deref().unwrap_or_default(),
+            self.new.security.as_deref().unwrap_or_default(),
+        );
+    }
+
+    /// Security requirements are alternatives: removing one breaks the clients using it, adding one only offers a new way in, unless the operation was public.
+    fn compare_requirements(
+        &mut self,
+        pointer: &str,
+        old_security: &[SecurityRequirementObject],
+        new_security: &[SecurityRequirementObject],
+    ) {
+        for requirement in old_security {
+            if !new_security.contains(requirement) {
+                self.push(
+                    pointer,
+                    ChangeKind::SecurityRequirementRemoved(requirement.clone()),
+                    Severity::Breaking,
+                );
+            }
+        }
+
+        let was_public = old_security.is_empty() || old_security.iter().any(|r| r.is_empty());
+        for requirement in new_security {
+            if !old_security.contains(requirement) {
+                self.push(
+                    pointer,
+                    ChangeKind::SecurityRequirementAdded(requirement.clone()),
+                    if was_public && !requirement.is_empty() {
+                        Severity::Breaking
+                    } else {
+                        Severity::NonBreaking
+                    },
+                );
+            }
+        }
+    }
+
+    fn compare_security_schemes(&mut self) {
+        let empty = IndexMap::new();
+        let old = self
+            .old
+            .components
+            .as_ref()
+            .map_or(&empty, |components| &components.security_schemes);
+        let new = self
+            .new
+            .components
+            .as_ref()
+            .map_or(&empty, |components| &components.security_schemes);
+
+        for (name, old_scheme) in old {
+            let pointer = push_pointer("/components/securitySchemes", name);
+
+            match new.get(name) {
+                Some(new_scheme) if new_scheme != old_scheme => self.push(
+                    &pointer,
+                    ChangeKind::SecuritySchemeChanged(name.clone()),
+                    Severity::Unknown,
+                ),
+                Some(_) => {}
+                None => self.push(
+                    &pointer,
+                    ChangeKind::SecuritySchemeRemoved(name.clone()),
+                    Severity::Breaking,
+                ),
+            }
+        }
+
+        for name in new.keys() {
+            if !old.contains_key(name) {
+                self.push(
+                    &push_pointer("/components/securitySchemes", name),
+                    ChangeKind::SecuritySchemeAdded(name.clone()),
+                    Severity::NonBreaking,
+                );
+            }
+        }
+    }
+
+    fn compare_schema_or_content(
+        &mut self,
+        pointer: &str,
+        old: &'a SchemaOrContentObject,
+        new: &'a SchemaOrContentObject,
+        direction: Direction,
+    ) {
+        match (old, new) {
+            (SchemaOrContentObject::Schema(old), SchemaOrContentObject::Schema(new)) => {
+                self.compare_schema(&push_pointer(pointer, "schema"), old, new, direction)
+            }
+            (SchemaOrContentObject::Content(old), SchemaOrContentObject::Content(new)) => self
+                .compare_content(
+                    &push_pointer(pointer, "content"),
+                    Some(old),
+                    Some(new),
+                    direction,
+                ),
+            _ => self.push(
+                pointer,
+                ChangeKind::ConstraintChanged {
+                    keyword: "schema",
+                    old: None,
+                    new: None,
+                },
+                Severity::Unknown,
+            ),
+        }
+    }
+
+    fn compare_content(
+        &mut self,
+        pointer: &str,
+        old: Option<&'a ContentMap>,
+        new: Option<&'a ContentMap>,
+        direction: Direction,
+    ) {
+        for (media_type, old_media) in old.into_iter().flatten() {
+            let media_pointer = push_pointer(pointer, &media_type.to_string());
+
+            let new_media = match new.and_then(|new| new.get(media_type)) {
+                Some(media) => media,
+                None => {
+                    self.push(
+                        &media_pointer,
+                        ChangeKind::MediaTypeRemoved(media_type.clone()),
+                        Severity::Breaking,
+                    );
+                    continue;
+                }
+            };
+
+            let schema_pointer = push_pointer(&media_pointer, "schema");
+            match (&old_media.schema, &new_media.schema) {
+                (Some(old), Some(new)) => self.compare_schema(&schema_pointer, old, new, direction),
+                (None, Some(_)) => self.tightened(&schema_pointer, direction, "schema", None, None),
+                (Some(_), None) => self.loosened(&schema_pointer, direction, "schema", None, None),
+                (None, None) => {}
+            }
+        }
+
+        for media_type in new.into_iter().flat_map(ContentMap::keys) {
+            if !old.is_some_and(|old| old.contains_key(media_type)) {
+                self.push(
+                    &push_pointer(pointer, &media_type.to_string()),
+                    ChangeKind::MediaTypeAdded(media_type.clone()),
+                    Severity::NonBreaking,
+                );
+            }
+        }
+    }
+
+    fn compare_schema(
+        &mut self,
+        pointer: &str,
+        old: &'a ReferenceObjectOr<SchemaObject>,
+        new: &'a ReferenceObjectOr<SchemaObject>,
+        direction: Direction,
+    ) {
+        let old_schema = self.resolve(pointer, old, self.old);
+        let new_schema = self.resolve(pointer, new, self.new);
+        let (old_schema, new_schema) = match (old_schema, new_schema) {
+            (Some(old), Some(new)) => (old, new),
+            _ => return,
+        };
+
+        match (old.as_reference(), new.as_reference()) {
+            (Some(old_reference), Some(new_reference)) => {
+                let pair = (old_reference, new_reference);
+                if self.references.contains(&pair) {
+                    return;
+                }
+
+                self.references.push(pair);
+                self.compare_schema_object(pointer, old_schema, new_schema, direction);
+                self.references.pop();
+            }
+            _ => self.compare_schema_object(pointer, old_schema, new_schema, direction),
+        }
+    }
+
+    fn compare_schema_object(
+        &mut self,
+        pointer: &str,
+        old: &'a SchemaObject,
+        new: &'a SchemaObject,
+        direction: Direction,
+    ) {
+        let type_value = |schema_type: Option<SchemaType>| {
+            schema_type.map(|schema_type| Value::from(schema_type.as_str()))
+        };
+        match (old.schema_type, new.schema_type) {
+            (old_type, new_type) if old_type == new_type => {}
+            (Some(SchemaType::Integer), Some(SchemaType::Number)) | (Some(_), None) => self
+                .loosened(
+                    pointer,
+                    direction,
+                    "type",
+                    type_value(old.schema_type),
+                    type_value(new.schema_type),
+                ),
+            (Some(SchemaType::Number), Some(SchemaType::Integer)) | (None, Some(_)) => self
+                .tightened(
+                    pointer,
+                    direction,
+                    "type",
+                    type_value(old.schema_type),
+                    type_value(new.schema_type),
+                ),
+            (old_type, new_type) => {
+                // Nothing else is comparable once the type differs
+                self.push(
+                    pointer,
+                    ChangeKind::TypeChanged {
+                        old: old_type,
+                        new: new_type,
+                    },
+                    Severity::Breaking,
+                );
+                return;
+            }
+        }
+
+        self.compare_flag(
+            pointer,
+            direction,
+            "nullable",
+            old.nullable,
+            new.nullable,
+            false,
+        );
+        self.compare_keyword(pointer, direction, "format", &old.format, &new.format);
+        self.compare_enum(pointer, direction, old, new);
+
+        self.compare_upper_bound(pointer, direction, "maximum", old.maximum, new.maximum);
+        self.compare_flag(
+            pointer,
+            direction,
+            "exclusiveMaximum",
+            old.exclusive_maximum.unwrap_or(false),
+            new.exclusive_maximum.unwrap_or(false),
+            true,
+        );
+        self.compare_lower_bound(pointer, direction, "minimum", old.minimum, new.minimum);
+        self.compare_flag(
+            pointer,
+            direction,
+            "exclusiveMinimum",
+            old.exclusive_minimum.unwrap_or(false),
+            new.exclusive_minimum.unwrap_or(false),
+            true,
+        );
+        self.compare_multiple_of(pointer, direction, old, new);
+
+        self.compare_upper_bound(
+            pointer,
+            direction,
+            "maxLength",
+            old.max_length,
+            new.max_length,
+        );
+        self.compare_lower_bound(
+            pointer,
+            direction,
+            "minLength",
+            old.min_length,
+            new.min_length,
+        );
+        self.compare_keyword(pointer, direction, "pattern", &old.pattern, &new.pattern);
+
+        self.compare_upper_bound(pointer, direction, "maxItems", old.max_items, new.max_items);
+        self.compare_lower_bound(pointer, direction, "minItems", old.min_items, new.min_items);
+        self.compare_flag(
+            pointer,
+            direction,
+            "uniqueItems",
+            old.unique_items,
+            new.unique_items,
+            true,
+        );
+        match (&old.items, &new.items) {
+            (Some(old_items), Some(new_items)) => self.compare_schema(
+                &push_pointer(pointer, "items"),
+                old_items,
+                new_items,
+                direction,
+            ),
+            (None, Some(_)) => self.tightened(pointer, direction, "items", None, None),
+            (Some(_), None) => self.loosened(pointer, direction, "items", None, None),
+            (None, None) => {}
+        }
+
+        self.compare_upper_bound(
+            pointer,
+            direction,
+            "maxProperties",
+            old.max_properties,
+            new.max_properties,
+        );
+        self.compare_lower_bound(
+            pointer,
+            direction,
+            "minProperties",
+            old.min_properties,
+            new.min_properties,
+        );
+        self.compare_required(pointer, direction, old, new);
+        self.compare_properties(pointer, direction, old, new);
+        self.compare_additional_properties(pointer, direction, old, new);
+
+        self.compare_composition(pointer, direction, "allOf", &old.all_of, &new.all_of, true);
+        self.compare_composition(pointer, direction, "oneOf", &old.one_of, &new.one_of, false);
+        self.compare_composition(pointer, direction, "anyOf", &old.any_of, &new.any_of, false);
+        if old.not != new.not {
+            self.push(
+                pointer,
+                ChangeKind::ConstraintChanged {
+                    keyword: "not",
+                    old: to_value(&old.not),
+                    new: to_value(&new.not),
+                },
+                Severity::Unknown,
+            );
+        }
+    }
+
+    fn compare_enum(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        old: &SchemaObject,
+        new: &SchemaObject,
+    ) {
+        match (&old.enumeration, &new.enumeration) {
+            (Some(old_values), Some(new_values)) => {
+                let removed: Vec<Value> = old_values
+                    .iter()
+                    .filter(|value| !new_values.contains(value))
+                    .cloned()
+                    .collect();
+                let added: Vec<Value> = new_values
+                    .iter()
+                    .filter(|value| !old_values.contains(value))
+                    .cloned()
+                    .collect();
+
+                if !removed.is_empty() {
+                    self.push(
+                        pointer,
+                        ChangeKind::EnumValuesRemoved(removed),
+                        tightened(direction),
+                    );
+                }
+                if !added.is_empty() {
+                    self.push(
+                        pointer,
+                        ChangeKind::EnumValuesAdded(added),
+                        loosened(direction),
+                    );
+                }
+            }
+            (None, Some(values)) => self.tightened(
+                pointer,
+                direction,
+                "enum",
+                None,
+                Some(Value::from(values.clone())),
+            ),
+            (Some(values), None) => self.loosened(
+                pointer,
+                direction,
+                "enum",
+                Some(Value::from(values.clone())),
+                None,
+            ),
+            (None, None) => {}
+        }
+    }
+
+    fn compare_multiple_of(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        old: &SchemaObject,
+        new: &SchemaObject,
+    ) {
+        let old_value = old.multiple_of.map(|value| Value::from(value.get()));
+        let new_value = new.multiple_of.map(|value| Value::from(value.get()));
+
+        match (old.multiple_of, new.multiple_of) {
+            (Some(old_multiple), Some(new_multiple)) if old_multiple != new_multiple => {
+                let (old_multiple, new_multiple) = (old_multiple.get(), new_multiple.get());
+                // Multiples of 6 are all multiples of 3, but not the other way around
+                if new_multiple.is_multiple_of(old_multiple) {
+                    self.tightened(pointer, direction, "multipleOf", old_value, new_value)
+                } else if old_multiple.is_multiple_of(new_multiple) {
+                    self.loosened(pointer, direction, "multipleOf", old_value, new_value)
+                } else {
+                    self.changed(pointer, "multipleOf", old_value, new_value)
+                }
+            }
+            (None, Some(_)) => self.tightened(pointer, direction, "multipleOf", None, new_value),
+            (Some(_), None) => self.loosened(pointer, direction, "multipleOf", old_value, None),
+            _ => {}
+        }
+    }
+
+    fn compare_required(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        old: &SchemaObject,
+        new: &SchemaObject,
+    ) {
+        let old_required = old.required.as_deref().unwrap_or_default();
+        let new_required = new.required.as_deref().unwrap_or_default();
+
+        for name in old_required {
+            if !new_required.contains(name) {
+                self.push(
+                    pointer,
+                    ChangeKind::PropertyOptional(name.clone()),
+                    loosened(direction),
+                );
+            }
+        }
+
+        for name in new_required {
+            if !old_required.contains(name) {
+                self.push(
+                    pointer,
+                    ChangeKind::PropertyRequired(name.clone()),
+                    tightened(direction),
+                );
+            }
+        }
+    }
+
+    fn compare_properties(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        old: &'a SchemaObject,
+        new: &'a SchemaObject,
+    ) {
+        let old_properties = old.properties.as_ref();
+        let new_properties = new.properties.as_ref();
+        let properties_pointer = push_pointer(pointer, "properties");
+
+        for (name, old_property) in old_properties.into_iter().flatten() {
+            let property_pointer = push_pointer(&properties_pointer, name);
+
+            match new_properties.and_then(|properties| properties.get(name)) {
+                Some(new_property) => {
+                    self.compare_schema(&property_pointer, old_property, new_property, direction)
+                }
+                None => {
+                    // Clients may still send the property unless unknown properties are rejected, but can't rely on receiving it anymore
+                    let closed = matches!(
+                        new.additional_properties,
+                        Some(AdditionalProperties::Allowed(false))
+                    );
+                    let severity = if direction == Direction::Request && closed {
+                        Severity::Breaking
+                    } else {
+                        loosened(direction)
+                    };
+                    self.push(
+                        &property_pointer,
+                        ChangeKind::PropertyRemoved(name.clone()),
+                        severity,
+                    );
+                }
+            }
+        }
+
+        for name in new_properties.into_iter().flat_map(IndexMap::keys) {
+            if !old_properties.is_some_and(|properties| properties.contains_key(name)) {
+                // Whether the property is required is reported separately
+                self.push(
+                    &push_pointer(&properties_pointer, name),
+                    ChangeKind::PropertyAdded(name.clone()),
+                    Severity::NonBreaking,
+                );
+            }
+        }
+    }
+
+    fn compare_additional_properties(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        old: &'a SchemaObject,
+        new: &'a SchemaObject,
+    ) {
+        let allowed = |additional: &Option<AdditionalProperties>| match additional {
+            None | Some(AdditionalProperties::Allowed(true)) => Some(true),
+            Some(AdditionalProperties::Allowed(false)) => Some(false),
+            Some(AdditionalProperties::Schema(_)) => None,
+        };
+        let old_value = to_value(&old.additional_properties);
+        let new_value = to_value(&new.additional_properties);
+
+        match (&old.additional_properties, &new.additional_properties) {
+            (
+                Some(AdditionalProperties::Schema(old_schema)),
+                Some(AdditionalProperties::Schema(new_schema)),
+            ) => self.compare_schema(
+                &push_pointer(pointer, "additionalProperties"),
+                old_schema,
+                new_schema,
+                direction,
+            ),
+            (old_additional, new_additional) => {
+                // Anything is looser than a schema, which is looser than nothing
+                let rank = |additional| match allowed(additional) {
+                    Some(true) => 2,
+                    None => 1,
+                    Some(false) => 0,
+                };
+
+                match rank(old_additional).cmp(&rank(new_additional)) {
+                    std::cmp::Ordering::Greater => self.tightened(
+                        pointer,
+                        direction,
+                        "additionalProperties",
+                        old_value,
+                        new_value,
+                    ),
+                    std::cmp::Ordering::Less => self.loosened(
+                        pointer,
+                        direction,
+                        "additionalProperties",
+                        old_value,
+                        new_value,
+                    ),
+                    std::cmp::Ordering::Equal => {}
+                }
+            }
+        }
+    }
+
+    /// Compares the subschemas of `allOf`, `oneOf` and `anyOf`, pairwise when their number didn't change.
+    /// `conjunctive` tells whether every subschema applies, in which case additional subschemas tighten the schema.
+    fn compare_composition(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: &'a Option<Vec<ReferenceObjectOr<SchemaObject>>>,
+        new: &'a Option<Vec<ReferenceObjectOr<SchemaObject>>>,
+        conjunctive: bool,
+    ) {
+        let old_schemas = old.as_deref().unwrap_or_default();
+        let new_schemas = new.as_deref().unwrap_or_default();
+        let keyword_pointer = push_pointer(pointer, keyword);
+
+        if old_schemas.len() == new_schemas.len() {
+            for (i, (old_schema, new_schema)) in old_schemas.iter().zip(new_schemas).enumerate() {
+                self.compare_schema(
+                    &push_pointer(&keyword_pointer, &i.to_string()),
+                    old_schema,
+                    new_schema,
+                    direction,
+                );
+            }
+            return;
+        }
+
+        let added = new_schemas
+            .iter()
+            .any(|schema| !old_schemas.contains(schema));
+        let removed = old_schemas
+            .iter()
+            .any(|schema| !new_schemas.contains(schema));
+        let (old_value, new_value) = (to_value(old), to_value(new));
+
+        match (added, removed) {
+            (true, false) if conjunctive => {
+                self.tightened(pointer, direction, keyword, old_value, new_value)
+            }
+            (true, false) => self.loosened(pointer, direction, keyword, old_value, new_value),
+            (false, true) if conjunctive => {
+                self.loosened(pointer, direction, keyword, old_value, new_value)
+            }
+            (false, true) => self.tightened(pointer, direction, keyword, old_value, new_value),
+            _ => self.changed(pointer, keyword, old_value, new_value),
+        }
+    }
+
+    fn compare_upper_bound<T: PartialOrd + Copy + Into<Value>>(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: Option<T>,
+        new: Option<T>,
+    ) {
+        match (old, new) {
+            (Some(old), Some(new)) if new < old => self.tightened(
+                pointer,
+                direction,
+                keyword,
+                Some(old.into()),
+                Some(new.into()),
+            ),
+            (Some(old), Some(new)) if new > old => self.loosened(
+                pointer,
+                direction,
+                keyword,
+                Some(old.into()),
+                Some(new.into()),
+            ),
+            (None, Some(new)) => {
+                self.tightened(pointer, direction, keyword, None, Some(new.into()))
+            }
+            (Some(old), None) => self.loosened(pointer, direction, keyword, Some(old.into()), None),
+            _ => {}
+        }
+    }
+
+    fn compare_lower_bound<T: PartialOrd + Copy + Into<Value>>(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: Option<T>,
+        new: Option<T>,
+    ) {
+        match (old, new) {
+            (Some(old), Some(new)) if new > old => self.tightened(
+                pointer,
+                direction,
+                keyword,
+                Some(old.into()),
+                Some(new.into()),
+            ),
+            (Some(old), Some(new)) if new < old => self.loosened(
+                pointer,
+                direction,
+                keyword,
+                Some(old.into()),
+                Some(new.into()),
+            ),
+            (None, Some(new)) => {
+                self.tightened(pointer, direction, keyword, None, Some(new.into()))
+            }
+            (Some(old), None) => self.loosened(pointer, direction, keyword, Some(old.into()), None),
+            _ => {}
+        }
+    }
+
+    /// Compares a boolean keyword, `tight` being the value that accepts fewer values.
+    fn compare_flag(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: bool,
+        new: bool,
+        tight: bool,
+    ) {
+        if old == new {
+            return;
+        }
+
+        if new == tight {
+            self.tightened(
+                pointer,
+                direction,
+                keyword,
+                Some(old.into()),
+                Some(new.into()),
+            )
+        } else {
+            self.loosened(
+                pointer,
+                direction,
+                keyword,
+                Some(old.into()),
+                Some(new.into()),
+            )
+        }
+    }
+
+    /// Compares a keyword whose values can't be ordered, like `pattern`: only adding or removing it has a known impact.
+    fn compare_keyword(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: &Option<String>,
+        new: &Option<String>,
+    ) {
+        let (old_value, new_value) = (old.clone().map(Value::from), new.clone().map(Value::from));
+
+        match (old, new) {
+            (Some(old), Some(new)) if old != new => {
+                self.changed(pointer, keyword, old_value, new_value)
+            }
+            (None, Some(_)) => self.tightened(pointer, direction, keyword, None, new_value),
+            (Some(_), None) => self.loosened(pointer, direction, keyword, old_value, None),
+            _ => {}
+        }
+    }
+
+    fn tightened(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: Option<Value>,
+        new: Option<Value>,
+    ) {
+        self.push(
+            pointer,
+            ChangeKind::ConstraintTightened { keyword, old, new },
+            tightened(direction),
+        );
+    }
+
+    fn loosened(
+        &mut self,
+        pointer: &str,
+        direction: Direction,
+        keyword: &'static str,
+        old: Option<Value>,
+        new: Option<Value>,
+    ) {
+        self.push(
+            pointer,
+            ChangeKind::ConstraintLoosened { keyword, old, new },
+            loosened(direction),
+        );
+    }
+
+    fn changed(
+        &mut self,
+        pointer: &str,
+        keyword: &'static str,
+        old: Option<Value>,
+        new: Option<Value>,
+    ) {
+        self.push(
+            pointer,
+            ChangeKind::ConstraintChanged { keyword, old, new },
+            Severity::Unknown,
+        );
+    }
+
+    /// The parameters applying to an operation, keyed by location and name, with header names lowercased since they are case insensitive.
+    /// Parameters of the operation override the ones of the path item.
+    fn parameters(
+        &mut self,
+        document: &'a OpenAPIObject,
+        item_pointer: &str,
+        item: &'a PathsItemObject,
+        operation_pointer: &str,
+        operation: &'a OperationObject,
+    ) -> Parameters<'a> {
+        let mut parameters = IndexMap::new();
+        let sources = [
+            (item_pointer, &item.parameters),
+            (operation_pointer, &operation.parameters),
+        ];
+
+        for (pointer, source) in IntoIterator::into_iter(sources) {
+            let pointer = push_pointer(pointer, "parameters");
+
+            for (i, parameter) in source.iter().flatten().enumerate() {
+                let parameter_pointer = push_pointer(&pointer, &i.to_string());
+                let parameter = match self.resolve(&parameter_pointer, parameter, document) {
+                    Some(parameter) => parameter,
+                    None => continue,
+                };
+                let name = match parameter.location() {
+                    ParameterLocation::Header => parameter.name().to_ascii_lowercase(),
+                    _ => parameter.name().to_string(),
+                };
+
+                parameters.insert((parameter.location(), name), (parameter_pointer, parameter));
+            }
+        }
+
+        parameters
+    }
+
+    /// The resolved responses of an operation, keyed as they appear in the document.
+    fn responses(
+        &mut self,
+        pointer: &str,
+        document: &'a OpenAPIObject,
+        operation: &'a OperationObject,
+    ) -> IndexMap<String, &'a ResponseObject> {
+        let declared = operation
+            .responses
+            .responses
+            .iter()
+            .map(|(key, response)| (key.to_string(), response))
+            .chain(
+                operation
+                    .responses
+                    .default
+                    .iter()
+                    .map(|response| (String::from("default"), response)),
+            );
+
+        let mut responses = IndexMap::new();
+        for (key, response) in declared {
+            if let Some(response) = self.resolve(&push_pointer(pointer, &key), response, document) {
+                responses.insert(key, response);
+            }
+        }
+        responses
+    }
+
+    /// The resolved headers of a response keyed by their lowercased name, along with their name as declared.
+    fn headers(
+        &mut self,
+        pointer: &str,
+        document: &'a OpenAPIObject,
+        response: &'a ResponseObject,
+    ) -> IndexMap<String, (&'a str, &'a HeaderObject)> {
+        let mut headers = IndexMap::new();
+        for (name, header) in response.headers.iter().flatten() {
+            let header_pointer = push_pointer(&push_pointer(pointer, "headers"), name);
+            if let Some(header) = self.resolve(&header_pointer, header, document) {
+                headers.insert(name.to_ascii_lowercase(), (name.as_str(), header));
+            }
+        }
+        headers
+    }
+}
+
+/// Accepting fewer values breaks clients sending them, but not clients receiving them.
+fn tightened(direction: Direction) -> Severity {
+    match direction {
+        Direction::Request => Severity::Breaking,
+        Direction::Response => Severity::NonBreaking,
+    }
+}
+
+/// Accepting more values breaks clients receiving them, but not clients sending them.
+fn loosened(direction: Direction) -> Severity {
+    match direction {
+        Direction::Request => Severity::NonBreaking,
+        Direction::Response => Severity::Breaking,
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: &Option<T>) -> Option<Value> {
+    value
+        .as_ref()
+        .and_then(|value| serde_json::to_value(value).ok())
+}
+
+/// Replaces the names of the variables of a path template, since they don't affect which requests match.
+pub(crate) fn normalize_template(template: &str) -> String {
+    let mut normalized = String::with_capacity(template.len());
+    let mut in_variable = false;
+
+    for c in template.chars() {
+        match c {
+            '{' => {
+                in_variable = true;
+                normalized.push_str("{}");
+            }
+            '}' => in_variable = false,
+            c if !in_variable => normalized.push(c),
+            _ => {}
+        }
+    }
+
+    normalized
+}
+
+/// The names of the variables of a path template, in order.
+fn variables(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| Some(rest.split_once('}')?.0))
+}
+
+/// The security requirements of an operation, which override the ones of the document.
+fn security<'a>(
+    document: &'a OpenAPIObject,
+    operation: &'a OperationObject,
+) -> &'a [SecurityRequirementObject] {
+    operation
+        .security
+        .as_deref()
+        .or(document.security.as_deref())
+        .unwrap_or_default()
+}
@@ -0,0 +1,7 @@
+mod change;
+mod changelog;
+mod compare;
+
+pub use change::*;
+pub use changelog::*;
+pub use compare::*;
@@ -4,7 +4,7 @@ use url::Url;
 use super::Extensions;
 
 /// Allows referencing an external resource for extended documentation.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ExternalDocumentationObject {
     /// A short description of the target documentation. [CommonMark syntax](https://spec.commonmark.org/) MAY be used for rich text representation.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -0,0 +1,330 @@
+use http::Method;
+use indexmap::IndexMap;
+
+use super::{
+    AdditionalProperties, ComponentKind, ComponentRef, ContentMap, OpenAPIObject, OperationObject,
+    ParameterObject, ReferenceObjectOr, ResponseKey, ResponsesObject, SchemaObject,
+    SchemaOrContentObject,
+};
+use crate::utils::pascal_case;
+
+impl OpenAPIObject {
+    /// Moves inline schemas describing objects with properties, or compositions with `allOf`, `oneOf` or `anyOf`, to `components.schemas` and replaces them with references, returning the components that were added.
+    ///
+    /// Schemas are extracted from request bodies, responses and parameters, of operations and components alike, and from the properties, items, additional properties and variants of other schemas.
+    /// Names are derived from where the schema was found, e.g. `CreateUserRequest`, `GetUserResponse`, `ListPets404Response` or `UserAddress` for the `address` property of `User`.
+    /// Operations without an `operationId` are named after their method and path, e.g. `GetUsersId`.
+    /// Schemas identical to one that is already a component are replaced with a reference to it, and conflicting names get a numeric suffix.
+    pub fn extract_inline_schemas(&mut self) -> Vec<ComponentRef> {
+        let components = self.components.get_or_insert_with(Default::default);
+        let mut extractor = Extractor {
+            schemas: components.schemas.take().unwrap_or_default(),
+            added: Vec::new(),
+        };
+
+        // Component schemas are taken out while their nested schemas are extracted next to them
+        for i in 0..extractor.schemas.len() {
+            let name = extractor.schemas.get_index(i).map(|(name, _)| name.clone());
+            let mut schema = std::mem::replace(&mut extractor.schemas[i], placeholder());
+
+            if let (Some(name), ReferenceObjectOr::Object(object)) = (name, &mut schema) {
+                extractor.extract_nested(object, &name);
+            }
+            extractor.schemas[i] = schema;
+        }
+
+        for (name, response) in components.responses.iter_mut().flatten() {
+            if let ReferenceObjectOr::Object(response) = response {
+                extractor.extract_content(response.content.as_mut(), &format!("{}Response", name));
+            }
+        }
+        for (name, request_body) in components.request_bodies.iter_mut().flatten() {
+            if let ReferenceObjectOr::Object(request_body) = request_body {
+                extractor
+                    .extract_content(Some(&mut request_body.content), &format!("{}Request", name));
+            }
+        }
+        for (name, parameter) in components.parameters.iter_mut().flatten() {
+            if let ReferenceObjectOr::Object(parameter) = parameter {
+                extractor.extract_parameter(parameter, &pascal_case(name));
+            }
+        }
+
+        for (template, path_item) in self.paths.iter_mut() {
+            if let ReferenceObjectOr::Object(path_item) = path_item {
+                let path_name = pascal_case(template);
+
+                for parameter in path_item.parameters.iter_mut().flatten() {
+                    if let ReferenceObjectOr::Object(parameter) = parameter {
+                        let name = format!("{}{}", path_name, pascal_case(parameter.name()));
+                        extractor.extract_parameter(parameter, &name);
+                    }
+                }
+
+                for (method, operation) in path_item.operations_mut() {
+                    let name = operation_name(template, &method, operation);
+                    extractor.extract_operation(operation, &name);
+                }
+            }
+        }
+
+        if !extractor.schemas.is_empty() {
+            components.schemas = Some(extractor.schemas);
+        }
+        if components.is_empty() {
+            self.components = None;
+        }
+
+        extractor.added
+    }
+}
+
+struct Extractor {
+    schemas: IndexMap<String, ReferenceObjectOr<SchemaObject>>,
+    added: Vec<ComponentRef>,
+}
+
+impl Extractor {
+    fn extract_operation(&mut self, operation: &mut OperationObject, name: &str) {
+        for parameter in operation.parameters.iter_mut().flatten() {
+            if let ReferenceObjectOr::Object(parameter) = parameter {
+                let name = format!("{}{}", name, pascal_case(parameter.name()));
+                self.extract_parameter(parameter, &name);
+            }
+        }
+
+        if let Some(ReferenceObjectOr::Object(request_body)) = &mut operation.request_body {
+            self.extract_content(Some(&mut request_body.content), &format!("{}Request", name));
+        }
+
+        self.extract_responses(&mut operation.responses, name);
+    }
+
+    fn extract_responses(&mut self, responses: &mut ResponsesObject, name: &str) {
+        let success = responses.responses.keys().position(|key| match key {
+            ResponseKey::Status(status) => status.is_success(),
+            ResponseKey::Range(range) => range.as_str() == "2XX",
+        });
+
+        for (i, (key, response)) in responses.responses.iter_mut().enumerate() {
+            if let ReferenceObjectOr::Object(response) = response {
+                let name = if Some(i) == success {
+                    format!("{}Response", name)
+                } else {
+                    format!("{}{}Response", name, key)
+                };
+                self.extract_content(response.content.as_mut(), &name);
+            }
+        }
+
+        if let Some(ReferenceObjectOr::Object(response)) = &mut responses.default {
+            self.extract_content(
+                response.content.as_mut(),
+                &format!("{}DefaultResponse", name),
+            );
+        }
+    }
+
+    fn extract_parameter(&mut self, parameter: &mut ParameterObject, name: &str) {
+        match &mut parameter.fixed_fields_mut().schema {
+            SchemaOrContentObject::Schema(schema) => self.extract(schema, name.to_string()),
+            SchemaOrContentObject::Content(content) => self.extract_content(Some(content), name),
+        }
+    }
+
+    fn extract_content(&mut self, content: Option<&mut ContentMap>, name: &str) {
+        for media_type in content.into_iter().flat_map(|content| content.values_mut()) {
+            if let Some(schema) = &mut media_type.schema {
+                self.extract(schema, name.to_string());
+            }
+        }
+    }
+
+    /// Replaces `schema` with a reference if it is worth a component, after extracting the schemas it holds.
+    fn extract(&mut self, schema: &mut ReferenceObjectOr<SchemaObject>, name: String) {
+        let object = match schema {
+            ReferenceObjectOr::Object(object) => object,
+            ReferenceObjectOr::ReferenceObject { .. } => return,
+        };
+
+        self.extract_nested(object, &name);
+
+        if is_extractable(object) {
+            let object = std::mem::replace(schema, placeholder());
+            *schema = ReferenceObjectOr::ReferenceObject {
+                reference: self.insert(name, object).to_reference(),
+            };
+        }
+    }
+
+    fn extract_nested(&mut self, schema: &mut SchemaObject, name: &str) {
+        for (property, schema) in schema.properties.iter_mut().flatten() {
+            self.extract(schema, format!("{}{}", name, pascal_case(property)));
+        }
+
+        if let Some(items) = &mut schema.items {
+            self.extract(items, format!("{}Item", name));
+        }
+
+        if let Some(AdditionalProperties::Schema(schema)) = &mut schema.additional_properties {
+            self.extract(schema, format!("{}Value", name));
+        }
+
+        let variants = schema.one_of.iter_mut().chain(&mut schema.any_of).flatten();
+        for (i, variant) in variants.enumerate() {
+            self.extract(variant, format!("{}Variant{}", name, i + 1));
+        }
+
+        // The members of `allOf` only make sense together, so only what they hold is extracted
+        for member in schema.all_of.iter_mut().flatten() {
+            if let ReferenceObjectOr::Object(member) = member {
+                self.extract_nested(member, name);
+            }
+        }
+    }
+
+    /// Adds `schema` to the components under `name`, or a free variant of it, unless an identical component exists.
+    fn insert(&mut self, name: String, schema: ReferenceObjectOr<SchemaObject>) -> ComponentRef {
+        if let Some((existing, _)) = self.schemas.iter().find(|(_, other)| **other == schema) {
+            return ComponentRef::new(ComponentKind::Schemas, existing.as_str());
+        }
+
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while self.schemas.contains_key(&unique) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+
+        self.schemas.insert(unique.clone(), schema);
+        let component = ComponentRef::new(ComponentKind::Schemas, unique);
+        self.added.push(component.clone());
+        component
+    }
+}
+
+fn is_extractable(schema: &SchemaObject) -> bool {
+    schema
+        .properties
+        .as_ref()
+        .is_some_and(|properties| !properties.is_empty())
+        || schema.all_of.is_some()
+        || schema.one_of.is_some()
+        || schema.any_of.is_some()
+}
+
+/// The name of an operation, from its `operationId` or else its method and path.
+fn operation_name(template: &str, method: &Method, operation: &OperationObject) -> String {
+    match &operation.operation_id {
+        Some(operation_id) => pascal_case(operation_id),
+        None => pascal_case(&format!("{} {}", method.as_str().to_lowercase(), template)),
+    }
+}
+
+/// Stands in for a schema while it is moved.
+fn placeholder() -> ReferenceObjectOr<SchemaObject> {
+    ReferenceObjectOr::ReferenceObject {
+        reference: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v3::OpenAPIObject;
+
+    #[test]
+    fn names() {
+        let mut document: OpenAPIObject = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Users, version: "1"}
+servers: []
+paths:
+  /users:
+    post:
+      operationId: createUser
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                name: {type: string}
+                address: {type: object, properties: {city: {type: string}}}
+      responses:
+        "201":
+          description: Created
+          content: {application/json: {schema: {$ref: "#/components/schemas/User"}}}
+  /users/{id}:
+    get:
+      parameters:
+        - name: filter
+          in: query
+          schema: {type: object, properties: {active: {type: boolean}}}
+      responses:
+        "200":
+          description: A user
+          content:
+            application/json:
+              schema: {type: object, properties: {tags: {type: array, items: {type: object, properties: {label: {type: string}}}}}}
+        "404":
+          description: Not found
+          content:
+            application/json:
+              schema: {type: object, properties: {message: {type: string}}}
+        default:
+          description: An error
+          content:
+            application/json:
+              schema: {type: object, properties: {code: {type: integer}}}
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        address: {type: object, properties: {street: {type: string}}}
+        pet:
+          oneOf:
+            - {type: object, properties: {barks: {type: boolean}}}
+            - {type: object, properties: {meows: {type: boolean}}}
+    Error: {type: object, properties: {message: {type: string}}}
+    CreateUserRequest: {type: string}
+"##,
+        )
+        .unwrap();
+
+        let added: Vec<String> = document
+            .extract_inline_schemas()
+            .into_iter()
+            .map(|component| component.name)
+            .collect();
+        assert_eq!(
+            added,
+            [
+                "UserAddress",
+                "UserPetVariant1",
+                "UserPetVariant2",
+                "UserPet",
+                "CreateUserRequestAddress",
+                // The name of an existing component gets a suffix
+                "CreateUserRequest2",
+                "GetUsersIdFilter",
+                "GetUsersIdResponseTagsItem",
+                "GetUsersIdResponse",
+                "GetUsersIdDefaultResponse",
+            ]
+        );
+
+        // The schema of the `404` response is identical to `Error`
+        let document = serde_json::to_value(&document).unwrap();
+        let responses = &document["paths"]["/users/{id}"]["get"]["responses"];
+        assert_eq!(
+            responses["404"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Error"
+        );
+        assert_eq!(
+            responses["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/GetUsersIdResponse"
+        );
+    }
+}
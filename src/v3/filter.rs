@@ -0,0 +1,317 @@
+use http::Method;
+use indexmap::IndexSet;
+use serde_json::Value;
+use std::fmt;
+
+use super::{
+    ComponentKind, ComponentRef, OpenAPIObject, OperationObject, ReferenceObjectOr, References,
+};
+
+type Predicate = Box<dyn Fn(&str, &Method, &OperationObject) -> bool>;
+
+/// Selects the operations of a document to keep, e.g. to publish the public subset of an internal API.
+///
+/// An operation is kept if it matches every criterion that is set: one of the [tags](Filter::with_tags), one of the [path globs](Filter::with_paths), one of the [methods](Filter::with_methods) and every [predicate](Filter::with_predicate).
+/// For instance `Filter::new().with_extension("x-internal", |value| value != Some(&Value::Bool(true)))` drops the operations marked with `x-internal: true`.
+#[derive(Default)]
+pub struct Filter {
+    tags: Vec<String>,
+    paths: Vec<String>,
+    methods: Vec<Method>,
+    predicates: Vec<Predicate>,
+}
+
+impl fmt::Debug for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Filter")
+            .field("tags", &self.tags)
+            .field("paths", &self.paths)
+            .field("methods", &self.methods)
+            .field("predicates", &self.predicates.len())
+            .finish()
+    }
+}
+
+impl Filter {
+    /// A filter keeping every operation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps operations having at least one of `tags`.
+    pub fn with_tags<T: Into<String>>(mut self, tags: impl IntoIterator<Item = T>) -> Self {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Keeps operations whose path template matches one of `globs`.
+    ///
+    /// In a glob, `*` matches anything but `/`, `**` matches anything and `?` matches a single character other than `/`, e.g. `/users/*` matches `/users/{id}` but not `/users/{id}/pets`.
+    pub fn with_paths<T: Into<String>>(mut self, globs: impl IntoIterator<Item = T>) -> Self {
+        self.paths.extend(globs.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods.extend(methods);
+        self
+    }
+
+    /// Keeps operations for which `predicate` returns `true`, given the path template, the method and the operation.
+    pub fn with_predicate(
+        mut self,
+        predicate: impl Fn(&str, &Method, &OperationObject) -> bool + 'static,
+    ) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// Keeps operations for which `predicate` returns `true`, given the value of their `name` extension if any.
+    pub fn with_extension(
+        self,
+        name: impl Into<String>,
+        predicate: impl Fn(Option<&Value>) -> bool + 'static,
+    ) -> Self {
+        let name = name.into();
+        self.with_predicate(move |_, _, operation| predicate(operation.extensions.get(&name)))
+    }
+
+    /// Whether the operation handling `method` requests to `path` is kept.
+    pub fn matches(&self, path: &str, method: &Method, operation: &OperationObject) -> bool {
+        let tags = operation.tags.as_deref().unwrap_or_default();
+
+        (self.tags.is_empty() || tags.iter().any(|tag| self.tags.contains(tag)))
+            && (self.paths.is_empty() || self.paths.iter().any(|glob| glob_matches(glob, path)))
+            && (self.methods.is_empty() || self.methods.contains(method))
+            && self
+                .predicates
+                .iter()
+                .all(|predicate| predicate(path, method, operation))
+    }
+
+    /// Removes the operations that don't match, then [prunes](prune) what is left unused.
+    pub fn apply(&self, document: &mut OpenAPIObject) {
+        for (path, path_item) in document.paths.iter_mut() {
+            if let ReferenceObjectOr::Object(path_item) = path_item {
+                let slots = [
+                    (Method::GET, &mut path_item.get),
+                    (Method::PUT, &mut path_item.put),
+                    (Method::POST, &mut path_item.post),
+                    (Method::DELETE, &mut path_item.delete),
+                    (Method::OPTIONS, &mut path_item.options),
+                    (Method::HEAD, &mut path_item.head),
+                    (Method::PATCH, &mut path_item.patch),
+                    (Method::TRACE, &mut path_item.trace),
+                ];
+
+                for (method, slot) in IntoIterator::into_iter(slots) {
+                    if slot
+                        .as_ref()
+                        .is_some_and(|operation| !self.matches(path, &method, operation))
+                    {
+                        *slot = None;
+                    }
+                }
+            }
+        }
+
+        document.paths.retain(|_, path_item| match path_item {
+            ReferenceObjectOr::Object(path_item) => path_item.operations().next().is_some(),
+            ReferenceObjectOr::ReferenceObject { .. } => true,
+        });
+
+        prune(document);
+    }
+}
+
+/// Removes the components that can't be reached from the paths of the document, and the tags that no operation uses.
+pub fn prune(document: &mut OpenAPIObject) {
+    let reachable = reachable_components(document);
+
+    if let Some(components) = &mut document.components {
+        components.retain(|component| reachable.contains(component));
+
+        if components.is_empty() {
+            document.components = None;
+        }
+    }
+
+    let mut used_tags = IndexSet::new();
+    for path_item in document.paths.values() {
+        if let ReferenceObjectOr::Object(path_item) = path_item {
+            for (_, operation) in path_item.operations() {
+                used_tags.extend(operation.tags.iter().flatten().cloned());
+            }
+        }
+    }
+
+    if let Some(tags) = &mut document.tags {
+        tags.retain(|tag| used_tags.contains(&tag.name));
+
+        if tags.is_empty() {
+            document.tags = None;
+        }
+    }
+}
+
+/// The components used by the paths of the document, directly or through other components, including the security schemes required by the document or its operations.
+pub fn reachable_components(document: &OpenAPIObject) -> IndexSet<ComponentRef> {
+    let mut pending = Vec::new();
+
+    document
+        .paths
+        .visit_references("/paths", &mut |_, reference| {
+            pending.extend(ComponentRef::parse(reference))
+        });
+
+    let operations = document
+        .paths
+        .values()
+        .filter_map(ReferenceObjectOr::as_object)
+        .flat_map(|path_item| path_item.operations().map(|(_, operation)| operation));
+    let requirements = document
+        .security
+        .iter()
+        .flatten()
+        .chain(operations.flat_map(|operation| operation.security.iter().flatten()));
+    for requirement in requirements {
+        pending.extend(
+            requirement
+                .keys()
+                .map(|name| ComponentRef::new(ComponentKind::SecuritySchemes, name.as_str())),
+        );
+    }
+
+    let mut reachable = IndexSet::new();
+    while let Some(component) = pending.pop() {
+        if reachable.contains(&component) {
+            continue;
+        }
+
+        if let Some(components) = &document.components {
+            components.visit_component_references(&component, &mut |_, reference| {
+                pending.extend(ComponentRef::parse(reference))
+            });
+        }
+        reachable.insert(component);
+    }
+
+    reachable
+}
+
+/// Matches a path template against a glob where `*` doesn't cross `/`, `**` does and `?` matches a single character.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    if let Some(rest) = glob.strip_prefix("**") {
+        return path
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(path.len()))
+            .any(|i| glob_matches(rest, &path[i..]));
+    }
+
+    if let Some(rest) = glob.strip_prefix('*') {
+        let segment = path.find('/').unwrap_or(path.len());
+        return path[..segment]
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(segment))
+            .any(|i| glob_matches(rest, &path[i..]));
+    }
+
+    let mut glob_chars = glob.chars();
+    let mut path_chars = path.chars();
+    match (glob_chars.next(), path_chars.next()) {
+        (None, None) => true,
+        (Some('?'), Some(c)) if c != '/' => glob_matches(glob_chars.as_str(), path_chars.as_str()),
+        (Some(expected), Some(c)) if expected == c && expected != '?' => {
+            glob_matches(glob_chars.as_str(), path_chars.as_str())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs() {
+        assert!(glob_matches("/users", "/users"));
+        assert!(!glob_matches("/users", "/users/{id}"));
+
+        assert!(glob_matches("/users/*", "/users/{id}"));
+        assert!(glob_matches("/users/*", "/users/"));
+        assert!(!glob_matches("/users/*", "/users/{id}/pets"));
+        assert!(glob_matches("/users/*/pets", "/users/{id}/pets"));
+        assert!(glob_matches("/users/{id}.*", "/users/{id}.json"));
+        assert!(!glob_matches("/users/*", "/users"));
+
+        assert!(glob_matches("/users/**", "/users/{id}/pets/{petId}"));
+        assert!(glob_matches("/users/**", "/users/"));
+        assert!(glob_matches("**/pets", "/users/{id}/pets"));
+        assert!(glob_matches("/**/pets/*", "/users/{id}/pets/{petId}"));
+        assert!(!glob_matches("**/pets", "/users/{id}/pets/{petId}"));
+
+        assert!(glob_matches("/v?/users", "/v1/users"));
+        assert!(!glob_matches("/v?/users", "/v10/users"));
+        assert!(!glob_matches("/users?", "/users/"));
+    }
+
+    #[test]
+    fn apply() {
+        let mut document: OpenAPIObject = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Users, version: "1"}
+servers: []
+tags: [{name: users}, {name: admin}]
+paths:
+  /users:
+    get:
+      tags: [users]
+      responses: {"200": {description: Users, content: {application/json: {schema: {$ref: "#/components/schemas/Users"}}}}}
+  /users/{id}:
+    get:
+      tags: [users]
+      responses: {"200": {description: A user}}
+    delete:
+      tags: [admin]
+      responses: {"204": {description: Deleted}}
+  /admin/users/{id}/ban:
+    post:
+      tags: [users]
+      responses: {"204": {description: Banned}}
+components:
+  schemas:
+    Users: {type: array, items: {$ref: "#/components/schemas/User"}}
+    User: {type: object}
+    Ban: {type: object}
+"##,
+        )
+        .unwrap();
+
+        Filter::new()
+            .with_paths(["/users", "/users/*"])
+            .with_methods([Method::GET, Method::DELETE])
+            .with_tags(["users"])
+            .apply(&mut document);
+
+        assert_eq!(
+            document.paths.keys().collect::<Vec<_>>(),
+            ["/users", "/users/{id}"]
+        );
+        let path_item = document.paths["/users/{id}"].as_object().unwrap();
+        assert!(path_item.get.is_some());
+        assert!(path_item.delete.is_none());
+
+        let tags: Vec<&str> = document
+            .tags
+            .iter()
+            .flatten()
+            .map(|tag| tag.name.as_str())
+            .collect();
+        assert_eq!(tags, ["users"]);
+        let schemas = document.components.unwrap().schemas.unwrap();
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), ["Users", "User"]);
+    }
+}
@@ -0,0 +1,735 @@
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::{collections::HashMap, fmt, num::NonZeroU32};
+
+use super::{
+    visit::visit_schemas_mut, AdditionalProperties, ComponentKind, ComponentRef, OpenAPIObject,
+    ReferenceObjectOr, SchemaObject, SchemaType,
+};
+use crate::utils::push_pointer;
+
+/// The reasons an `allOf` could not be flattened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlattenError {
+    /// The members disagree on `keyword` in a way a single schema can't express, e.g. `type: string` and `type: integer`, or bounds leaving no valid value.
+    Conflict {
+        pointer: String,
+        keyword: &'static str,
+    },
+    /// A member references something else than a component schema of this document.
+    UnresolvedReference { pointer: String, reference: String },
+    /// A member includes, directly or not, the schema being flattened.
+    Cycle { pointer: String, reference: String },
+}
+
+impl fmt::Display for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlattenError::Conflict { pointer, keyword } => write!(
+                f,
+                "the members of the `allOf` at `{}` have conflicting `{}`",
+                pointer, keyword
+            ),
+            FlattenError::UnresolvedReference { pointer, reference } => write!(
+                f,
+                "cannot resolve `{}` in the `allOf` at `{}`",
+                reference, pointer
+            ),
+            FlattenError::Cycle { pointer, reference } => write!(
+                f,
+                "`{}` includes itself through the `allOf` at `{}`",
+                reference, pointer
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+impl OpenAPIObject {
+    /// Replaces every schema using `allOf` with a single schema merging its members, resolving references to component schemas.
+    ///
+    /// `properties` and `required` are unioned, numeric, length, item and property count bounds are intersected, as are `enum`s, and `multipleOf`s are combined into their least common multiple.
+    /// Other keywords must be equal in every member that sets them, except for annotations like `title` and `description`, taken from the schema itself or else the first member setting them.
+    /// Schemas that can't be merged are reported and left as they are, like the schemas including them.
+    ///
+    /// A member with a [discriminator](super::DiscriminatorObject) is a base of a polymorphic schema: its discriminator is not copied into the component schemas including it, whose discriminating property is restricted to their own value instead, and the base's `mapping` is completed with them since they no longer reference it.
+    pub fn flatten_all_of(&mut self) -> Vec<FlattenError> {
+        let schemas = self
+            .components
+            .as_ref()
+            .and_then(|components| components.schemas.clone())
+            .unwrap_or_default();
+        let mut flattener = Flattener {
+            schemas: &schemas,
+            flattened: HashMap::new(),
+            in_progress: Vec::new(),
+            subtypes: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        for name in schemas.keys() {
+            let _ = flattener.component(name);
+        }
+        visit_schemas_mut(self, &mut |pointer, schema| {
+            if let ReferenceObjectOr::Object(schema) = schema {
+                flattener.flatten(schema, pointer, None);
+            }
+        });
+
+        let Flattener {
+            mut flattened,
+            subtypes,
+            errors,
+            ..
+        } = flattener;
+        if let Some(target) = self
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.as_mut())
+        {
+            for (name, schema) in target.iter_mut() {
+                if let Some((flattened, _)) = flattened.remove(name) {
+                    *schema = ReferenceObjectOr::Object(flattened);
+                }
+            }
+
+            for (base, subtype) in subtypes {
+                let discriminator = match target.get_mut(&base) {
+                    Some(ReferenceObjectOr::Object(base)) => base.discriminator.as_mut(),
+                    _ => None,
+                };
+
+                if let Some(discriminator) = discriminator {
+                    if !discriminator
+                        .mapping
+                        .values()
+                        .any(|reference| points_to(reference, &subtype))
+                    {
+                        let reference = ComponentRef::new(ComponentKind::Schemas, subtype.as_str());
+                        discriminator
+                            .mapping
+                            .entry(subtype)
+                            .or_insert_with(|| reference.to_reference());
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Why a component schema can't be used as a member.
+enum Unavailable {
+    Missing,
+    Cycle,
+    /// It has an `allOf` that could not be flattened, which was already reported.
+    Failed,
+}
+
+struct Flattener<'s> {
+    schemas: &'s IndexMap<String, ReferenceObjectOr<SchemaObject>>,
+    /// The component schemas flattened so far, along with whether their own `allOf` could be flattened.
+    flattened: HashMap<String, (SchemaObject, bool)>,
+    in_progress: Vec<String>,
+    /// Pairs of component schemas with a discriminator and the component schemas including them.
+    subtypes: Vec<(String, String)>,
+    errors: Vec<FlattenError>,
+}
+
+impl Flattener<'_> {
+    fn component(&mut self, name: &str) -> Result<SchemaObject, Unavailable> {
+        if let Some((schema, complete)) = self.flattened.get(name) {
+            return if *complete {
+                Ok(schema.clone())
+            } else {
+                Err(Unavailable::Failed)
+            };
+        }
+        if self.in_progress.iter().any(|other| other == name) {
+            return Err(Unavailable::Cycle);
+        }
+
+        let schemas = self.schemas;
+        self.in_progress.push(name.to_string());
+        let result = match schemas.get(name) {
+            Some(ReferenceObjectOr::Object(schema)) => {
+                let mut schema = schema.clone();
+                let pointer = ComponentRef::new(ComponentKind::Schemas, name).to_pointer();
+                let complete = self.flatten(&mut schema, &pointer, Some(name));
+
+                self.flattened
+                    .insert(name.to_string(), (schema.clone(), complete));
+                if complete {
+                    Ok(schema)
+                } else {
+                    Err(Unavailable::Failed)
+                }
+            }
+            Some(ReferenceObjectOr::ReferenceObject { reference }) => {
+                match ComponentRef::parse(reference) {
+                    Some(target) if target.kind == ComponentKind::Schemas => {
+                        self.component(&target.name)
+                    }
+                    _ => Err(Unavailable::Missing),
+                }
+            }
+            None => Err(Unavailable::Missing),
+        };
+        self.in_progress.pop();
+
+        result
+    }
+
+    /// Flattens the `allOf` of `schema` and of the schemas it holds, returning whether its own could be.
+    fn flatten(&mut self, schema: &mut SchemaObject, pointer: &str, name: Option<&str>) -> bool {
+        self.flatten_nested(schema, pointer);
+
+        let all_of = match schema.all_of.take() {
+            Some(all_of) => all_of,
+            None => return true,
+        };
+
+        let mut members = Vec::with_capacity(all_of.len());
+        for (i, member) in all_of.iter().enumerate() {
+            match member {
+                ReferenceObjectOr::ReferenceObject { reference } => {
+                    let resolved = match ComponentRef::parse(reference) {
+                        Some(component) if component.kind == ComponentKind::Schemas => self
+                            .component(&component.name)
+                            .map(|member| (member, Some(component.name))),
+                        _ => Err(Unavailable::Missing),
+                    };
+
+                    match resolved {
+                        Ok(member) => members.push(member),
+                        Err(reason) => {
+                            let pointer = pointer.to_string();
+                            let reference = reference.clone();
+                            match reason {
+                                Unavailable::Missing => self
+                                    .errors
+                                    .push(FlattenError::UnresolvedReference { pointer, reference }),
+                                Unavailable::Cycle => {
+                                    self.errors.push(FlattenError::Cycle { pointer, reference })
+                                }
+                                Unavailable::Failed => {}
+                            }
+
+                            schema.all_of = Some(all_of);
+                            return false;
+                        }
+                    }
+                }
+                ReferenceObjectOr::Object(member) => {
+                    let mut member = member.clone();
+                    let member_pointer =
+                        push_pointer(&push_pointer(pointer, "allOf"), &i.to_string());
+
+                    if !self.flatten(&mut member, &member_pointer, None) {
+                        schema.all_of = Some(all_of);
+                        return false;
+                    }
+                    members.push((member, None));
+                }
+            }
+        }
+
+        let mut merged = schema.clone();
+        for (mut member, base) in members {
+            // Inline members keep their discriminator, only the one of a base is left out
+            if let Some(base) = base {
+                if let (Some(discriminator), Some(name)) = (member.discriminator.take(), name) {
+                    let value = discriminator
+                        .mapping
+                        .iter()
+                        .find(|(_, reference)| points_to(reference, name))
+                        .map_or(name, |(value, _)| value.as_str());
+
+                    restrict_property(&mut member, &discriminator.property_name, value);
+                    self.subtypes.push((base, name.to_string()));
+                }
+            }
+
+            if let Err(keyword) = merge(&mut merged, member) {
+                self.errors.push(FlattenError::Conflict {
+                    pointer: pointer.to_string(),
+                    keyword,
+                });
+                schema.all_of = Some(all_of);
+                return false;
+            }
+        }
+
+        *schema = merged;
+        true
+    }
+
+    fn flatten_nested(&mut self, schema: &mut SchemaObject, pointer: &str) {
+        let mut nested = Vec::new();
+
+        for (property, schema) in schema.properties.iter_mut().flatten() {
+            nested.push((
+                push_pointer(&push_pointer(pointer, "properties"), property),
+                schema,
+            ));
+        }
+        if let Some(items) = &mut schema.items {
+            nested.push((push_pointer(pointer, "items"), items));
+        }
+        if let Some(AdditionalProperties::Schema(schema)) = &mut schema.additional_properties {
+            nested.push((push_pointer(pointer, "additionalProperties"), schema));
+        }
+        if let Some(not) = &mut schema.not {
+            nested.push((push_pointer(pointer, "not"), not));
+        }
+        for (keyword, variants) in [("oneOf", &mut schema.one_of), ("anyOf", &mut schema.any_of)] {
+            for (i, variant) in variants.iter_mut().flatten().enumerate() {
+                nested.push((
+                    push_pointer(&push_pointer(pointer, keyword), &i.to_string()),
+                    variant,
+                ));
+            }
+        }
+
+        for (pointer, schema) in nested {
+            if let ReferenceObjectOr::Object(schema) = schema {
+                self.flatten(schema, &pointer, None);
+            }
+        }
+    }
+}
+
+/// Whether a discriminator mapping value points to the component schema `name`.
+fn points_to(reference: &str, name: &str) -> bool {
+    reference == name
+        || ComponentRef::parse(reference).is_some_and(|component| {
+            component.kind == ComponentKind::Schemas && component.name == name
+        })
+}
+
+/// Restricts the inline schema of `property` to `value`.
+fn restrict_property(schema: &mut SchemaObject, property: &str, value: &str) {
+    let property = schema
+        .properties
+        .as_mut()
+        .and_then(|properties| properties.get_mut(property));
+
+    if let Some(ReferenceObjectOr::Object(property)) = property {
+        let value = Value::String(value.to_string());
+        match &mut property.enumeration {
+            Some(values) => values.retain(|other| *other == value),
+            None => property.enumeration = Some(vec![value]),
+        }
+    }
+}
+
+/// Merges `source` into `target` so that `target` only accepts what both accepted, returning the conflicting keyword otherwise.
+fn merge(target: &mut SchemaObject, source: SchemaObject) -> Result<(), &'static str> {
+    let SchemaObject {
+        nullable,
+        discriminator,
+        read_only,
+        write_only,
+        xml,
+        external_docs,
+        example,
+        deprecated,
+        title,
+        multiple_of,
+        maximum,
+        exclusive_maximum,
+        minimum,
+        exclusive_minimum,
+        max_length,
+        min_length,
+        pattern,
+        max_items,
+        min_items,
+        unique_items,
+        max_properties,
+        min_properties,
+        required,
+        enumeration,
+        schema_type,
+        all_of: _,
+        one_of,
+        any_of,
+        not,
+        items,
+        properties,
+        additional_properties,
+        description,
+        format,
+        default,
+    } = source;
+
+    target.nullable &= nullable;
+    target.read_only |= read_only;
+    target.write_only |= write_only;
+    target.deprecated |= deprecated;
+    target.unique_items |= unique_items;
+
+    first(&mut target.xml, xml);
+    first(&mut target.external_docs, external_docs);
+    first(&mut target.example, example);
+    first(&mut target.title, title);
+    first(&mut target.description, description);
+    first(&mut target.default, default);
+
+    equal(&mut target.discriminator, discriminator, "discriminator")?;
+    equal(&mut target.format, format, "format")?;
+    equal(&mut target.pattern, pattern, "pattern")?;
+    equal(&mut target.one_of, one_of, "oneOf")?;
+    equal(&mut target.any_of, any_of, "anyOf")?;
+    equal(&mut target.not, not, "not")?;
+
+    match (target.schema_type, schema_type) {
+        (Some(SchemaType::Integer), Some(SchemaType::Number)) => {}
+        (Some(SchemaType::Number), Some(SchemaType::Integer)) => {
+            target.schema_type = Some(SchemaType::Integer)
+        }
+        _ => equal(&mut target.schema_type, schema_type, "type")?,
+    }
+
+    target.multiple_of = match (target.multiple_of, multiple_of) {
+        (Some(a), Some(b)) => Some(lcm(a, b).ok_or("multipleOf")?),
+        (a, b) => a.or(b),
+    };
+
+    bound(
+        (&mut target.maximum, &mut target.exclusive_maximum),
+        (maximum, exclusive_maximum),
+        |new, old| new < old,
+    );
+    bound(
+        (&mut target.minimum, &mut target.exclusive_minimum),
+        (minimum, exclusive_minimum),
+        |new, old| new > old,
+    );
+    if let (Some(minimum), Some(maximum)) = (target.minimum, target.maximum) {
+        let exclusive =
+            target.exclusive_minimum == Some(true) || target.exclusive_maximum == Some(true);
+        if minimum > maximum || (minimum == maximum && exclusive) {
+            return Err("minimum");
+        }
+    }
+
+    let counts = [
+        (
+            &mut target.min_length,
+            min_length,
+            &mut target.max_length,
+            max_length,
+            "minLength",
+        ),
+        (
+            &mut target.min_items,
+            min_items,
+            &mut target.max_items,
+            max_items,
+            "minItems",
+        ),
+        (
+            &mut target.min_properties,
+            min_properties,
+            &mut target.max_properties,
+            max_properties,
+            "minProperties",
+        ),
+    ];
+    for (target_min, min, target_max, max, keyword) in counts {
+        *target_min = (*target_min).max(min);
+        *target_max = match (*target_max, max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if let (Some(min), Some(max)) = (*target_min, *target_max) {
+            if min > max {
+                return Err(keyword);
+            }
+        }
+    }
+
+    for name in required.into_iter().flatten() {
+        let target = target.required.get_or_insert_with(Vec::new);
+        if !target.contains(&name) {
+            target.push(name);
+        }
+    }
+
+    target.enumeration = match (target.enumeration.take(), enumeration) {
+        (Some(mut values), Some(others)) => {
+            values.retain(|value| others.contains(value));
+            if values.is_empty() {
+                return Err("enum");
+            }
+            Some(values)
+        }
+        (values, others) => values.or(others),
+    };
+
+    match (&mut target.items, items) {
+        (Some(target), Some(items)) => merge_schemas(target, *items, "items")?,
+        (target, items) => first(target, items),
+    }
+
+    for (name, schema) in properties.into_iter().flatten() {
+        let properties = target.properties.get_or_insert_with(IndexMap::new);
+        match properties.get_mut(&name) {
+            Some(existing) => merge_schemas(existing, schema, "properties")?,
+            None => {
+                properties.insert(name, schema);
+            }
+        }
+    }
+
+    target.additional_properties =
+        match (target.additional_properties.take(), additional_properties) {
+            (Some(AdditionalProperties::Allowed(false)), _)
+            | (_, Some(AdditionalProperties::Allowed(false))) => {
+                Some(AdditionalProperties::Allowed(false))
+            }
+            (
+                Some(AdditionalProperties::Schema(mut target)),
+                Some(AdditionalProperties::Schema(schema)),
+            ) => {
+                merge_schemas(&mut target, *schema, "additionalProperties")?;
+                Some(AdditionalProperties::Schema(target))
+            }
+            (Some(AdditionalProperties::Schema(target)), _) => {
+                Some(AdditionalProperties::Schema(target))
+            }
+            (_, Some(AdditionalProperties::Schema(schema))) => {
+                Some(AdditionalProperties::Schema(schema))
+            }
+            (target, other) => target.or(other),
+        };
+
+    Ok(())
+}
+
+/// Merges two schemas that must both apply, which is only possible for equal references.
+fn merge_schemas(
+    target: &mut ReferenceObjectOr<SchemaObject>,
+    source: ReferenceObjectOr<SchemaObject>,
+    keyword: &'static str,
+) -> Result<(), &'static str> {
+    if *target == source {
+        return Ok(());
+    }
+
+    match (target, source) {
+        (ReferenceObjectOr::Object(target), ReferenceObjectOr::Object(source)) => {
+            merge(target, source)
+        }
+        _ => Err(keyword),
+    }
+}
+
+/// Keeps the value of `target` if there is one, or else takes `source`.
+fn first<T>(target: &mut Option<T>, source: Option<T>) {
+    if target.is_none() {
+        *target = source;
+    }
+}
+
+/// Takes `source` if `target` has no value, otherwise they must be equal.
+fn equal<T: PartialEq>(
+    target: &mut Option<T>,
+    source: Option<T>,
+    keyword: &'static str,
+) -> Result<(), &'static str> {
+    match (target.as_ref(), source) {
+        (Some(target), Some(source)) if *target != source => Err(keyword),
+        (None, source) => {
+            *target = source;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Keeps the stricter of two bounds, where `stricter(new, old)` tells whether `new` is.
+fn bound(
+    (target, target_exclusive): (&mut Option<i32>, &mut Option<bool>),
+    (source, source_exclusive): (Option<i32>, Option<bool>),
+    stricter: impl Fn(i32, i32) -> bool,
+) {
+    match (*target, source) {
+        (None, Some(_)) => {
+            *target = source;
+            *target_exclusive = source_exclusive;
+        }
+        (Some(old), Some(new)) if stricter(new, old) => {
+            *target = source;
+            *target_exclusive = source_exclusive;
+        }
+        (Some(old), Some(new)) if old == new && source_exclusive == Some(true) => {
+            *target_exclusive = Some(true);
+        }
+        _ => {}
+    }
+}
+
+fn lcm(a: NonZeroU32, b: NonZeroU32) -> Option<NonZeroU32> {
+    let (mut x, mut y) = (a.get(), b.get());
+    while y != 0 {
+        let remainder = x % y;
+        x = y;
+        y = remainder;
+    }
+
+    (a.get() / x).checked_mul(b.get()).and_then(NonZeroU32::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document(schemas: &str) -> OpenAPIObject {
+        let source = format!(
+            "openapi: 3.0.3\ninfo: {{title: Pets, version: '1'}}\nservers: []\npaths: {{}}\ncomponents:\n  schemas:\n{}",
+            schemas
+        );
+        serde_yaml::from_str(&source).unwrap()
+    }
+
+    fn schemas(document: &OpenAPIObject) -> Value {
+        serde_json::to_value(&document.components.as_ref().unwrap().schemas).unwrap()
+    }
+
+    #[test]
+    fn merge_members() {
+        let mut document = document(
+            r##"
+    Named:
+      type: object
+      required: [name]
+      properties: {name: {type: string, maxLength: 20}}
+    Pet:
+      description: A pet.
+      allOf:
+        - {$ref: "#/components/schemas/Named"}
+        - type: object
+          required: [age]
+          properties:
+            name: {type: string, maxLength: 10, minLength: 1}
+            age: {type: integer, multipleOf: 4}
+        - properties: {age: {type: integer, multipleOf: 6}}
+"##,
+        );
+
+        assert_eq!(document.flatten_all_of(), []);
+        let pet = &schemas(&document)["Pet"];
+        assert!(pet.get("allOf").is_none());
+        assert_eq!(pet["description"], "A pet.");
+        assert_eq!(pet["type"], "object");
+        assert_eq!(pet["required"], json!(["name", "age"]));
+        assert_eq!(pet["properties"]["name"]["maxLength"], 10);
+        assert_eq!(pet["properties"]["name"]["minLength"], 1);
+        assert_eq!(pet["properties"]["age"]["multipleOf"], 12);
+    }
+
+    #[test]
+    fn errors() {
+        let mut document = document(
+            r##"
+    Name: {type: string}
+    Conflict:
+      allOf:
+        - {$ref: "#/components/schemas/Name"}
+        - {type: integer}
+    Bounds:
+      allOf:
+        - {type: integer, minimum: 10}
+        - {type: integer, maximum: 5}
+    Missing:
+      allOf:
+        - {$ref: "#/components/schemas/Unknown"}
+    Tree:
+      properties:
+        node:
+          allOf:
+            - {$ref: "#/components/schemas/Tree"}
+"##,
+        );
+
+        assert_eq!(
+            document.flatten_all_of(),
+            [
+                FlattenError::Conflict {
+                    pointer: "/components/schemas/Conflict".to_string(),
+                    keyword: "type",
+                },
+                FlattenError::Conflict {
+                    pointer: "/components/schemas/Bounds".to_string(),
+                    keyword: "minimum",
+                },
+                FlattenError::UnresolvedReference {
+                    pointer: "/components/schemas/Missing".to_string(),
+                    reference: "#/components/schemas/Unknown".to_string(),
+                },
+                FlattenError::Cycle {
+                    pointer: "/components/schemas/Tree/properties/node".to_string(),
+                    reference: "#/components/schemas/Tree".to_string(),
+                },
+            ]
+        );
+        // Schemas that can't be flattened are left as they are
+        assert_eq!(
+            schemas(&document)["Conflict"]["allOf"][1]["type"],
+            "integer"
+        );
+    }
+
+    #[test]
+    fn discriminators() {
+        let mut document = document(
+            r##"
+    Pet:
+      type: object
+      required: [petType]
+      properties: {petType: {type: string}}
+      discriminator:
+        propertyName: petType
+        mapping: {dog: "#/components/schemas/Dog"}
+    Dog:
+      allOf:
+        - {$ref: "#/components/schemas/Pet"}
+        - properties: {barks: {type: boolean}}
+    Cat:
+      allOf:
+        - {$ref: "#/components/schemas/Pet"}
+        - properties: {meows: {type: boolean}}
+    Shape:
+      allOf:
+        - properties: {kind: {type: string}}
+          discriminator: {propertyName: kind, mapping: {}}
+        - required: [kind]
+"##,
+        );
+
+        assert_eq!(document.flatten_all_of(), []);
+        let schemas = schemas(&document);
+
+        // The base's discriminator isn't copied, its property is restricted instead
+        assert!(schemas["Dog"].get("discriminator").is_none());
+        assert_eq!(
+            schemas["Dog"]["properties"]["petType"]["enum"],
+            json!(["dog"])
+        );
+        assert_eq!(
+            schemas["Cat"]["properties"]["petType"]["enum"],
+            json!(["Cat"])
+        );
+        assert_eq!(
+            schemas["Pet"]["discriminator"]["mapping"],
+            json!({ "dog": "#/components/schemas/Dog", "Cat": "#/components/schemas/Cat" })
+        );
+
+        assert_eq!(schemas["Shape"]["discriminator"]["propertyName"], "kind");
+    }
+}
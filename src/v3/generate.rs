@@ -0,0 +1,622 @@
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Number, Value};
+
+use super::{
+    AdditionalProperties, ComponentRef, OpenAPIObject, ReferenceObjectOr, SchemaObject, SchemaType,
+};
+
+/// Generates example values from [Schema Objects](SchemaObject).
+///
+/// The `example`, then the `default`, then the first `enum` value of a schema is used when there is one.
+/// Otherwise a value is synthesized to satisfy its type, `format`, bounds, `pattern`, `required` properties and number of properties, using the first branch of `oneOf` and `anyOf`, named by the discriminator if any, and merging the parts of `allOf`.
+/// A reference met again while generating its own value is a cycle, ended by leaving the optional property out or by `null`.
+///
+/// By default the generated values are always the same, e.g. `0` for integers and `"string"` for strings.
+/// With a [seed](ExampleGenerator::with_seed) they are random instead, and branches and optional properties are chosen randomly, the same seed always giving the same values.
+#[derive(Debug, Clone, Default)]
+pub struct ExampleGenerator<'a> {
+    document: Option<&'a OpenAPIObject>,
+    seed: Option<u64>,
+}
+
+impl<'a> ExampleGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `document` to resolve references, without a document they are generated as `null`.
+    pub fn with_document(mut self, document: &'a OpenAPIObject) -> Self {
+        self.document = Some(document);
+        self
+    }
+
+    /// Generates random values from `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Generates a value of a schema or of the one it references.
+    pub fn generate(&self, schema: &ReferenceObjectOr<SchemaObject>) -> Value {
+        self.generation()
+            .reference_or_schema(schema)
+            .unwrap_or(Value::Null)
+    }
+
+    /// Generates a value of an inline schema.
+    pub fn generate_schema(&self, schema: &SchemaObject) -> Value {
+        self.generation().schema(schema).unwrap_or(Value::Null)
+    }
+
+    fn generation<'s>(&self) -> Generation<'s>
+    where
+        'a: 's,
+    {
+        Generation {
+            document: self.document,
+            rng: self.seed.map(Rng),
+            visiting: Vec::new(),
+        }
+    }
+}
+
+/// The state of the generation of a single value.
+struct Generation<'s> {
+    document: Option<&'s OpenAPIObject>,
+    rng: Option<Rng>,
+    /// The references whose value is being generated, to detect cycles.
+    visiting: Vec<&'s str>,
+}
+
+impl<'s> Generation<'s> {
+    /// The value of `schema`, or `None` if it is a reference in a cycle.
+    fn reference_or_schema(
+        &mut self,
+        schema: &'s ReferenceObjectOr<SchemaObject>,
+    ) -> Option<Value> {
+        let reference = match schema {
+            ReferenceObjectOr::Object(schema) => return self.schema(schema),
+            ReferenceObjectOr::ReferenceObject { reference } => reference.as_str(),
+        };
+        if self.visiting.contains(&reference) {
+            return None;
+        }
+
+        let resolved = match self.document.map(|document| schema.resolve(document)) {
+            Some(Ok(resolved)) => resolved,
+            _ => return Some(Value::Null),
+        };
+        self.visiting.push(reference);
+        let value = self.schema(resolved);
+        self.visiting.pop();
+        value
+    }
+
+    fn schema(&mut self, schema: &'s SchemaObject) -> Option<Value> {
+        if let Some(value) = schema.example.as_ref().or(schema.default.as_ref()) {
+            return Some(value.clone());
+        }
+        if let Some(values) = schema
+            .enumeration
+            .as_ref()
+            .filter(|values| !values.is_empty())
+        {
+            return Some(values[self.index(values.len())].clone());
+        }
+
+        let branches = schema
+            .one_of
+            .as_ref()
+            .or(schema.any_of.as_ref())
+            .filter(|branches| !branches.is_empty());
+        if let Some(branches) = branches {
+            // The chosen branch, or the next one if it is in a cycle
+            let start = self.index(branches.len());
+            for i in 0..branches.len() {
+                let branch = &branches[(start + i) % branches.len()];
+                if let Some(mut value) = self.reference_or_schema(branch) {
+                    discriminate(schema, branch, &mut value);
+                    return Some(value);
+                }
+            }
+            return None;
+        }
+
+        let mut value = match schema_type(schema) {
+            Some(schema_type) => self.typed(schema, schema_type)?,
+            None => Value::Null,
+        };
+        for part in schema.all_of.iter().flatten() {
+            value = merge(value, self.reference_or_schema(part)?);
+        }
+        Some(value)
+    }
+
+    fn typed(&mut self, schema: &'s SchemaObject, schema_type: SchemaType) -> Option<Value> {
+        Some(match schema_type {
+            SchemaType::Boolean => Value::Bool(self.chance()),
+            SchemaType::Integer => Value::from(self.integer(schema)),
+            SchemaType::Number => self.number(schema),
+            SchemaType::String => Value::String(self.string(schema)),
+            SchemaType::Array => self.array(schema)?,
+            SchemaType::Object => self.object(schema),
+        })
+    }
+
+    fn integer(&mut self, schema: &SchemaObject) -> i64 {
+        let (min, max) = integer_bounds(schema);
+        let target = match &mut self.rng {
+            Some(rng) => {
+                let low = if schema.minimum.is_some() || schema.maximum.is_none() {
+                    min.max(0).min(max)
+                } else {
+                    max.saturating_sub(100)
+                };
+                rng.between(low, max.min(low.saturating_add(100)))
+            }
+            None => 0i64.max(min).min(max),
+        };
+
+        match schema.multiple_of {
+            Some(multiple) => {
+                let multiple = i64::from(multiple.get());
+                let above = target.div_euclid(multiple) * multiple
+                    + if target.rem_euclid(multiple) == 0 {
+                        0
+                    } else {
+                        multiple
+                    };
+                if above <= max {
+                    above
+                } else {
+                    target.div_euclid(multiple) * multiple
+                }
+            }
+            None => target,
+        }
+    }
+
+    fn number(&mut self, schema: &SchemaObject) -> Value {
+        let (min, max) = integer_bounds(schema);
+        let rng = match &mut self.rng {
+            Some(rng) if schema.multiple_of.is_none() => rng,
+            // No integer lies between bounds less than one apart, e.g. exclusive 1 and 2
+            _ if min > max && schema.multiple_of.is_none() => {
+                let (low, high) = (schema.minimum.unwrap_or(0), schema.maximum.unwrap_or(0));
+                return Value::from((f64::from(low) + f64::from(high)) / 2.0);
+            }
+            _ => return Value::from(self.integer(schema) as f64),
+        };
+
+        let low = schema.minimum.map_or(0.0, f64::from);
+        let high = schema.maximum.map_or(low + 100.0, f64::from).max(low);
+        let low = schema.minimum.map_or(high - 100.0, |_| low).min(high);
+        // Strictly between the bounds, which satisfies them whether they are exclusive or not
+        let value = low + (high - low) * (rng.below(1000) as f64 + 1.0) / 1001.0;
+        // Rounding could reach an exclusive bound when they are close
+        let rounded = (value * 100.0).round() / 100.0;
+        let value = if rounded > low && rounded < high {
+            rounded
+        } else {
+            value
+        };
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+
+    fn string(&mut self, schema: &SchemaObject) -> String {
+        if let Some(value) = schema
+            .pattern
+            .as_deref()
+            .and_then(|pattern| self.matching(pattern))
+        {
+            return value;
+        }
+        if let Some(value) = schema
+            .format
+            .as_deref()
+            .and_then(|format| self.formatted(format))
+        {
+            return value;
+        }
+
+        let min = schema.min_length.unwrap_or(0) as usize;
+        let max = schema
+            .max_length
+            .map_or(usize::MAX, |max| max as usize)
+            .max(min);
+        match &mut self.rng {
+            Some(rng) => {
+                let len = rng.between(min.max(1).min(max) as i64, min.max(10).min(max) as i64);
+                (0..len)
+                    .map(|_| {
+                        let alphanumerics = b"abcdefghijklmnopqrstuvwxyz0123456789";
+                        alphanumerics[rng.below(alphanumerics.len() as u64) as usize] as char
+                    })
+                    .collect()
+            }
+            None => "string".chars().cycle().take(6.max(min).min(max)).collect(),
+        }
+    }
+
+    /// A value of one of the formats defined by the OpenAPI Specification or JSON Schema, or `None` if `format` is unknown.
+    fn formatted(&mut self, format: &str) -> Option<String> {
+        let rng = match &mut self.rng {
+            Some(rng) => rng,
+            None => {
+                return Some(
+                    match format {
+                        "date" => "2021-01-01",
+                        "date-time" => "2021-01-01T00:00:00Z",
+                        "time" => "00:00:00",
+                        "email" => "user@example.com",
+                        "uuid" => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+                        "uri" => "https://example.com/",
+                        "hostname" => "example.com",
+                        "ipv4" => "192.0.2.1",
+                        "ipv6" => "2001:db8::1",
+                        "byte" => "c3RyaW5n",
+                        _ => return None,
+                    }
+                    .to_string(),
+                )
+            }
+        };
+
+        let date = |rng: &mut Rng| {
+            format!(
+                "{:04}-{:02}-{:02}",
+                rng.between(1970, 2030),
+                rng.between(1, 12),
+                rng.between(1, 28)
+            )
+        };
+        let time = |rng: &mut Rng| {
+            format!(
+                "{:02}:{:02}:{:02}",
+                rng.between(0, 23),
+                rng.between(0, 59),
+                rng.between(0, 59)
+            )
+        };
+        Some(match format {
+            "date" => date(rng),
+            "date-time" => format!("{}T{}Z", date(rng), time(rng)),
+            "time" => time(rng),
+            "email" => format!("user{}@example.com", rng.below(1000)),
+            "uuid" => {
+                let hex: String = (0..32).map(|_| format!("{:x}", rng.below(16))).collect();
+                format!(
+                    "{}-{}-4{}-a{}-{}",
+                    &hex[..8],
+                    &hex[8..12],
+                    &hex[13..16],
+                    &hex[17..20],
+                    &hex[20..]
+                )
+            }
+            "uri" => format!("https://example.com/{}", rng.below(1000)),
+            "hostname" => format!("host{}.example.com", rng.below(1000)),
+            "ipv4" => format!("192.0.2.{}", rng.between(1, 254)),
+            "ipv6" => format!("2001:db8::{:x}", rng.between(1, 0xffff)),
+            "byte" => "c3RyaW5n".to_string(),
+            _ => return None,
+        })
+    }
+
+    /// A string matching `pattern`, or `None` if it isn't a valid regular expression.
+    fn matching(&mut self, pattern: &str) -> Option<String> {
+        let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+        let mut value = String::new();
+        self.write_matching(&hir, &mut value);
+        Some(value)
+    }
+
+    fn write_matching(&mut self, hir: &Hir, value: &mut String) {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {}
+            HirKind::Literal(literal) => value.push_str(&String::from_utf8_lossy(&literal.0)),
+            HirKind::Class(class) => {
+                let ranges: Vec<(char, char)> = match class {
+                    Class::Unicode(class) => class
+                        .ranges()
+                        .iter()
+                        .map(|range| (range.start(), range.end()))
+                        .collect(),
+                    Class::Bytes(class) => class
+                        .ranges()
+                        .iter()
+                        .map(|range| (char::from(range.start()), char::from(range.end())))
+                        .collect(),
+                };
+                // Printable ASCII characters are preferred, as they read better
+                let contains = |c: char| {
+                    ranges
+                        .iter()
+                        .any(|(start, end)| (*start..=*end).contains(&c))
+                };
+                let printable: Vec<char> = "aA0"
+                    .chars()
+                    .chain(' '..='~')
+                    .filter(|c| contains(*c))
+                    .collect();
+                let c = if printable.is_empty() {
+                    ranges.first().map(|(start, _)| *start)
+                } else {
+                    Some(printable[self.index(printable.len())])
+                };
+                value.extend(c);
+            }
+            HirKind::Repetition(repetition) => {
+                let min = repetition.min;
+                let max = repetition.max.unwrap_or(u32::MAX);
+                let count = match &mut self.rng {
+                    Some(rng) => {
+                        rng.between(min.into(), max.min(min.saturating_add(5)).into()) as u32
+                    }
+                    None => min.max(1).min(max),
+                };
+                for _ in 0..count {
+                    self.write_matching(&repetition.sub, value);
+                }
+            }
+            HirKind::Capture(capture) => self.write_matching(&capture.sub, value),
+            HirKind::Concat(hirs) => {
+                for hir in hirs {
+                    self.write_matching(hir, value);
+                }
+            }
+            HirKind::Alternation(hirs) => {
+                let hir = &hirs[self.index(hirs.len())];
+                self.write_matching(hir, value);
+            }
+        }
+    }
+
+    /// The items of an array, or `None` if they are in a cycle and there must be some.
+    fn array(&mut self, schema: &'s SchemaObject) -> Option<Value> {
+        let items = match &schema.items {
+            Some(items) => items,
+            None => return Some(Value::Array(Vec::new())),
+        };
+
+        let min = schema.min_items.unwrap_or(0);
+        let max = schema.max_items.unwrap_or(u32::MAX).max(min);
+        let count = match &mut self.rng {
+            Some(rng) => rng.between(min.into(), max.min(min + 3).into()) as usize,
+            None => min.max(1).min(max) as usize,
+        };
+
+        let mut values = Vec::new();
+        // Unique items are found by trying random values once the same ones are generated again
+        let mut attempts = 0;
+        while values.len() < count && attempts < count * 8 {
+            let value = match self.rng {
+                None if attempts > 0 && schema.unique_items => {
+                    let mut generation = Generation {
+                        document: self.document,
+                        rng: Some(Rng(attempts as u64)),
+                        visiting: self.visiting.clone(),
+                    };
+                    generation.reference_or_schema(items)
+                }
+                _ => self.reference_or_schema(items),
+            };
+            let value = match value {
+                Some(value) => value,
+                None if min == 0 => break,
+                None => return None,
+            };
+
+            attempts += 1;
+            if !schema.unique_items || !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        Some(Value::Array(values))
+    }
+
+    fn object(&mut self, schema: &'s SchemaObject) -> Value {
+        let required = schema.required.as_deref().unwrap_or_default();
+        let mut object = Map::new();
+
+        for (name, property) in schema.properties.iter().flatten() {
+            let is_required = required.contains(name);
+            if !is_required && self.rng.is_some() && !self.chance() {
+                continue;
+            }
+            match self.reference_or_schema(property) {
+                Some(value) => {
+                    object.insert(name.clone(), value);
+                }
+                None if is_required => {
+                    object.insert(name.clone(), Value::Null);
+                }
+                None => {}
+            }
+        }
+        for name in required {
+            object.entry(name.clone()).or_insert(Value::Null);
+        }
+
+        // Optional properties left out are added back first, then additional ones
+        let min = schema.min_properties.unwrap_or(0) as usize;
+        for (name, property) in schema.properties.iter().flatten() {
+            if object.len() >= min {
+                break;
+            }
+            if !object.contains_key(name) {
+                if let Some(value) = self.reference_or_schema(property) {
+                    object.insert(name.clone(), value);
+                }
+            }
+        }
+        match &schema.additional_properties {
+            Some(AdditionalProperties::Schema(additional)) => {
+                self.additional_properties(&mut object, min.max(1), Some(additional))
+            }
+            Some(AdditionalProperties::Allowed(false)) => {}
+            _ => self.additional_properties(&mut object, min, None),
+        }
+
+        // Then optional properties are left out, the last ones first
+        let max = schema.max_properties.map_or(usize::MAX, |max| max as usize);
+        while object.len() > max {
+            match object.keys().rev().find(|name| !required.contains(name)) {
+                Some(name) => {
+                    let name = name.clone();
+                    object.remove(&name);
+                }
+                None => break,
+            }
+        }
+        Value::Object(object)
+    }
+
+    /// Adds `additionalProp1`, `additionalProp2`… to `object` until it has `count` properties, with values of `schema`, or `null` if any value is allowed.
+    fn additional_properties(
+        &mut self,
+        object: &mut Map<String, Value>,
+        count: usize,
+        schema: Option<&'s ReferenceObjectOr<SchemaObject>>,
+    ) {
+        for i in 1.. {
+            if object.len() >= count {
+                break;
+            }
+            let name = format!("additionalProp{}", i);
+            if object.contains_key(&name) {
+                continue;
+            }
+            let value = match schema.map(|schema| self.reference_or_schema(schema)) {
+                Some(Some(value)) => value,
+                Some(None) => break,
+                None => Value::Null,
+            };
+            object.insert(name, value);
+        }
+    }
+
+    /// The index of the chosen one of `len` alternatives, the first one unless random.
+    fn index(&mut self, len: usize) -> usize {
+        match &mut self.rng {
+            Some(rng) => rng.below(len as u64) as usize,
+            None => 0,
+        }
+    }
+
+    /// A random boolean, or `true` unless random.
+    fn chance(&mut self) -> bool {
+        self.index(2) == 0
+    }
+}
+
+/// The inclusive bounds of an integer, given by the schema and its format.
+pub(crate) fn integer_bounds(schema: &SchemaObject) -> (i64, i64) {
+    let (mut min, mut max) = match schema.format.as_deref() {
+        Some("int32") => (i32::MIN.into(), i32::MAX.into()),
+        _ => (i64::MIN, i64::MAX),
+    };
+    if let Some(minimum) = schema.minimum {
+        min = i64::from(minimum) + i64::from(schema.exclusive_minimum == Some(true));
+    }
+    if let Some(maximum) = schema.maximum {
+        max = i64::from(maximum) - i64::from(schema.exclusive_maximum == Some(true));
+    }
+    (min, max)
+}
+
+/// The type of `schema`, implied by the keywords it uses if it isn't given.
+pub(crate) fn schema_type(schema: &SchemaObject) -> Option<SchemaType> {
+    schema.schema_type.or(
+        if schema.properties.is_some() || schema.additional_properties.is_some() {
+            Some(SchemaType::Object)
+        } else if schema.items.is_some() {
+            Some(SchemaType::Array)
+        } else {
+            None
+        },
+    )
+}
+
+/// Sets the discriminator property of `value`, generated for `branch` of `schema`, to the name of the branch.
+fn discriminate(
+    schema: &SchemaObject,
+    branch: &ReferenceObjectOr<SchemaObject>,
+    value: &mut Value,
+) {
+    if let (Some((property, name)), Value::Object(object)) =
+        (discriminator_value(schema, branch), value)
+    {
+        object.insert(property, Value::String(name));
+    }
+}
+
+/// The discriminator property of `schema` and its value for `branch`: the key of the branch in the mapping, or the name of the schema it references.
+pub(crate) fn discriminator_value(
+    schema: &SchemaObject,
+    branch: &ReferenceObjectOr<SchemaObject>,
+) -> Option<(String, String)> {
+    let (discriminator, reference) = match (&schema.discriminator, branch) {
+        (Some(discriminator), ReferenceObjectOr::ReferenceObject { reference }) => {
+            (discriminator, reference)
+        }
+        _ => return None,
+    };
+
+    let name = discriminator
+        .mapping
+        .iter()
+        .find(|(_, mapped)| *mapped == reference)
+        .map(|(name, _)| name.clone())
+        .or_else(|| ComponentRef::parse(reference).map(|component| component.name))
+        .unwrap_or_else(|| reference.clone());
+    Some((discriminator.property_name.clone(), name))
+}
+
+/// Merges the values of the parts of an `allOf`, the properties of objects being combined.
+pub(crate) fn merge(value: Value, part: Value) -> Value {
+    match (value, part) {
+        (Value::Object(mut object), Value::Object(part)) => {
+            for (name, value) in part {
+                object.entry(name).or_insert(value);
+            }
+            Value::Object(object)
+        }
+        (Value::Null, part) => part,
+        (value, _) => value,
+    }
+}
+
+/// The [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator, which is good enough for examples and always gives the same values from the same seed.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..n`, or `0` if `n` is zero.
+    fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            0
+        } else {
+            self.next() % n
+        }
+    }
+
+    /// A value in `low..=high`, or `low` if the range is empty.
+    fn between(&mut self, low: i64, high: i64) -> i64 {
+        if high <= low {
+            return low;
+        }
+        let span = (high as i128 - low as i128 + 1) as u128;
+        (low as i128 + (u128::from(self.next()) % span) as i128) as i64
+    }
+}
@@ -0,0 +1,293 @@
+use indexmap::IndexSet;
+
+use super::{ComponentRef, ComponentsObject};
+
+/// The references between the components of a document.
+///
+/// Only references to components that exist are edges, references to other documents or to missing components are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentGraph {
+    nodes: IndexSet<ComponentRef>,
+    /// The components referenced by each node, in order of appearance.
+    edges: Vec<IndexSet<usize>>,
+    /// The components referencing each node.
+    reverse_edges: Vec<IndexSet<usize>>,
+    /// The strongly connected components, dependencies first.
+    groups: Vec<Vec<usize>>,
+    /// The index in `groups` of each node.
+    group_of: Vec<usize>,
+}
+
+impl ComponentGraph {
+    pub fn new(components: &ComponentsObject) -> Self {
+        let nodes: IndexSet<ComponentRef> = components.component_refs().into_iter().collect();
+        let mut edges = vec![IndexSet::new(); nodes.len()];
+        let mut reverse_edges = vec![IndexSet::new(); nodes.len()];
+
+        for (from, component) in nodes.iter().enumerate() {
+            components.visit_component_references(component, &mut |_, reference| {
+                let to = ComponentRef::parse(reference).and_then(|to| nodes.get_index_of(&to));
+
+                if let Some(to) = to {
+                    edges[from].insert(to);
+                    reverse_edges[to].insert(from);
+                }
+            });
+        }
+
+        let groups = tarjan(&edges);
+        let mut group_of = vec![0; nodes.len()];
+        for (i, group) in groups.iter().enumerate() {
+            for &node in group {
+                group_of[node] = i;
+            }
+        }
+
+        Self {
+            nodes,
+            edges,
+            reverse_edges,
+            groups,
+            group_of,
+        }
+    }
+
+    /// Every component, in declaration order.
+    pub fn components(&self) -> impl Iterator<Item = &ComponentRef> {
+        self.nodes.iter()
+    }
+
+    /// Every reference between two components, as `(from, to)` pairs.
+    pub fn edges(&self) -> impl Iterator<Item = (&ComponentRef, &ComponentRef)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, targets)| {
+                targets
+                    .iter()
+                    .map(move |&to| (&self.nodes[from], &self.nodes[to]))
+            })
+    }
+
+    /// The components `component` references directly.
+    pub fn references(&self, component: &ComponentRef) -> impl Iterator<Item = &ComponentRef> {
+        self.neighbours(&self.edges, component)
+    }
+
+    /// The components directly referencing `component`.
+    pub fn referenced_by(&self, component: &ComponentRef) -> impl Iterator<Item = &ComponentRef> {
+        self.neighbours(&self.reverse_edges, component)
+    }
+
+    /// Groups of components that reference each other, directly or not, such as recursive schemas.
+    ///
+    /// Every component belongs to exactly one group, and groups come after the groups they reference, so a group never references a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&ComponentRef>> {
+        self.groups
+            .iter()
+            .map(|group| group.iter().map(|&node| &self.nodes[node]).collect())
+            .collect()
+    }
+
+    /// Every component after the components it references, e.g. to emit type definitions in dependency order.
+    ///
+    /// Components that reference each other can't be ordered, they are kept next to each other in declaration order.
+    pub fn topological_order(&self) -> Vec<&ComponentRef> {
+        self.groups
+            .iter()
+            .flatten()
+            .map(|&node| &self.nodes[node])
+            .collect()
+    }
+
+    /// Whether `component` references itself, directly or through other components.
+    pub fn is_recursive(&self, component: &ComponentRef) -> bool {
+        match self.nodes.get_index_of(component) {
+            Some(node) => {
+                self.groups[self.group_of[node]].len() > 1 || self.edges[node].contains(&node)
+            }
+            None => false,
+        }
+    }
+
+    /// Whether `from` and `to` reference each other, directly or not. A reference from `from` to `to` then closes a cycle and must be indirect in generated code, e.g. boxed.
+    pub fn in_same_cycle(&self, from: &ComponentRef, to: &ComponentRef) -> bool {
+        match (self.nodes.get_index_of(from), self.nodes.get_index_of(to)) {
+            (Some(from), Some(to)) if from == to => self.edges[from].contains(&to),
+            (Some(from), Some(to)) => self.group_of[from] == self.group_of[to],
+            _ => false,
+        }
+    }
+
+    fn neighbours<'g>(
+        &'g self,
+        edges: &'g [IndexSet<usize>],
+        component: &ComponentRef,
+    ) -> impl Iterator<Item = &'g ComponentRef> {
+        self.nodes
+            .get_index_of(component)
+            .into_iter()
+            .flat_map(move |node| edges[node].iter().map(move |&other| &self.nodes[other]))
+    }
+}
+
+/// [Tarjan's algorithm](https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm), which finds groups in reverse topological order.
+fn tarjan(edges: &[IndexSet<usize>]) -> Vec<Vec<usize>> {
+    struct State<'e> {
+        edges: &'e [IndexSet<usize>],
+        index: Vec<Option<usize>>,
+        low_link: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next_index: usize,
+        groups: Vec<Vec<usize>>,
+    }
+
+    fn visit(state: &mut State<'_>, node: usize) {
+        state.index[node] = Some(state.next_index);
+        state.low_link[node] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(node);
+        state.on_stack[node] = true;
+
+        for &next in state.edges[node].iter() {
+            match state.index[next] {
+                None => {
+                    visit(state, next);
+                    state.low_link[node] = state.low_link[node].min(state.low_link[next]);
+                }
+                Some(index) if state.on_stack[next] => {
+                    state.low_link[node] = state.low_link[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(state.low_link[node]) == state.index[node] {
+            let mut group = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack[member] = false;
+                group.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            group.sort_unstable();
+            state.groups.push(group);
+        }
+    }
+
+    let mut state = State {
+        edges,
+        index: vec![None; edges.len()],
+        low_link: vec![0; edges.len()],
+        on_stack: vec![false; edges.len()],
+        stack: Vec::new(),
+        next_index: 0,
+        groups: Vec::new(),
+    };
+
+    for node in 0..edges.len() {
+        if state.index[node].is_none() {
+            visit(&mut state, node);
+        }
+    }
+
+    state.groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::{ComponentKind, OpenAPIObject};
+
+    fn graph() -> ComponentGraph {
+        let document: OpenAPIObject = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Orders, version: "1"}
+servers: []
+paths: {}
+components:
+  schemas:
+    Order:
+      type: object
+      properties:
+        customer: {$ref: "#/components/schemas/Customer"}
+        items: {type: array, items: {$ref: "#/components/schemas/Item"}}
+        coupon: {$ref: "#/components/schemas/Coupon"}
+    Tree: {type: object, properties: {root: {$ref: "#/components/schemas/Node"}}}
+    Node: {type: object, properties: {tree: {$ref: "#/components/schemas/Tree"}}}
+    Customer: {type: object, properties: {address: {$ref: "#/components/schemas/Address"}}}
+    Address: {type: object}
+    Item: {type: object, properties: {parts: {type: array, items: {$ref: "#/components/schemas/Item"}}}}
+  responses:
+    Order:
+      description: An order.
+      content: {application/json: {schema: {$ref: "#/components/schemas/Order"}}}
+"##,
+        )
+        .unwrap();
+        ComponentGraph::new(document.components.as_ref().unwrap())
+    }
+
+    fn schema(name: &str) -> ComponentRef {
+        ComponentRef::new(ComponentKind::Schemas, name)
+    }
+
+    #[test]
+    fn edges() {
+        let graph = graph();
+
+        // The reference to the missing `Coupon` isn't an edge
+        assert_eq!(
+            graph.references(&schema("Order")).collect::<Vec<_>>(),
+            [&schema("Customer"), &schema("Item")]
+        );
+        assert_eq!(
+            graph.referenced_by(&schema("Order")).collect::<Vec<_>>(),
+            [&ComponentRef::new(ComponentKind::Responses, "Order")]
+        );
+        assert_eq!(graph.edges().count(), 7);
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let graph = graph();
+        let response = ComponentRef::new(ComponentKind::Responses, "Order");
+
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![
+                vec![&schema("Address")],
+                vec![&schema("Customer")],
+                vec![&schema("Item")],
+                vec![&schema("Order")],
+                vec![&schema("Tree"), &schema("Node")],
+                vec![&response],
+            ]
+        );
+        assert_eq!(
+            graph.topological_order(),
+            [
+                &schema("Address"),
+                &schema("Customer"),
+                &schema("Item"),
+                &schema("Order"),
+                &schema("Tree"),
+                &schema("Node"),
+                &response,
+            ]
+        );
+
+        assert!(graph.is_recursive(&schema("Tree")));
+        assert!(graph.is_recursive(&schema("Item")));
+        assert!(!graph.is_recursive(&schema("Order")));
+        assert!(!graph.is_recursive(&schema("Coupon")));
+
+        assert!(graph.in_same_cycle(&schema("Node"), &schema("Tree")));
+        assert!(graph.in_same_cycle(&schema("Item"), &schema("Item")));
+        assert!(!graph.in_same_cycle(&schema("Order"), &schema("Order")));
+        assert!(!graph.in_same_cycle(&schema("Order"), &schema("Customer")));
+    }
+}
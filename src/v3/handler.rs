@@ -0,0 +1,95 @@
+use http::Method;
+
+use super::{
+    ComponentsObject, InfoObject, OpenAPIObject, OperationObject, PathsItemObject, PathsObject,
+    ReferenceObjectOr,
+};
+
+#[cfg(feature = "derive")]
+pub use oa_derive::operation;
+
+/// A handler serving an operation.
+///
+/// With the `derive` feature, annotating a function with `#[operation(get, "/users/{id}", tag = "users")]` implements it for a type named after the function.
+/// The summary and description of the operation come from the doc comments of the function, and its parameters from its arguments:
+/// those named after a variable of the path template are path parameters, those annotated with `#[query]`, `#[header]` or `#[cookie]` are parameters in that location, optionally renamed with e.g. `#[header("X-Request-Id")]`, and the one annotated with `#[body]` is the JSON request body.
+/// `Option` arguments aren't required. The successful response, `200` unless given by `status = 201`, holds the return type as JSON, or the `Ok` type of a `Result`, and is `204` when there is nothing to return.
+pub trait Handler {
+    fn method() -> Method;
+
+    fn path() -> &'static str;
+
+    /// The operation, whose schemas reference the components added by [`Handler::register`].
+    fn operation() -> OperationObject;
+
+    /// Adds the component schemas of the operation to `components`, keeping those that are already there.
+    fn register(components: &mut ComponentsObject);
+}
+
+/// Assembles the operations of handlers into the paths and components of a document.
+#[derive(Debug, Default)]
+pub struct Operations {
+    paths: PathsObject,
+    components: ComponentsObject,
+}
+
+impl Operations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<H: Handler>(mut self) -> Self {
+        self.add::<H>();
+        self
+    }
+
+    /// Adds the operation of `H`, replacing the one with the same method and path if any.
+    ///
+    /// # Panics
+    ///
+    /// If the method of `H` isn't one of those a path item can describe.
+    pub fn add<H: Handler>(&mut self) {
+        H::register(&mut self.components);
+
+        let path_item = self
+            .paths
+            .entry(H::path().to_string())
+            .or_insert_with(|| ReferenceObjectOr::Object(PathsItemObject::default()));
+        if let ReferenceObjectOr::Object(path_item) = path_item {
+            let method = H::method();
+            let operation = path_item
+                .operation_entry(&method)
+                .unwrap_or_else(|| panic!("a path item can't describe {} operations", method));
+            *operation = Some(H::operation());
+        }
+    }
+
+    pub fn paths(&self) -> &PathsObject {
+        &self.paths
+    }
+
+    pub fn components(&self) -> &ComponentsObject {
+        &self.components
+    }
+
+    /// A document describing `info` and the operations, for version 3.0.3 of the specification.
+    pub fn into_document(self, info: InfoObject) -> OpenAPIObject {
+        let components = if self.components.is_empty() {
+            None
+        } else {
+            Some(self.components)
+        };
+
+        OpenAPIObject {
+            openapi: "3.0.3".to_string(),
+            info,
+            servers: Vec::new(),
+            paths: self.paths,
+            components,
+            security: None,
+            tags: None,
+            external_docs: None,
+            extensions: Default::default(),
+        }
+    }
+}
@@ -1,4 +1,29 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct HeaderObject;
+use super::{ExampleObject, SchemaOrContentObject};
+
+/// The Header Object follows the structure of the [Parameter Object](super::ParameterObject) with the following changes:
+/// `name` MUST NOT be specified, it is given in the corresponding headers map.
+/// `in` MUST NOT be specified, it is implicitly in header.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct HeaderObject {
+    /// A brief description of the header. [CommonMark syntax](https://spec.commonmark.org/) MAY be used for rich text representation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Determines whether this header is mandatory.
+    #[serde(default)]
+    pub required: bool,
+    /// Specifies that a header is deprecated and SHOULD be transitioned out of usage.
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(flatten)]
+    pub schema: SchemaOrContentObject,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<IndexMap<String, ExampleObject>>,
+}
@@ -4,7 +4,7 @@ use url::Url;
 use super::{ContactObject, Extensions, LicenseObject};
 
 /// The object provides metadata about the API. The metadata MAY be used by the clients if needed, and MAY be presented in editing or documentation generation tools for convenience.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct InfoObject {
     /// The title of the API.
     pub title: String,
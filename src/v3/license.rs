@@ -4,7 +4,7 @@ use url::Url;
 use super::Extensions;
 
 /// License information for the exposed API.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct LicenseObject {
     /// The license name used for the API.
     pub name: String,
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use super::ServerObject;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct LinkObject {
     #[serde(rename = "operationRef", skip_serializing_if = "Option::is_none")]
     pub operation_ref: Option<String>,
@@ -5,7 +5,7 @@ use serde_json::Value;
 use super::{EncodingObject, ExampleObject, ReferenceObjectOr, SchemaObject};
 
 /// Each Media Type Object provides schema and examples for the media type identified by its key.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct MediaTypeObject {
     /// The schema defining the content of the request, response, or parameter.
     #[serde(skip_serializing_if = "Option::is_none")]
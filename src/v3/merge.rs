@@ -0,0 +1,493 @@
+use indexmap::IndexMap;
+use std::{collections::HashMap, fmt};
+
+use super::{
+    rename::rename_references, ComponentKind, ComponentRef, ComponentsObject, OpenAPIObject,
+    PathsItemObject, ReferenceObjectOr,
+};
+use crate::utils::push_pointer;
+
+/// How to handle two sources defining different objects under the same name.
+///
+/// Identical objects are never a conflict, so components shared by every source, like a common error schema, are kept once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Fail with [`MergeError::Conflict`].
+    #[default]
+    Error,
+    /// Keep the object of the source that comes first.
+    FirstWins,
+    /// Prefix the names of the components of every source with its [namespace](MergeSource::with_namespace) and rewrite references to them, e.g. `User` becomes `billing_User`.
+    /// Paths and components of sources without a namespace can still conflict, which is an error.
+    Prefix,
+}
+
+/// A document to merge, along with options applying to it only.
+#[derive(Debug)]
+pub struct MergeSource {
+    document: OpenAPIObject,
+    namespace: Option<String>,
+    path_prefix: Option<String>,
+}
+
+impl MergeSource {
+    pub fn new(document: OpenAPIObject) -> Self {
+        Self {
+            document,
+            namespace: None,
+            path_prefix: None,
+        }
+    }
+
+    /// Sets the namespace used to prefix component names with [`ConflictPolicy::Prefix`].
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Prepends `prefix`, e.g. `/billing`, to every path of the document.
+    pub fn with_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+}
+
+impl From<OpenAPIObject> for MergeSource {
+    fn from(document: OpenAPIObject) -> Self {
+        Self::new(document)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// There is nothing to merge.
+    NoSources,
+    /// Two sources define different objects at the location given by a [JSON Pointer](https://tools.ietf.org/html/rfc6901).
+    Conflict(String),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::NoSources => f.write_str("no documents to merge"),
+            MergeError::Conflict(pointer) => write!(f, "conflicting definitions of `{}`", pointer),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Combines several documents into one.
+///
+/// Paths, every kind of component, tags and servers are merged, the `openapi` version, `info` and `externalDocs` come from the first source.
+/// Tags and servers are deduplicated, keeping the first definition of a tag.
+/// When sources declare different global `security`, every source's requirements are moved to its operations that don't override them.
+pub fn merge(
+    sources: impl IntoIterator<Item = MergeSource>,
+    policy: ConflictPolicy,
+) -> Result<OpenAPIObject, MergeError> {
+    let mut documents: Vec<OpenAPIObject> = sources
+        .into_iter()
+        .map(|source| {
+            let mut document = source.document;
+
+            if let (ConflictPolicy::Prefix, Some(namespace)) = (policy, &source.namespace) {
+                prefix_components(&mut document, namespace);
+            }
+            if let Some(prefix) = &source.path_prefix {
+                prefix_paths(&mut document, prefix);
+            }
+
+            document
+        })
+        .collect();
+
+    if documents.is_empty() {
+        return Err(MergeError::NoSources);
+    }
+
+    let shared_security = documents
+        .windows(2)
+        .all(|pair| pair[0].security == pair[1].security);
+    if !shared_security {
+        for document in &mut documents {
+            push_down_security(document);
+        }
+    }
+
+    let mut documents = documents.into_iter();
+    let mut merged = documents.next().ok_or(MergeError::NoSources)?;
+
+    for document in documents {
+        merge_document(&mut merged, document, policy)?;
+    }
+
+    Ok(merged)
+}
+
+fn merge_document(
+    target: &mut OpenAPIObject,
+    source: OpenAPIObject,
+    policy: ConflictPolicy,
+) -> Result<(), MergeError> {
+    for (template, path_item) in source.paths {
+        let pointer = push_pointer("/paths", &template);
+
+        match target.paths.get_mut(&template) {
+            Some(ReferenceObjectOr::Object(existing)) => {
+                if let ReferenceObjectOr::Object(path_item) = path_item {
+                    merge_path_item(existing, path_item, &pointer, policy)?;
+                } else {
+                    conflict(&pointer, policy)?;
+                }
+            }
+            Some(existing) => {
+                if *existing != path_item {
+                    conflict(&pointer, policy)?;
+                }
+            }
+            None => {
+                target.paths.insert(template, path_item);
+            }
+        }
+    }
+
+    if let Some(components) = source.components {
+        let target = target.components.get_or_insert_with(Default::default);
+        merge_components(target, components, policy)?;
+    }
+
+    for tag in source.tags.into_iter().flatten() {
+        let tags = target.tags.get_or_insert_with(Vec::new);
+        if !tags.iter().any(|existing| existing.name == tag.name) {
+            tags.push(tag);
+        }
+    }
+
+    for server in source.servers {
+        if !target.servers.contains(&server) {
+            target.servers.push(server);
+        }
+    }
+
+    for (name, value) in source.extensions {
+        target.extensions.entry(name).or_insert(value);
+    }
+
+    Ok(())
+}
+
+fn merge_path_item(
+    target: &mut PathsItemObject,
+    source: PathsItemObject,
+    pointer: &str,
+    policy: ConflictPolicy,
+) -> Result<(), MergeError> {
+    let PathsItemObject {
+        summary,
+        description,
+        get,
+        put,
+        post,
+        delete,
+        options,
+        head,
+        patch,
+        trace,
+        servers,
+        parameters,
+        extensions,
+    } = source;
+
+    let operations = [
+        ("get", &mut target.get, get),
+        ("put", &mut target.put, put),
+        ("post", &mut target.post, post),
+        ("delete", &mut target.delete, delete),
+        ("options", &mut target.options, options),
+        ("head", &mut target.head, head),
+        ("patch", &mut target.patch, patch),
+        ("trace", &mut target.trace, trace),
+    ];
+    for (method, target, source) in IntoIterator::into_iter(operations) {
+        merge_field(target, source, &push_pointer(pointer, method), policy)?;
+    }
+
+    merge_field(
+        &mut target.summary,
+        summary,
+        &push_pointer(pointer, "summary"),
+        policy,
+    )?;
+    merge_field(
+        &mut target.description,
+        description,
+        &push_pointer(pointer, "description"),
+        policy,
+    )?;
+    merge_field(
+        &mut target.servers,
+        servers,
+        &push_pointer(pointer, "servers"),
+        policy,
+    )?;
+    merge_field(
+        &mut target.parameters,
+        parameters,
+        &push_pointer(pointer, "parameters"),
+        policy,
+    )?;
+
+    for (name, value) in extensions {
+        target.extensions.entry(name).or_insert(value);
+    }
+
+    Ok(())
+}
+
+fn merge_components(
+    target: &mut ComponentsObject,
+    source: ComponentsObject,
+    policy: ConflictPolicy,
+) -> Result<(), MergeError> {
+    let ComponentsObject {
+        schemas,
+        responses,
+        parameters,
+        examples,
+        request_bodies,
+        headers,
+        security_schemes,
+        links,
+        callbacks,
+        extensions,
+    } = source;
+
+    merge_map(&mut target.schemas, schemas, ComponentKind::Schemas, policy)?;
+    merge_map(
+        &mut target.responses,
+        responses,
+        ComponentKind::Responses,
+        policy,
+    )?;
+    merge_map(
+        &mut target.parameters,
+        parameters,
+        ComponentKind::Parameters,
+        policy,
+    )?;
+    merge_map(
+        &mut target.examples,
+        examples,
+        ComponentKind::Examples,
+        policy,
+    )?;
+    merge_map(
+        &mut target.request_bodies,
+        request_bodies,
+        ComponentKind::RequestBodies,
+        policy,
+    )?;
+    merge_map(&mut target.headers, headers, ComponentKind::Headers, policy)?;
+    merge_entries(
+        &mut target.security_schemes,
+        security_schemes,
+        ComponentKind::SecuritySchemes,
+        policy,
+    )?;
+    merge_map(&mut target.links, links, ComponentKind::Links, policy)?;
+    merge_map(
+        &mut target.callbacks,
+        callbacks,
+        ComponentKind::Callbacks,
+        policy,
+    )?;
+
+    for (name, value) in extensions {
+        target.extensions.entry(name).or_insert(value);
+    }
+
+    Ok(())
+}
+
+fn merge_map<T: PartialEq>(
+    target: &mut Option<IndexMap<String, T>>,
+    source: Option<IndexMap<String, T>>,
+    kind: ComponentKind,
+    policy: ConflictPolicy,
+) -> Result<(), MergeError> {
+    match source {
+        Some(source) => merge_entries(
+            target.get_or_insert_with(IndexMap::new),
+            source,
+            kind,
+            policy,
+        ),
+        None => Ok(()),
+    }
+}
+
+fn merge_entries<T: PartialEq>(
+    target: &mut IndexMap<String, T>,
+    source: IndexMap<String, T>,
+    kind: ComponentKind,
+    policy: ConflictPolicy,
+) -> Result<(), MergeError> {
+    for (name, object) in source {
+        match target.get(&name) {
+            Some(existing) if *existing != object => {
+                conflict(&ComponentRef::new(kind, name).to_pointer(), policy)?
+            }
+            Some(_) => {}
+            None => {
+                target.insert(name, object);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes the source value if the target has none, otherwise they must be equal.
+fn merge_field<T: PartialEq>(
+    target: &mut Option<T>,
+    source: Option<T>,
+    pointer: &str,
+    policy: ConflictPolicy,
+) -> Result<(), MergeError> {
+    match (target.as_ref(), source) {
+        (None, source) => {
+            *target = source;
+            Ok(())
+        }
+        (Some(existing), Some(source)) if *existing != source => conflict(pointer, policy),
+        _ => Ok(()),
+    }
+}
+
+fn conflict(pointer: &str, policy: ConflictPolicy) -> Result<(), MergeError> {
+    match policy {
+        ConflictPolicy::FirstWins => Ok(()),
+        ConflictPolicy::Error | ConflictPolicy::Prefix => {
+            Err(MergeError::Conflict(pointer.to_string()))
+        }
+    }
+}
+
+fn prefix_paths(document: &mut OpenAPIObject, prefix: &str) {
+    let prefix = prefix.trim_end_matches('/');
+
+    document.paths = std::mem::take(&mut document.paths)
+        .into_iter()
+        .map(|(template, path_item)| (format!("{}{}", prefix, template), path_item))
+        .collect();
+}
+
+/// Renames every component to `{namespace}_{name}`, rewriting references and security requirements.
+fn prefix_components(document: &mut OpenAPIObject, namespace: &str) {
+    let components = match &mut document.components {
+        Some(components) => components,
+        None => return,
+    };
+    let mut renames = HashMap::new();
+
+    prefix_map(
+        &mut components.schemas,
+        ComponentKind::Schemas,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.responses,
+        ComponentKind::Responses,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.parameters,
+        ComponentKind::Parameters,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.examples,
+        ComponentKind::Examples,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.request_bodies,
+        ComponentKind::RequestBodies,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.headers,
+        ComponentKind::Headers,
+        namespace,
+        &mut renames,
+    );
+    prefix_entries(
+        &mut components.security_schemes,
+        ComponentKind::SecuritySchemes,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.links,
+        ComponentKind::Links,
+        namespace,
+        &mut renames,
+    );
+    prefix_map(
+        &mut components.callbacks,
+        ComponentKind::Callbacks,
+        namespace,
+        &mut renames,
+    );
+
+    rename_references(document, &renames);
+}
+
+fn prefix_map<T>(
+    map: &mut Option<IndexMap<String, T>>,
+    kind: ComponentKind,
+    namespace: &str,
+    renames: &mut HashMap<ComponentRef, String>,
+) {
+    if let Some(map) = map {
+        prefix_entries(map, kind, namespace, renames);
+    }
+}
+
+fn prefix_entries<T>(
+    map: &mut IndexMap<String, T>,
+    kind: ComponentKind,
+    namespace: &str,
+    renames: &mut HashMap<ComponentRef, String>,
+) {
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(name, object)| {
+            let prefixed = format!("{}_{}", namespace, name);
+            renames.insert(ComponentRef::new(kind, name), prefixed.clone());
+            (prefixed, object)
+        })
+        .collect();
+}
+
+/// Copies the global security requirements to the operations that don't declare their own, so that they survive merging with documents requiring something else.
+fn push_down_security(document: &mut OpenAPIObject) {
+    let security = match document.security.take() {
+        Some(security) => security,
+        None => return,
+    };
+
+    for path_item in document.paths.values_mut() {
+        if let ReferenceObjectOr::Object(path_item) = path_item {
+            for (_, operation) in path_item.operations_mut() {
+                operation.security.get_or_insert_with(|| security.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use indexmap::IndexMap;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use super::MediaTypeObject;
+
+/// A map between media types or [media type ranges](https://tools.ietf.org/html/rfc7231#appendix-D) and their descriptions, as found in the `content` of requests, responses and parameters.
+pub type ContentMap = IndexMap<MediaType, MediaTypeObject>;
+
+/// A parsed media type as defined by [RFC6838](https://tools.ietf.org/html/rfc6838), e.g. `application/vnd.api+json; charset=utf-8`.
+///
+/// Type, subtype, suffix and parameter names are case insensitive and stored lowercased. Either the type or the subtype can be `*` to describe a range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MediaType {
+    pub type_name: String,
+    /// The subtype without its structured syntax suffix, e.g. `vnd.api` for `application/vnd.api+json`.
+    pub subtype: String,
+    /// The structured syntax suffix, e.g. `json` for `application/vnd.api+json`.
+    pub suffix: Option<String>,
+    pub parameters: Vec<(String, String)>,
+}
+
+impl MediaType {
+    pub fn new(type_name: impl Into<String>, subtype: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            subtype: subtype.into(),
+            suffix: None,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// The type and subtype, including the suffix but without parameters, e.g. `application/vnd.api+json`.
+    pub fn essence(&self) -> String {
+        match &self.suffix {
+            Some(suffix) => format!("{}/{}+{}", self.type_name, self.subtype, suffix),
+            None => format!("{}/{}", self.type_name, self.subtype),
+        }
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter, _)| parameter.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether this is a range, like `text/*` or `*/*`.
+    pub fn is_range(&self) -> bool {
+        self.type_name == "*" || self.subtype == "*"
+    }
+
+    /// Whether the content is JSON, i.e. `application/json` or any `+json` type.
+    pub fn is_json(&self) -> bool {
+        self.suffix.as_deref() == Some("json")
+            || (self.type_name == "application" && self.subtype == "json")
+    }
+
+    /// Whether `other` is included in this type or range. Parameters declared by `self` must be present in `other` with the same value.
+    ///
+    /// A range with a suffix, like `application/*+json`, only includes the types with the same suffix.
+    pub fn includes(&self, other: &MediaType) -> bool {
+        let type_matches = self.type_name == "*" || self.type_name == other.type_name;
+        let subtype_matches = (self.subtype == "*" || self.subtype == other.subtype)
+            && (self.suffix == other.suffix || (self.subtype == "*" && self.suffix.is_none()));
+
+        type_matches
+            && subtype_matches
+            && self
+                .parameters
+                .iter()
+                .filter(|(name, _)| name != "q")
+                .all(|(name, value)| {
+                    other
+                        .parameter(name)
+                        .is_some_and(|other| other.eq_ignore_ascii_case(value))
+                })
+    }
+
+    /// How specific this type is, `*/*` being the least specific and a type with parameters the most.
+    ///
+    /// A range with a suffix, like `application/*+json`, is more specific than `application/*`.
+    pub fn specificity(&self) -> (bool, bool, bool, usize) {
+        (
+            self.type_name != "*",
+            self.subtype != "*",
+            self.suffix.is_some(),
+            self.parameters
+                .iter()
+                .filter(|(name, _)| name != "q")
+                .count(),
+        )
+    }
+
+    /// The `q` parameter of a media range in an `Accept` header, defaulting to 1.
+    fn quality(&self) -> f32 {
+        self.parameter("q")
+            .and_then(|q| q.parse().ok())
+            .filter(|q: &f32| (0.0..=1.0).contains(q))
+            .unwrap_or(1.0)
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.essence())?;
+
+        for (name, value) in &self.parameters {
+            if !value.is_empty() && value.chars().all(is_token) {
+                write!(f, "; {}={}", name, value)?;
+            } else {
+                write!(
+                    f,
+                    "; {}=\"{}\"",
+                    name,
+                    value.replace('\\', "\\\\").replace('"', "\\\"")
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when parsing an invalid media type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMediaType(String);
+
+impl fmt::Display for InvalidMediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a valid media type", self.0)
+    }
+}
+
+impl std::error::Error for InvalidMediaType {}
+
+impl FromStr for MediaType {
+    type Err = InvalidMediaType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidMediaType(s.to_string());
+
+        let (essence, mut rest) = match s.find(';') {
+            Some(end) => (&s[..end], &s[end..]),
+            None => (s, ""),
+        };
+
+        let (type_name, subtype) = essence.trim().split_once('/').ok_or_else(invalid)?;
+        let (subtype, suffix) = match subtype.rsplit_once('+') {
+            Some((subtype, suffix)) => (subtype, Some(suffix)),
+            None => (subtype, None),
+        };
+
+        let tokens = Some(type_name)
+            .into_iter()
+            .chain(Some(subtype))
+            .chain(suffix);
+        for token in tokens {
+            if token.is_empty() || !token.chars().all(is_token) {
+                return Err(invalid());
+            }
+        }
+        if type_name == "*" && subtype != "*" {
+            return Err(invalid());
+        }
+
+        let mut parameters = Vec::new();
+        while let Some(parameter) = rest.strip_prefix(';') {
+            let parameter = parameter.trim_start();
+            if parameter.is_empty() {
+                break;
+            }
+
+            let (name, value) = parameter.split_once('=').ok_or_else(invalid)?;
+            let name = name.trim();
+            if name.is_empty() || !name.chars().all(is_token) {
+                return Err(invalid());
+            }
+
+            let (value, remaining) = if let Some(quoted) = value.strip_prefix('"') {
+                let mut unescaped = String::new();
+                let mut chars = quoted.char_indices();
+                let end = loop {
+                    match chars.next() {
+                        Some((_, '\\')) => unescaped.extend(chars.next().map(|(_, c)| c)),
+                        Some((i, '"')) => break i,
+                        Some((_, c)) => unescaped.push(c),
+                        None => return Err(invalid()),
+                    }
+                };
+                (unescaped, quoted[end + 1..].trim_start())
+            } else {
+                let end = value.find(';').unwrap_or(value.len());
+                (value[..end].trim().to_string(), &value[end..])
+            };
+
+            if !remaining.is_empty() && !remaining.starts_with(';') {
+                return Err(invalid());
+            }
+
+            parameters.push((name.to_ascii_lowercase(), value));
+            rest = remaining;
+        }
+
+        Ok(Self {
+            type_name: type_name.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            suffix: suffix.map(str::to_ascii_lowercase),
+            parameters,
+        })
+    }
+}
+
+/// `tchar` as defined by [RFC7230](https://tools.ietf.org/html/rfc7230#section-3.2.6).
+fn is_token(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+impl Serialize for MediaType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MediaType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MediaTypeVisitor;
+
+        impl<'de> de::Visitor<'de> for MediaTypeVisitor {
+            type Value = MediaType;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a media type or media type range")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(MediaTypeVisitor)
+    }
+}
+
+/// Selects the entry of `content` describing a body sent with `content_type`.
+///
+/// When several keys match, only the most specific one is applicable, e.g. `text/plain` overrides `text/*` which overrides `*/*`.
+pub fn negotiate_content_type<'c>(
+    content: &'c ContentMap,
+    content_type: &MediaType,
+) -> Option<(&'c MediaType, &'c MediaTypeObject)> {
+    content
+        .iter()
+        .filter(|(key, _)| key.includes(content_type))
+        // `max_by_key` returns the last maximum, reverse to prefer the first declared key on ties
+        .rev()
+        .max_by_key(|(key, _)| key.specificity())
+}
+
+/// Selects the entry of `content` that best satisfies an `Accept` header, following the [rules of RFC7231](https://tools.ietf.org/html/rfc7231#section-5.3.2).
+///
+/// Every key is weighted by the quality of the most specific range of `accept` that matches it, the heaviest key wins with ties going to the most specific key.
+/// Keys that are not acceptable, either because no range matches or because their quality is 0, are never selected.
+/// A missing or empty `accept` accepts anything.
+pub fn negotiate_accept<'c>(
+    content: &'c ContentMap,
+    accept: &str,
+) -> Option<(&'c MediaType, &'c MediaTypeObject)> {
+    let ranges: Vec<MediaType> = accept
+        .split(',')
+        .filter(|range| !range.trim().is_empty())
+        .filter_map(|range| range.trim().parse().ok())
+        .collect();
+
+    if ranges.is_empty() {
+        return content.iter().next();
+    }
+
+    content
+        .iter()
+        .filter_map(|(key, media)| {
+            // Keys that are ranges themselves, like `text/*`, can satisfy narrower ranges when nothing covers them entirely
+            let range = ranges
+                .iter()
+                .filter(|range| range.includes(key))
+                .max_by_key(|range| range.specificity())
+                .or_else(|| {
+                    ranges
+                        .iter()
+                        .filter(|range| key.includes(range))
+                        .max_by_key(|range| range.specificity())
+                })?;
+            let quality = range.quality();
+
+            if quality > 0.0 {
+                Some((quality, key, media))
+            } else {
+                None
+            }
+        })
+        .rev()
+        .max_by(|(a, a_key, _), (b, b_key, _)| {
+            a.partial_cmp(b)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a_key.specificity().cmp(&b_key.specificity()))
+        })
+        .map(|(_, key, media)| (key, media))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(keys: &[&str]) -> ContentMap {
+        keys.iter()
+            .map(|key| (key.parse().unwrap(), serde_json::from_str("{}").unwrap()))
+            .collect()
+    }
+
+    fn accepted(keys: &[&str], accept: &str) -> Option<String> {
+        negotiate_accept(&content(keys), accept).map(|(key, _)| key.to_string())
+    }
+
+    fn includes(range: &str, media_type: &str) -> bool {
+        let range: MediaType = range.parse().unwrap();
+        range.includes(&media_type.parse().unwrap())
+    }
+
+    #[test]
+    fn ranges() {
+        assert!(includes("*/*", "application/vnd.api+json"));
+        assert!(includes("application/*", "application/vnd.api+json"));
+        assert!(includes("application/*+json", "application/vnd.api+json"));
+        assert!(!includes("application/*+json", "application/xml"));
+        assert!(!includes("application/*+json", "application/vnd.api+xml"));
+        assert!(!includes("application/*+json", "text/vnd.api+json"));
+        assert!(!includes("application/vnd.api", "application/vnd.api+json"));
+
+        assert!(includes("text/plain", "text/plain; charset=utf-8"));
+        assert!(includes(
+            "text/plain; charset=UTF-8",
+            "text/plain; charset=utf-8"
+        ));
+        assert!(!includes("text/plain; charset=utf-8", "text/plain"));
+    }
+
+    #[test]
+    fn content_type() {
+        let content = content(&["*/*", "text/*", "text/plain", "application/*+json"]);
+        let negotiated = |content_type: &str| {
+            negotiate_content_type(&content, &content_type.parse().unwrap())
+                .map(|(key, _)| key.to_string())
+        };
+
+        assert_eq!(
+            negotiated("text/plain; charset=utf-8").as_deref(),
+            Some("text/plain")
+        );
+        assert_eq!(negotiated("text/html").as_deref(), Some("text/*"));
+        assert_eq!(
+            negotiated("application/vnd.api+json").as_deref(),
+            Some("application/*+json")
+        );
+        assert_eq!(negotiated("application/xml").as_deref(), Some("*/*"));
+    }
+
+    #[test]
+    fn suffix_ranges() {
+        // The suffix range wins over the plain range declared before it
+        let keys = ["application/*", "application/*+json"];
+        assert_eq!(
+            negotiate_content_type(&content(&keys), &"application/vnd.x+json".parse().unwrap())
+                .map(|(key, _)| key.to_string())
+                .as_deref(),
+            Some("application/*+json")
+        );
+        assert_eq!(
+            accepted(&keys, "application/vnd.x+json").as_deref(),
+            Some("application/*+json")
+        );
+        assert_eq!(
+            accepted(&keys, "application/xml").as_deref(),
+            Some("application/*")
+        );
+    }
+
+    #[test]
+    fn specificity() {
+        let keys = ["text/html", "text/plain", "application/json"];
+
+        // The most specific range sets the quality of a key
+        assert_eq!(
+            accepted(&keys, "text/*; q=0.5, text/plain, */*; q=0.1").as_deref(),
+            Some("text/plain")
+        );
+        assert_eq!(
+            accepted(&keys, "text/*, text/html; q=0.2, application/json; q=0.5").as_deref(),
+            Some("text/plain")
+        );
+        assert_eq!(
+            accepted(&keys, "*/*; q=0.5, application/json").as_deref(),
+            Some("application/json")
+        );
+    }
+
+    #[test]
+    fn unacceptable() {
+        let keys = ["text/html", "application/json"];
+
+        assert_eq!(
+            accepted(&keys, "text/html; q=0, */*").as_deref(),
+            Some("application/json")
+        );
+        assert_eq!(accepted(&keys, "*/*; q=0"), None);
+        assert_eq!(accepted(&keys, "application/json; q=0, text/*; q=0"), None);
+        assert_eq!(accepted(&keys, "image/png"), None);
+
+        // Anything is acceptable without ranges
+        assert_eq!(accepted(&keys, "").as_deref(), Some("text/html"));
+    }
+
+    #[test]
+    fn ties() {
+        // The first declared key wins between keys of the same quality and specificity
+        assert_eq!(
+            accepted(&["text/html", "application/json"], "*/*").as_deref(),
+            Some("text/html")
+        );
+        assert_eq!(
+            accepted(
+                &["application/json", "text/html"],
+                "text/html, application/json"
+            )
+            .as_deref(),
+            Some("application/json")
+        );
+
+        // Else the most specific key wins
+        assert_eq!(
+            accepted(&["text/*", "text/plain"], "text/plain, text/*").as_deref(),
+            Some("text/plain")
+        );
+        // Keys that are ranges satisfy narrower ranges
+        assert_eq!(
+            accepted(&["application/*"], "application/json").as_deref(),
+            Some("application/*")
+        );
+    }
+}
@@ -0,0 +1,491 @@
+use bytes::Bytes;
+use http::{
+    header::{ACCEPT, ALLOW, CONTENT_TYPE},
+    HeaderMap, HeaderValue, Request, Response, StatusCode,
+};
+use serde_json::{json, Value};
+
+use super::{
+    generate::ExampleGenerator,
+    negotiate_accept,
+    router::{RouteError, RouteMatch, Router},
+    validation::Validator,
+    HeaderObject, MediaType, MediaTypeObject, OpenAPIObject, ReferenceObjectOr, ResponseKey,
+    ResponseObject, ResponsesObject, SchemaOrContentObject, StatusRange,
+};
+
+/// Answers requests on behalf of the operations of a document.
+///
+/// Requests are routed and validated against the document, then answered with a response of the matched operation,
+/// whose body is the declared `example`, else the first of the `examples`, else generated from the schema by an [`ExampleGenerator`].
+/// By default the response is the successful one with the lowest status, and the media type is negotiated from the `Accept` header.
+///
+/// Like other mock servers, the `Prefer` header of a request selects what's returned, e.g. `Prefer: code=404, example=missing, dynamic=true`:
+/// `code` is the status of the response, `example` the name of one of its `examples`, and `dynamic=true` generates the body even when there is an example.
+///
+/// Requests that can't be answered get an error response with a JSON body listing the reasons:
+/// `404` when no path matches or the preferred response or example isn't declared, `405` when the path doesn't define the method,
+/// `400` when the request isn't valid, `406` when no media type is acceptable and `500` when the document is broken.
+#[derive(Debug)]
+pub struct MockServer<'a> {
+    document: &'a OpenAPIObject,
+    router: Router<'a>,
+    validator: Option<Validator<'a>>,
+    generator: ExampleGenerator<'a>,
+}
+
+/// The preferences a request expresses with its `Prefer` header.
+#[derive(Debug, Default)]
+struct Preferences {
+    code: Option<String>,
+    example: Option<String>,
+    dynamic: bool,
+}
+
+impl Preferences {
+    fn parse(headers: &HeaderMap) -> Self {
+        let mut preferences = Self::default();
+
+        let preferences_list = headers
+            .get_all("prefer")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split([',', ';']));
+        for preference in preferences_list {
+            let (name, value) = match preference.split_once('=') {
+                Some((name, value)) => (name.trim(), value.trim().trim_matches('"')),
+                None => (preference.trim(), ""),
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "code" => preferences.code = Some(value.to_string()),
+                "example" => preferences.example = Some(value.to_string()),
+                "dynamic" => preferences.dynamic = value.eq_ignore_ascii_case("true"),
+                _ => {}
+            }
+        }
+
+        preferences
+    }
+}
+
+/// The reason a request can't be answered.
+type Failure = (StatusCode, Vec<Value>);
+
+fn failure(status: StatusCode, message: impl Into<String>) -> Failure {
+    (status, vec![json!({ "message": message.into() })])
+}
+
+impl<'a> MockServer<'a> {
+    pub fn new(document: &'a OpenAPIObject) -> Self {
+        Self {
+            document,
+            router: Router::new(document),
+            validator: Some(Validator::new().with_document(document)),
+            generator: ExampleGenerator::new().with_document(document),
+        }
+    }
+
+    /// Generates random bodies from `seed`, the same seed always giving the same bodies.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.generator = self.generator.with_seed(seed);
+        self
+    }
+
+    /// Answers requests without validating them first.
+    pub fn without_validation(mut self) -> Self {
+        self.validator = None;
+        self
+    }
+
+    pub fn document(&self) -> &'a OpenAPIObject {
+        self.document
+    }
+
+    /// Answers `request`, see [`MockServer`] for how.
+    pub fn handle(&self, request: &Request<Bytes>) -> Response<Bytes> {
+        let route = match self.router.find(request.method(), request.uri().path()) {
+            Ok(route) => route,
+            Err(error) => {
+                let (status, errors) = match &error {
+                    RouteError::NotFound => failure(StatusCode::NOT_FOUND, error.to_string()),
+                    RouteError::MethodNotAllowed { .. } => {
+                        failure(StatusCode::METHOD_NOT_ALLOWED, error.to_string())
+                    }
+                };
+                let mut response = error_response(status, errors);
+                if let RouteError::MethodNotAllowed { allowed } = error {
+                    let allowed: Vec<&str> = allowed.iter().map(|method| method.as_str()).collect();
+                    if let Ok(allowed) = HeaderValue::from_str(&allowed.join(", ")) {
+                        response.headers_mut().insert(ALLOW, allowed);
+                    }
+                }
+                return response;
+            }
+        };
+
+        self.respond(&route, request)
+            .unwrap_or_else(|(status, errors)| error_response(status, errors))
+    }
+
+    fn respond(
+        &self,
+        route: &RouteMatch<'a>,
+        request: &Request<Bytes>,
+    ) -> Result<Response<Bytes>, Failure> {
+        if let Some(validator) = &self.validator {
+            let report = validator.validate_routed_request(route, request);
+            if !report.is_valid() {
+                let errors = report
+                    .errors
+                    .iter()
+                    .map(|error| json!({ "pointer": error.pointer, "message": error.kind.to_string() }))
+                    .collect();
+                return Err((StatusCode::BAD_REQUEST, errors));
+            }
+        }
+
+        let preferences = Preferences::parse(request.headers());
+        let responses = &route.operation.responses;
+        let (status, response) = match &preferences.code {
+            Some(code) => {
+                let status = code
+                    .parse::<u16>()
+                    .ok()
+                    .and_then(|code| StatusCode::from_u16(code).ok())
+                    .ok_or_else(|| {
+                        failure(
+                            StatusCode::BAD_REQUEST,
+                            format!("`{}` isn't a status code", code),
+                        )
+                    })?;
+                let response = responses.for_status(status).ok_or_else(|| {
+                    failure(
+                        StatusCode::NOT_FOUND,
+                        format!(
+                            "the operation doesn't declare a {} response",
+                            status.as_u16()
+                        ),
+                    )
+                })?;
+                (status, response)
+            }
+            None => default_response(responses).ok_or_else(|| {
+                failure(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "the operation doesn't declare any response",
+                )
+            })?,
+        };
+        let response = self.resolve(response)?;
+
+        let mut builder = Response::builder().status(status);
+        for (name, header) in response.headers.iter().flatten() {
+            let header = self.resolve(header)?;
+            if !header.required || name.eq_ignore_ascii_case("content-type") {
+                continue;
+            }
+            if let Ok(value) = HeaderValue::from_str(&self.header_value(header)) {
+                builder = builder.header(name.as_str(), value);
+            }
+        }
+
+        let body = match &response.content {
+            Some(content) if !content.is_empty() => {
+                let accept = request
+                    .headers()
+                    .get(ACCEPT)
+                    .and_then(|accept| accept.to_str().ok())
+                    .unwrap_or_default();
+                let (media_type, media) = negotiate_accept(content, accept).ok_or_else(|| {
+                    failure(
+                        StatusCode::NOT_ACCEPTABLE,
+                        "none of the media types of the response is acceptable",
+                    )
+                })?;
+                let value = self.example(media, &preferences)?;
+                let media_type = concrete_media_type(media_type);
+                builder = builder.header(CONTENT_TYPE, media_type.to_string());
+                encode(&media_type, value)
+            }
+            _ => Bytes::new(),
+        };
+
+        builder
+            .body(body)
+            .map_err(|error| failure(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))
+    }
+
+    fn resolve<T>(&self, object: &'a ReferenceObjectOr<T>) -> Result<&'a T, Failure>
+    where
+        T: super::Component,
+    {
+        object
+            .resolve(self.document)
+            .map_err(|error| failure(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))
+    }
+
+    /// The body of a response from its description in `media`.
+    fn example(
+        &self,
+        media: &MediaTypeObject,
+        preferences: &Preferences,
+    ) -> Result<Value, Failure> {
+        if let Some(name) = &preferences.example {
+            return media
+                .examples
+                .as_ref()
+                .and_then(|examples| examples.get(name))
+                .and_then(|example| example.value.clone())
+                .ok_or_else(|| {
+                    failure(
+                        StatusCode::NOT_FOUND,
+                        format!("the response doesn't declare an example named `{}`", name),
+                    )
+                });
+        }
+
+        if !preferences.dynamic {
+            let declared = media.example.clone().or_else(|| {
+                media
+                    .examples
+                    .iter()
+                    .flat_map(|examples| examples.values())
+                    .find_map(|example| example.value.clone())
+            });
+            if let Some(value) = declared {
+                return Ok(value);
+            }
+        }
+
+        Ok(media
+            .schema
+            .as_ref()
+            .map(|schema| self.generator.generate(schema))
+            .unwrap_or(Value::Null))
+    }
+
+    fn header_value(&self, header: &HeaderObject) -> String {
+        let declared = header.example.clone().or_else(|| {
+            header
+                .examples
+                .iter()
+                .flat_map(|examples| examples.values())
+                .find_map(|example| example.value.clone())
+        });
+        let value = declared.unwrap_or_else(|| match &header.schema {
+            SchemaOrContentObject::Schema(schema) => self.generator.generate(schema),
+            SchemaOrContentObject::Content(content) => content
+                .values()
+                .next()
+                .and_then(|media| media.schema.as_ref())
+                .map(|schema| self.generator.generate(schema))
+                .unwrap_or(Value::Null),
+        });
+
+        // Headers use the `simple` style
+        match value {
+            Value::String(value) => value,
+            Value::Array(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(item) => item,
+                    item => item.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+            value => value.to_string(),
+        }
+    }
+}
+
+/// The response of `responses` returned by default: the successful one with the lowest status, else the `default` one, else the first one.
+fn default_response(
+    responses: &ResponsesObject,
+) -> Option<(StatusCode, &ReferenceObjectOr<ResponseObject>)> {
+    responses
+        .responses
+        .iter()
+        .filter_map(|(key, response)| match key {
+            ResponseKey::Status(status) if status.is_success() => Some((*status, response)),
+            ResponseKey::Range(StatusRange::Success) => Some((StatusCode::OK, response)),
+            _ => None,
+        })
+        // `min_by_key` returns the first minimum, so an exact `200` wins over `2XX`
+        .min_by_key(|(status, _)| *status)
+        .or_else(|| {
+            responses
+                .default
+                .as_ref()
+                .map(|response| (StatusCode::OK, response))
+        })
+        .or_else(|| {
+            responses
+                .responses
+                .iter()
+                .next()
+                .map(|(key, response)| (first_status(key), response))
+        })
+}
+
+fn first_status(key: &ResponseKey) -> StatusCode {
+    match key {
+        ResponseKey::Status(status) => *status,
+        ResponseKey::Range(StatusRange::Informational) => StatusCode::CONTINUE,
+        ResponseKey::Range(StatusRange::Success) => StatusCode::OK,
+        ResponseKey::Range(StatusRange::Redirection) => StatusCode::MULTIPLE_CHOICES,
+        ResponseKey::Range(StatusRange::ClientError) => StatusCode::BAD_REQUEST,
+        ResponseKey::Range(StatusRange::ServerError) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// The type to send for content described by `media_type`, which can be a range like `*/*`.
+fn concrete_media_type(media_type: &MediaType) -> MediaType {
+    if !media_type.is_range() {
+        return media_type.clone();
+    }
+
+    let json = MediaType::new("application", "json");
+    if media_type.includes(&json) {
+        json
+    } else if media_type.type_name == "text" {
+        MediaType::new("text", "plain")
+    } else {
+        MediaType::new("application", "octet-stream")
+    }
+}
+
+/// Serializes `value` as JSON, except for strings sent as is in content that isn't JSON.
+fn encode(media_type: &MediaType, value: Value) -> Bytes {
+    match value {
+        Value::String(value) if !media_type.is_json() => Bytes::from(value),
+        Value::Null if !media_type.is_json() => Bytes::new(),
+        value => Bytes::from(serde_json::to_vec(&value).unwrap_or_default()),
+    }
+}
+
+fn error_response(status: StatusCode, errors: Vec<Value>) -> Response<Bytes> {
+    let body = serde_json::to_vec(&json!({ "errors": errors })).unwrap_or_default();
+    let mut response = Response::new(Bytes::from(body));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Method;
+
+    fn document() -> OpenAPIObject {
+        serde_yaml::from_str(
+            r#"
+openapi: 3.0.3
+info: {title: Pets, version: "1"}
+servers: []
+paths:
+  /pets/{id}:
+    get:
+      parameters:
+        - {name: id, in: path, required: true, schema: {type: integer}}
+      responses:
+        "200":
+          description: A pet.
+          content:
+            application/json:
+              schema:
+                type: object
+                required: [name]
+                properties: {name: {type: string, minLength: 8}}
+              examples:
+                rex: {value: {name: Rex}}
+                felix: {value: {name: Felix}}
+        "404":
+          description: No pet.
+          content:
+            application/json:
+              example: {message: Not found}
+    delete:
+      responses: {"204": {description: Deleted.}}
+"#,
+        )
+        .unwrap()
+    }
+
+    fn request(method: Method, uri: &str, headers: &[(&str, &str)]) -> Request<Bytes> {
+        let mut request = Request::builder().method(method).uri(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(Bytes::new()).unwrap()
+    }
+
+    fn body(response: &Response<Bytes>) -> Value {
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn routing_errors() {
+        let document = document();
+        let server = MockServer::new(&document);
+
+        let response = server.handle(&request(Method::GET, "/owners/1", &[]));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(body(&response)["errors"][0]["message"].is_string());
+
+        let response = server.handle(&request(Method::PUT, "/pets/1", &[]));
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, DELETE");
+
+        let response = server.handle(&request(Method::GET, "/pets/rex", &[]));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body(&response)["errors"][0]["pointer"], "/path/id");
+    }
+
+    #[test]
+    fn preferences() {
+        let document = document();
+        let server = MockServer::new(&document);
+        let get =
+            |prefer: &str| server.handle(&request(Method::GET, "/pets/1", &[("Prefer", prefer)]));
+
+        // The lowest successful status and the first example by default
+        let response = server.handle(&request(Method::GET, "/pets/1", &[]));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(body(&response), json!({"name": "Rex"}));
+
+        let response = get("code=404");
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body(&response), json!({"message": "Not found"}));
+        assert_eq!(get("code=500").status(), StatusCode::NOT_FOUND);
+        assert_eq!(get("code=abc").status(), StatusCode::BAD_REQUEST);
+
+        assert_eq!(body(&get("example=felix")), json!({"name": "Felix"}));
+        assert_eq!(get("example=tom").status(), StatusCode::NOT_FOUND);
+
+        // Generated from the schema instead of the examples
+        assert_eq!(body(&get("dynamic=true")), json!({"name": "stringst"}));
+
+        let response = server.handle(&request(Method::DELETE, "/pets/1", &[]));
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.body().is_empty());
+    }
+
+    #[test]
+    fn negotiation() {
+        let document = document();
+        let server = MockServer::new(&document);
+        let get =
+            |accept: &str| server.handle(&request(Method::GET, "/pets/1", &[("Accept", accept)]));
+
+        assert_eq!(get("application/*").status(), StatusCode::OK);
+        assert_eq!(get("text/html, */*;q=0.1").status(), StatusCode::OK);
+
+        let response = get("text/html");
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert!(body(&response)["errors"][0]["message"].is_string());
+    }
+}
@@ -2,18 +2,25 @@ pub type JsonValue = serde_json::Value;
 pub type JsonObject = indexmap::IndexMap<String, JsonValue>;
 pub type Extensions = JsonObject;
 
+mod builder;
 mod callback;
 mod components;
 mod contact;
+mod dedup;
 mod discriminator;
 mod encoding;
 mod example;
 mod external_documentation;
+mod extract;
+mod flatten;
+mod graph;
+mod handler;
 mod header;
 mod info;
 mod license;
 mod link;
 mod media_type;
+mod mime;
 mod oauth_flow;
 mod oauth_flows;
 mod openapi;
@@ -22,6 +29,7 @@ mod parameter;
 mod path_item;
 mod paths;
 mod reference;
+mod rename;
 mod request_body;
 mod response;
 mod responses;
@@ -31,20 +39,48 @@ mod security_scheme;
 mod server;
 mod server_variable;
 mod tag;
+mod to_schema;
+mod unused;
+mod visit;
 mod xml;
 
+pub mod codegen;
+
+pub mod diff;
+
+pub mod filter;
+
+pub mod generate;
+
+pub mod merge;
+
+pub mod mock;
+
+pub mod router;
+
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+pub mod validation;
+
+pub use builder::*;
 pub use callback::*;
 pub use components::*;
 pub use contact::*;
+pub use dedup::*;
 pub use discriminator::*;
 pub use encoding::*;
 pub use example::*;
 pub use external_documentation::*;
+pub use flatten::*;
+pub use graph::*;
+pub use handler::*;
 pub use header::*;
 pub use info::*;
 pub use license::*;
 pub use link::*;
 pub use media_type::*;
+pub use mime::*;
 pub use oauth_flow::*;
 pub use oauth_flows::*;
 pub use openapi::*;
@@ -53,6 +89,7 @@ pub use parameter::*;
 pub use path_item::*;
 pub use paths::*;
 pub use reference::*;
+pub use rename::*;
 pub use request_body::*;
 pub use response::*;
 pub use responses::*;
@@ -62,4 +99,7 @@ pub use security_scheme::*;
 pub use server::*;
 pub use server_variable::*;
 pub use tag::*;
+pub use to_schema::*;
+pub use unused::*;
+pub use visit::*;
 pub use xml::*;
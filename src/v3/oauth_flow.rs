@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Configuration details for a supported OAuth Flow
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct OAuthFlowObject {
     // TODO
 }
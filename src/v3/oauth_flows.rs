@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Extensions, OAuthFlowObject};
 
 /// Allows configuration of the supported OAuth Flows.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct OAuthFlowsObject {
     /// Configuration for the OAuth Implicit flow
     pub implicit: Option<OAuthFlowObject>,
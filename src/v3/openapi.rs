@@ -6,7 +6,7 @@ use super::{
 };
 
 /// This is the root document object of the [OpenAPI document](https://spec.openapis.org/oas/v3.0.3#oasDocument).
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct OpenAPIObject {
     /// This string MUST be the [semantic version](https://semver.org/spec/v2.0.0.html) number of the [OpenAPI Specification version](https://spec.openapis.org/oas/v3.0.3#versions) that the OpenAPI document uses. The openapi field SHOULD be used by tooling specifications and clients to interpret the OpenAPI document. This is not related to the API info.version string.
     pub openapi: String,
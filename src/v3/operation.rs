@@ -7,7 +7,7 @@ use super::{
 };
 
 /// Describes a single API operation on a path.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct OperationObject {
     /// A list of tags for API documentation control. Tags can be used for logical grouping of operations by resources or any other qualifier.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -4,7 +4,7 @@ use serde_json::Value;
 
 use super::{ExampleObject, SchemaOrContentObject};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "in")]
 pub enum ParameterObject {
     #[serde(rename = "path")]
@@ -37,7 +37,7 @@ pub enum ParameterObject {
     },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct ParameterObjectFixedFields {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -55,3 +55,61 @@ pub struct ParameterObjectFixedFields {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub examples: Option<IndexMap<String, ExampleObject>>,
 }
+
+/// The location of a parameter, as given by its `in` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+impl ParameterLocation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParameterLocation::Path => "path",
+            ParameterLocation::Query => "query",
+            ParameterLocation::Header => "header",
+            ParameterLocation::Cookie => "cookie",
+        }
+    }
+}
+
+impl ParameterObject {
+    pub fn location(&self) -> ParameterLocation {
+        match self {
+            ParameterObject::Path { .. } => ParameterLocation::Path,
+            ParameterObject::Query { .. } => ParameterLocation::Query,
+            ParameterObject::Header { .. } => ParameterLocation::Header,
+            ParameterObject::Cookie { .. } => ParameterLocation::Cookie,
+        }
+    }
+
+    pub fn fixed_fields(&self) -> &ParameterObjectFixedFields {
+        match self {
+            ParameterObject::Path { fixed_fields }
+            | ParameterObject::Query { fixed_fields, .. }
+            | ParameterObject::Header { fixed_fields }
+            | ParameterObject::Cookie { fixed_fields } => fixed_fields,
+        }
+    }
+
+    pub fn fixed_fields_mut(&mut self) -> &mut ParameterObjectFixedFields {
+        match self {
+            ParameterObject::Path { fixed_fields }
+            | ParameterObject::Query { fixed_fields, .. }
+            | ParameterObject::Header { fixed_fields }
+            | ParameterObject::Cookie { fixed_fields } => fixed_fields,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.fixed_fields().name
+    }
+
+    /// Whether the parameter is mandatory, path parameters always are.
+    pub fn is_required(&self) -> bool {
+        self.location() == ParameterLocation::Path || self.fixed_fields().required
+    }
+}
@@ -1,9 +1,10 @@
+use http::Method;
 use serde::{Deserialize, Serialize};
 
 use super::{Extensions, OperationObject, ParameterObject, ReferenceObjectOr, ServerObject};
 
 /// Describes the operations available on a single path. A Path Item MAY be empty, due to ACL constraints. The path itself is still exposed to the documentation viewer but they will not know which operations and parameters are available.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct PathsItemObject {
     /// An optional, string summary, intended to apply to all operations in this path.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -44,3 +45,64 @@ pub struct PathsItemObject {
     #[serde(flatten)]
     pub extensions: Extensions,
 }
+
+impl PathsItemObject {
+    /// Returns the operation defined for `method`, if any.
+    pub fn operation(&self, method: &Method) -> Option<&OperationObject> {
+        match *method {
+            Method::GET => self.get.as_ref(),
+            Method::PUT => self.put.as_ref(),
+            Method::POST => self.post.as_ref(),
+            Method::DELETE => self.delete.as_ref(),
+            Method::OPTIONS => self.options.as_ref(),
+            Method::HEAD => self.head.as_ref(),
+            Method::PATCH => self.patch.as_ref(),
+            Method::TRACE => self.trace.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the field holding the operation for `method`, or `None` if a path item can't describe `method` operations.
+    pub fn operation_entry(&mut self, method: &Method) -> Option<&mut Option<OperationObject>> {
+        match *method {
+            Method::GET => Some(&mut self.get),
+            Method::PUT => Some(&mut self.put),
+            Method::POST => Some(&mut self.post),
+            Method::DELETE => Some(&mut self.delete),
+            Method::OPTIONS => Some(&mut self.options),
+            Method::HEAD => Some(&mut self.head),
+            Method::PATCH => Some(&mut self.patch),
+            Method::TRACE => Some(&mut self.trace),
+            _ => None,
+        }
+    }
+
+    /// Iterates over every operation defined on this path, in the order they are declared by the specification.
+    pub fn operations(&self) -> impl Iterator<Item = (Method, &OperationObject)> {
+        IntoIterator::into_iter([
+            (Method::GET, &self.get),
+            (Method::PUT, &self.put),
+            (Method::POST, &self.post),
+            (Method::DELETE, &self.delete),
+            (Method::OPTIONS, &self.options),
+            (Method::HEAD, &self.head),
+            (Method::PATCH, &self.patch),
+            (Method::TRACE, &self.trace),
+        ])
+        .filter_map(|(method, operation)| operation.as_ref().map(|operation| (method, operation)))
+    }
+
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = (Method, &mut OperationObject)> {
+        IntoIterator::into_iter([
+            (Method::GET, &mut self.get),
+            (Method::PUT, &mut self.put),
+            (Method::POST, &mut self.post),
+            (Method::DELETE, &mut self.delete),
+            (Method::OPTIONS, &mut self.options),
+            (Method::HEAD, &mut self.head),
+            (Method::PATCH, &mut self.patch),
+            (Method::TRACE, &mut self.trace),
+        ])
+        .filter_map(|(method, operation)| operation.as_mut().map(|operation| (method, operation)))
+    }
+}
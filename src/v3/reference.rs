@@ -1,13 +1,117 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{Component, ComponentRef, OpenAPIObject};
 
 // TODO: It might me a better idea, even if more complicated, to drop this enum and implement ref for some objects.
 // That way it would better documented and more comformant to the spec
-#[derive(Debug, Deserialize, Serialize)]
+// NOTE: The reference variant must come first, otherwise objects whose fields are all optional (like the Schema Object) would swallow every `$ref`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum ReferenceObjectOr<T> {
-    Object(T),
     ReferenceObject {
         #[serde(rename = "$ref")]
         reference: String,
     },
+    Object(T),
+}
+
+impl<T> ReferenceObjectOr<T> {
+    /// Returns the inline object, if this is not a reference.
+    pub fn as_object(&self) -> Option<&T> {
+        match self {
+            ReferenceObjectOr::Object(object) => Some(object),
+            ReferenceObjectOr::ReferenceObject { .. } => None,
+        }
+    }
+
+    /// Returns the `$ref` string, if this is a reference.
+    pub fn as_reference(&self) -> Option<&str> {
+        match self {
+            ReferenceObjectOr::Object(_) => None,
+            ReferenceObjectOr::ReferenceObject { reference } => Some(reference),
+        }
+    }
 }
+
+impl<T> From<T> for ReferenceObjectOr<T> {
+    fn from(object: T) -> Self {
+        ReferenceObjectOr::Object(object)
+    }
+}
+
+impl<T: Component> ReferenceObjectOr<T> {
+    /// A reference to the component `name` of the kind of `T`, e.g. `#/components/schemas/Pet`.
+    pub fn component(name: impl Into<String>) -> Self {
+        ReferenceObjectOr::ReferenceObject {
+            reference: ComponentRef::new(T::KIND, name).to_reference(),
+        }
+    }
+
+    /// Follows references, possibly through several hops, until an inline object is found in the components of `document`.
+    /// Only local references to `#/components` are supported.
+    pub fn resolve<'a>(&'a self, document: &'a OpenAPIObject) -> Result<&'a T, ResolveError> {
+        let mut current = self;
+        let mut visited: Vec<&str> = Vec::new();
+
+        loop {
+            let reference = match current {
+                ReferenceObjectOr::Object(object) => return Ok(object),
+                ReferenceObjectOr::ReferenceObject { reference } => reference.as_str(),
+            };
+
+            if visited.contains(&reference) {
+                return Err(ResolveError::Cycle(reference.to_string()));
+            }
+            visited.push(reference);
+
+            let component = ComponentRef::parse(reference)
+                .ok_or_else(|| ResolveError::Unsupported(reference.to_string()))?;
+
+            if component.kind != T::KIND {
+                return Err(ResolveError::KindMismatch(reference.to_string()));
+            }
+
+            current = document
+                .components
+                .as_ref()
+                .and_then(T::components)
+                .and_then(|components| components.get(&component.name))
+                .ok_or_else(|| ResolveError::NotFound(reference.to_string()))?;
+        }
+    }
+}
+
+/// The reasons a [`ReferenceObjectOr`] could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The reference does not point inside `#/components`, e.g. it's a remote or relative document.
+    Unsupported(String),
+    /// The reference points to a different kind of component than the one expected.
+    KindMismatch(String),
+    /// No component is registered under the referenced name.
+    NotFound(String),
+    /// The reference eventually points back to itself.
+    Cycle(String),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Unsupported(reference) => {
+                write!(f, "unsupported reference `{}`", reference)
+            }
+            ResolveError::KindMismatch(reference) => {
+                write!(
+                    f,
+                    "reference `{}` points to the wrong kind of component",
+                    reference
+                )
+            }
+            ResolveError::NotFound(reference) => write!(f, "reference `{}` not found", reference),
+            ResolveError::Cycle(reference) => write!(f, "reference `{}` is cyclic", reference),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
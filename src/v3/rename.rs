@@ -0,0 +1,342 @@
+use indexmap::IndexMap;
+use std::{collections::HashMap, fmt};
+
+use super::{
+    ComponentKind, ComponentRef, OpenAPIObject, PathsItemObject, ReferenceObjectOr, References,
+    SecurityRequirementObject,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// There is no component to rename.
+    NotFound(ComponentRef),
+    /// Another component already has the new name.
+    NameTaken(ComponentRef),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::NotFound(component) => write!(f, "`{}` does not exist", component),
+            RenameError::NameTaken(component) => write!(f, "`{}` already exists", component),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+impl OpenAPIObject {
+    /// Renames a component, keeping its position among the components of its kind, and rewrites every reference to it.
+    ///
+    /// Discriminator mappings are rewritten along with `$ref`s, and renaming a security scheme renames it in the security requirements of the document, its operations and their callbacks.
+    pub fn rename_component(
+        &mut self,
+        kind: ComponentKind,
+        old: &str,
+        new: &str,
+    ) -> Result<(), RenameError> {
+        let components = self
+            .components
+            .as_mut()
+            .ok_or_else(|| RenameError::NotFound(ComponentRef::new(kind, old)))?;
+
+        let renamed = match kind {
+            ComponentKind::Schemas => rename_entry(components.schemas.as_mut(), old, new),
+            ComponentKind::Responses => rename_entry(components.responses.as_mut(), old, new),
+            ComponentKind::Parameters => rename_entry(components.parameters.as_mut(), old, new),
+            ComponentKind::Examples => rename_entry(components.examples.as_mut(), old, new),
+            ComponentKind::RequestBodies => {
+                rename_entry(components.request_bodies.as_mut(), old, new)
+            }
+            ComponentKind::Headers => rename_entry(components.headers.as_mut(), old, new),
+            ComponentKind::SecuritySchemes => {
+                rename_entry(Some(&mut components.security_schemes), old, new)
+            }
+            ComponentKind::Links => rename_entry(components.links.as_mut(), old, new),
+            ComponentKind::Callbacks => rename_entry(components.callbacks.as_mut(), old, new),
+        };
+
+        match renamed {
+            Renamed::Done => {}
+            Renamed::Unchanged => return Ok(()),
+            Renamed::NotFound => return Err(RenameError::NotFound(ComponentRef::new(kind, old))),
+            Renamed::NameTaken => return Err(RenameError::NameTaken(ComponentRef::new(kind, new))),
+        }
+
+        let mut renames = HashMap::new();
+        renames.insert(ComponentRef::new(kind, old), new.to_string());
+        rename_references(self, &renames);
+
+        Ok(())
+    }
+}
+
+enum Renamed {
+    Done,
+    Unchanged,
+    NotFound,
+    NameTaken,
+}
+
+fn rename_entry<T>(map: Option<&mut IndexMap<String, T>>, old: &str, new: &str) -> Renamed {
+    let map = match map {
+        Some(map) if map.contains_key(old) => map,
+        _ => return Renamed::NotFound,
+    };
+
+    if old == new {
+        return Renamed::Unchanged;
+    }
+    if map.contains_key(new) {
+        return Renamed::NameTaken;
+    }
+
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(name, object)| {
+            if name == old {
+                (new.to_string(), object)
+            } else {
+                (name, object)
+            }
+        })
+        .collect();
+
+    Renamed::Done
+}
+
+/// Rewrites the references to the components renamed in `renames`, as well as the security requirements naming renamed security schemes.
+pub(crate) fn rename_references(
+    document: &mut OpenAPIObject,
+    renames: &HashMap<ComponentRef, String>,
+) {
+    document.visit_references_mut(&mut |reference| {
+        if let Some(component) = ComponentRef::parse(reference) {
+            if let Some(name) = renames.get(&component) {
+                *reference = ComponentRef::new(component.kind, name.as_str()).to_reference();
+            }
+        }
+    });
+
+    // Security requirements refer to security schemes by name rather than with a `$ref`
+    let rename_requirement = |requirement: &mut SecurityRequirementObject| {
+        *requirement = std::mem::take(requirement)
+            .into_iter()
+            .map(|(name, scopes)| {
+                let component = ComponentRef::new(ComponentKind::SecuritySchemes, name);
+                match renames.get(&component) {
+                    Some(name) => (name.clone(), scopes),
+                    None => (component.name, scopes),
+                }
+            })
+            .collect();
+    };
+    document
+        .security
+        .iter_mut()
+        .flatten()
+        .for_each(rename_requirement);
+    for path_item in document.paths.values_mut() {
+        if let ReferenceObjectOr::Object(path_item) = path_item {
+            rename_requirements(path_item, &rename_requirement);
+        }
+    }
+    let callbacks = document
+        .components
+        .iter_mut()
+        .flat_map(|components| components.callbacks.iter_mut().flatten());
+    for (_, callback) in callbacks {
+        if let ReferenceObjectOr::Object(callback) = callback {
+            for path_item in callback.values_mut() {
+                rename_requirements(path_item, &rename_requirement);
+            }
+        }
+    }
+}
+
+/// Renames the security requirements of the operations of `path_item` and of their callbacks.
+fn rename_requirements(
+    path_item: &mut PathsItemObject,
+    rename_requirement: &dyn Fn(&mut SecurityRequirementObject),
+) {
+    for (_, operation) in path_item.operations_mut() {
+        operation
+            .security
+            .iter_mut()
+            .flatten()
+            .for_each(rename_requirement);
+
+        for callback in operation
+            .callbacks
+            .iter_mut()
+            .flat_map(IndexMap::values_mut)
+        {
+            if let ReferenceObjectOr::Object(callback) = callback {
+                for path_item in callback.values_mut() {
+                    rename_requirements(path_item, rename_requirement);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rename_security_scheme() {
+        let mut document: OpenAPIObject = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Hooks, version: "1"}
+servers: []
+security: [{apiKey: []}]
+paths:
+  /hooks:
+    post:
+      security: [{apiKey: []}, {oauth: [write]}]
+      responses: {"201": {description: Created}}
+      callbacks:
+        event:
+          "{$request.body#/url}":
+            post:
+              security: [{apiKey: []}]
+              responses: {"200": {description: Received}}
+components:
+  callbacks:
+    ping:
+      "{$request.body#/url}":
+        get:
+          security: [{apiKey: []}]
+          responses: {"200": {description: Pong}}
+  securitySchemes:
+    apiKey: {type: apiKey, name: key, in: header}
+    oauth: {type: http, scheme: bearer}
+"##,
+        )
+        .unwrap();
+
+        document
+            .rename_component(ComponentKind::SecuritySchemes, "apiKey", "key")
+            .unwrap();
+
+        let document = serde_json::to_value(&document).unwrap();
+        assert_eq!(document["security"], json!([{ "key": [] }]));
+        let operation = &document["paths"]["/hooks"]["post"];
+        assert_eq!(
+            operation["security"],
+            json!([{ "key": [] }, { "oauth": ["write"] }])
+        );
+        assert_eq!(
+            operation["callbacks"]["event"]["{$request.body#/url}"]["post"]["security"],
+            json!([{ "key": [] }])
+        );
+        assert_eq!(
+            document["components"]["callbacks"]["ping"]["{$request.body#/url}"]["get"]["security"],
+            json!([{ "key": [] }])
+        );
+        assert_eq!(
+            document["components"]["securitySchemes"]["key"]["name"],
+            "key"
+        );
+    }
+
+    fn pets() -> OpenAPIObject {
+        serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Pets, version: "1"}
+servers: []
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: A pet.
+          content:
+            application/json:
+              schema:
+                oneOf:
+                  - {$ref: "#/components/schemas/Cat"}
+                  - {$ref: "#/components/schemas/Dog"}
+                discriminator:
+                  propertyName: kind
+                  mapping:
+                    cat: "#/components/schemas/Cat"
+                    dog: Dog
+components:
+  schemas:
+    Cat: {type: object}
+    Dog: {type: object}
+    Bird: {type: object}
+"##,
+        )
+        .unwrap()
+    }
+
+    fn schema_names(document: &OpenAPIObject) -> Vec<&str> {
+        let schemas = document.components.as_ref().unwrap().schemas.as_ref();
+        schemas.unwrap().keys().map(String::as_str).collect()
+    }
+
+    fn mapping(document: &OpenAPIObject) -> serde_json::Value {
+        let document = serde_json::to_value(document).unwrap();
+        document["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"]["discriminator"]["mapping"]
+            .clone()
+    }
+
+    #[test]
+    fn rename_schema() {
+        let mut document = pets();
+        document
+            .rename_component(ComponentKind::Schemas, "Cat", "Feline")
+            .unwrap();
+        document
+            .rename_component(ComponentKind::Schemas, "Dog", "Canine")
+            .unwrap();
+
+        // Full references stay full and bare names stay bare
+        assert_eq!(
+            mapping(&document),
+            json!({"cat": "#/components/schemas/Feline", "dog": "Canine"})
+        );
+        assert_eq!(schema_names(&document), ["Feline", "Canine", "Bird"]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut document = pets();
+
+        assert_eq!(
+            document.rename_component(ComponentKind::Schemas, "Cat", "Dog"),
+            Err(RenameError::NameTaken(ComponentRef::new(
+                ComponentKind::Schemas,
+                "Dog"
+            )))
+        );
+        assert_eq!(
+            document.rename_component(ComponentKind::Schemas, "Fish", "Trout"),
+            Err(RenameError::NotFound(ComponentRef::new(
+                ComponentKind::Schemas,
+                "Fish"
+            )))
+        );
+        assert_eq!(
+            document.rename_component(ComponentKind::Responses, "Cat", "Feline"),
+            Err(RenameError::NotFound(ComponentRef::new(
+                ComponentKind::Responses,
+                "Cat"
+            )))
+        );
+
+        // Renaming to the same name changes nothing
+        assert_eq!(
+            document.rename_component(ComponentKind::Schemas, "Cat", "Cat"),
+            Ok(())
+        );
+        assert_eq!(document, pets());
+    }
+}
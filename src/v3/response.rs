@@ -1,14 +1,16 @@
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use super::{LinkObject, MediaTypeObject, ReferenceObjectOr};
+use super::{ContentMap, HeaderObject, LinkObject, ReferenceObjectOr};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct ResponseObject {
     pub description: String,
-    // TODO: headers
+    /// Maps a header name to its definition. [RFC7230](https://tools.ietf.org/html/rfc7230#page-22) states header names are case insensitive. If a response header is defined with the name "Content-Type", it SHALL be ignored.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<IndexMap<String, MediaTypeObject>>,
+    pub headers: Option<IndexMap<String, ReferenceObjectOr<HeaderObject>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<ContentMap>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub links: Option<IndexMap<String, ReferenceObjectOr<LinkObject>>>,
 }
@@ -1,12 +1,384 @@
+use http::StatusCode;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 
-use super::{ReferenceObjectOr, ResponseObject};
+use super::{Extensions, ReferenceObjectOr, ResponseObject};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, PartialEq, Serialize)]
 pub struct ResponsesObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<ReferenceObjectOr<ResponseObject>>,
     #[serde(flatten)]
-    pub responses: IndexMap<String, ReferenceObjectOr<ResponseObject>>, // TODO: Should be a http status code instead of a String
+    pub responses: IndexMap<ResponseKey, ReferenceObjectOr<ResponseObject>>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl ResponsesObject {
+    /// Returns the response that applies to `status`: an exact match wins over a range like `2XX`, which wins over the `default` response.
+    pub fn for_status(&self, status: StatusCode) -> Option<&ReferenceObjectOr<ResponseObject>> {
+        self.responses
+            .get(&ResponseKey::Status(status))
+            .or_else(|| {
+                let range = StatusRange::of(status)?;
+                self.responses.get(&ResponseKey::Range(range))
+            })
+            .or(self.default.as_ref())
+    }
+}
+
+/// The key of a response, either an exact HTTP status code or a range of them such as `2XX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResponseKey {
+    Status(StatusCode),
+    Range(StatusRange),
+}
+
+impl ResponseKey {
+    /// Whether `status` is covered by this key.
+    pub fn matches(&self, status: StatusCode) -> bool {
+        match self {
+            ResponseKey::Status(expected) => *expected == status,
+            ResponseKey::Range(range) => range.contains(status),
+        }
+    }
+}
+
+/// The ranges of status codes a response can be declared for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StatusRange {
+    /// `1XX`
+    Informational,
+    /// `2XX`
+    Success,
+    /// `3XX`
+    Redirection,
+    /// `4XX`
+    ClientError,
+    /// `5XX`
+    ServerError,
+}
+
+impl StatusRange {
+    /// Returns the range `status` belongs to, or `None` if it is outside of 100 to 599.
+    pub fn of(status: StatusCode) -> Option<Self> {
+        Some(match status.as_u16() {
+            100..=199 => StatusRange::Informational,
+            200..=299 => StatusRange::Success,
+            300..=399 => StatusRange::Redirection,
+            400..=499 => StatusRange::ClientError,
+            500..=599 => StatusRange::ServerError,
+            _ => return None,
+        })
+    }
+
+    pub fn contains(&self, status: StatusCode) -> bool {
+        StatusRange::of(status) == Some(*self)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusRange::Informational => "1XX",
+            StatusRange::Success => "2XX",
+            StatusRange::Redirection => "3XX",
+            StatusRange::ClientError => "4XX",
+            StatusRange::ServerError => "5XX",
+        }
+    }
+}
+
+impl fmt::Display for ResponseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseKey::Status(status) => f.write_str(status.as_str()),
+            ResponseKey::Range(range) => f.write_str(range.as_str()),
+        }
+    }
+}
+
+/// The error returned when a string is neither a status code between 100 and 599 nor a range between `1XX` and `5XX`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidResponseKey(String);
+
+impl fmt::Display for InvalidResponseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a valid HTTP status code or range", self.0)
+    }
+}
+
+impl std::error::Error for InvalidResponseKey {}
+
+impl FromStr for ResponseKey {
+    type Err = InvalidResponseKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = match s {
+            "1XX" => Some(StatusRange::Informational),
+            "2XX" => Some(StatusRange::Success),
+            "3XX" => Some(StatusRange::Redirection),
+            "4XX" => Some(StatusRange::ClientError),
+            "5XX" => Some(StatusRange::ServerError),
+            _ => None,
+        };
+
+        if let Some(range) = range {
+            return Ok(ResponseKey::Range(range));
+        }
+
+        s.parse::<u16>()
+            .ok()
+            .filter(|code| (100..=599).contains(code) && s.len() == 3)
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .map(ResponseKey::Status)
+            .ok_or_else(|| InvalidResponseKey(s.to_string()))
+    }
+}
+
+impl From<StatusCode> for ResponseKey {
+    fn from(status: StatusCode) -> Self {
+        ResponseKey::Status(status)
+    }
+}
+
+impl From<StatusRange> for ResponseKey {
+    fn from(range: StatusRange) -> Self {
+        ResponseKey::Range(range)
+    }
+}
+
+impl Serialize for ResponseKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ResponseKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ResponseKeyVisitor;
+
+        impl<'de> de::Visitor<'de> for ResponseKeyVisitor {
+            type Value = ResponseKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an HTTP status code or a range like 2XX")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+
+            // Status codes are often left unquoted in YAML documents
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(&value.to_string())
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(&value.to_string())
+            }
+        }
+
+        deserializer.deserialize_str(ResponseKeyVisitor)
+    }
+}
+
+/// A key of the Responses Object.
+enum ResponsesField {
+    Default,
+    Response(ResponseKey),
+    Extension(String),
+}
+
+impl<'de> Deserialize<'de> for ResponsesField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ResponsesFieldVisitor;
+
+        impl<'de> de::Visitor<'de> for ResponsesFieldVisitor {
+            type Value = ResponsesField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter
+                    .write_str("`default`, an HTTP status code, a range like 2XX or an extension")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(match value {
+                    "default" => ResponsesField::Default,
+                    _ if value.starts_with("x-") => ResponsesField::Extension(value.to_string()),
+                    _ => ResponsesField::Response(value.parse().map_err(E::custom)?),
+                })
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(&value.to_string())
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_str(&value.to_string())
+            }
+        }
+
+        deserializer.deserialize_str(ResponsesFieldVisitor)
+    }
+}
+
+// Extensions are told apart from response keys before those are parsed, which two flattened maps can't do
+impl<'de> Deserialize<'de> for ResponsesObject {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ResponsesObjectVisitor;
+
+        impl<'de> de::Visitor<'de> for ResponsesObjectVisitor {
+            type Value = ResponsesObject;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a Responses Object")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut responses = ResponsesObject::default();
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        ResponsesField::Default => responses.default = Some(map.next_value()?),
+                        ResponsesField::Response(key) => {
+                            responses.responses.insert(key, map.next_value()?);
+                        }
+                        ResponsesField::Extension(name) => {
+                            responses.extensions.insert(name, map.next_value()?);
+                        }
+                    }
+                }
+                Ok(responses)
+            }
+        }
+
+        deserializer.deserialize_map(ResponsesObjectVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_keys_round_trip() {
+        let responses: ResponsesObject = serde_yaml::from_str(
+            r#"
+default: {description: An error.}
+200: {description: Quoted or not, codes are parsed.}
+"404": {description: Not found.}
+4XX: {description: A client error.}
+"#,
+        )
+        .unwrap();
+        let keys: Vec<&ResponseKey> = responses.responses.keys().collect();
+        assert_eq!(
+            keys,
+            [
+                &ResponseKey::Status(StatusCode::OK),
+                &ResponseKey::Status(StatusCode::NOT_FOUND),
+                &ResponseKey::Range(StatusRange::ClientError),
+            ]
+        );
+
+        let json = serde_json::to_value(&responses).unwrap();
+        let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["200", "404", "4XX", "default"]);
+        assert_eq!(
+            serde_json::from_value::<ResponsesObject>(json).unwrap(),
+            responses
+        );
+
+        for invalid in ["2xx", "6XX", "099", "600", "0200", "20", "default"] {
+            assert_eq!(
+                invalid.parse::<ResponseKey>(),
+                Err(InvalidResponseKey(invalid.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn for_status() {
+        let responses: ResponsesObject = serde_yaml::from_str(
+            r#"
+404: {description: Exact}
+4XX: {description: Range}
+default: {description: Default}
+"#,
+        )
+        .unwrap();
+        let description = |status: u16| {
+            responses
+                .for_status(StatusCode::from_u16(status).unwrap())
+                .and_then(ReferenceObjectOr::as_object)
+                .map(|response| response.description.as_str())
+        };
+
+        assert_eq!(description(404), Some("Exact"));
+        assert_eq!(description(400), Some("Range"));
+        assert_eq!(description(200), Some("Default"));
+
+        let responses: ResponsesObject = serde_yaml::from_str("200: {description: OK}").unwrap();
+        assert!(responses.for_status(StatusCode::CREATED).is_none());
+
+        // Codes past 599 belong to no range
+        let responses: ResponsesObject =
+            serde_yaml::from_str("5XX: {description: A server error.}").unwrap();
+        let status = StatusCode::from_u16(600).unwrap();
+        assert_eq!(StatusRange::of(status), None);
+        assert!(responses.for_status(status).is_none());
+        assert!(responses.for_status(StatusCode::BAD_GATEWAY).is_some());
+    }
+
+    #[test]
+    fn extensions() {
+        let responses: ResponsesObject = serde_yaml::from_str(
+            r#"
+200: {description: OK}
+x-foo: {bar: 1}
+"#,
+        )
+        .unwrap();
+        assert_eq!(responses.responses.len(), 1);
+        assert_eq!(responses.extensions["x-foo"], serde_json::json!({"bar": 1}));
+
+        let json = serde_json::to_value(&responses).unwrap();
+        assert_eq!(json["x-foo"]["bar"], 1);
+        assert_eq!(
+            serde_json::from_value::<ResponsesObject>(json).unwrap(),
+            responses
+        );
+    }
 }
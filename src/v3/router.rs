@@ -0,0 +1,304 @@
+use http::Method;
+use indexmap::IndexMap;
+use percent_encoding::percent_decode_str;
+use std::fmt;
+use url::Url;
+
+use super::{OpenAPIObject, OperationObject, PathsItemObject, ReferenceObjectOr, ServerObject};
+
+/// Finds the operation of a document that handles a request.
+///
+/// Paths are matched after stripping the path of any of the document [servers](ServerObject), with concrete paths taking precedence over templated ones,
+/// so `/users/me` is preferred to `/users/{id}`.
+#[derive(Debug)]
+pub struct Router<'a> {
+    document: &'a OpenAPIObject,
+    base_paths: Vec<String>,
+    routes: Vec<Route<'a>>,
+}
+
+#[derive(Debug)]
+struct Route<'a> {
+    template: &'a str,
+    path_item: &'a PathsItemObject,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Literal(String),
+    Template(Vec<Part>),
+}
+
+#[derive(Debug)]
+enum Part {
+    Literal(String),
+    Variable(String),
+}
+
+/// An operation matched by a [`Router`].
+#[derive(Debug, Clone)]
+pub struct RouteMatch<'a> {
+    /// The templated path as it appears in the document, e.g. `/users/{id}`.
+    pub template: &'a str,
+    pub path_item: &'a PathsItemObject,
+    pub method: Method,
+    pub operation: &'a OperationObject,
+    /// The decoded values of the path template variables.
+    pub path_parameters: IndexMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    /// No path of the document matches the request path.
+    NotFound,
+    /// A path matches but it doesn't define an operation for the request method.
+    MethodNotAllowed { allowed: Vec<Method> },
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::NotFound => f.write_str("no path matches the request"),
+            RouteError::MethodNotAllowed { allowed } => {
+                f.write_str("method not allowed, expected one of: ")?;
+                for (i, method) in allowed.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(method.as_str())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
+impl<'a> Router<'a> {
+    pub fn new(document: &'a OpenAPIObject) -> Self {
+        let mut base_paths: Vec<String> = document.servers.iter().map(base_path).collect();
+        if base_paths.is_empty() {
+            base_paths.push(String::new());
+        }
+        // Try the most specific prefixes first
+        base_paths.sort_by_key(|path| std::cmp::Reverse(path.len()));
+        base_paths.dedup();
+
+        let mut routes: Vec<Route<'a>> = document
+            .paths
+            .iter()
+            .filter_map(|(template, path_item)| match path_item {
+                ReferenceObjectOr::Object(path_item) => Some(Route {
+                    template,
+                    path_item,
+                    segments: parse_template(template),
+                }),
+                // Path items can only reference external documents
+                ReferenceObjectOr::ReferenceObject { .. } => None,
+            })
+            .collect();
+
+        routes.sort_by_key(Route::specificity);
+
+        Self {
+            document,
+            base_paths,
+            routes,
+        }
+    }
+
+    pub fn document(&self) -> &'a OpenAPIObject {
+        self.document
+    }
+
+    /// Finds the operation handling `method` requests to `path`, which must not include the query string.
+    pub fn find(&self, method: &Method, path: &str) -> Result<RouteMatch<'a>, RouteError> {
+        let mut allowed: Option<Vec<Method>> = None;
+
+        for base_path in &self.base_paths {
+            let path = match strip_base_path(path, base_path) {
+                Some(path) => path,
+                None => continue,
+            };
+            let segments: Vec<&str> = path.split('/').skip(1).collect();
+
+            for route in &self.routes {
+                let path_parameters = match route.matches(&segments) {
+                    Some(path_parameters) => path_parameters,
+                    None => continue,
+                };
+
+                match route.path_item.operation(method) {
+                    Some(operation) => {
+                        return Ok(RouteMatch {
+                            template: route.template,
+                            path_item: route.path_item,
+                            method: method.clone(),
+                            operation,
+                            path_parameters,
+                        })
+                    }
+                    None => {
+                        allowed.get_or_insert_with(|| {
+                            route
+                                .path_item
+                                .operations()
+                                .map(|(method, _)| method)
+                                .collect()
+                        });
+                    }
+                }
+            }
+        }
+
+        match allowed {
+            Some(allowed) => Err(RouteError::MethodNotAllowed { allowed }),
+            None => Err(RouteError::NotFound),
+        }
+    }
+}
+
+impl Route<'_> {
+    /// Literal segments sort before templated ones, so that concrete paths are tried first.
+    fn specificity(&self) -> Vec<u8> {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(_) => 0,
+                Segment::Template(_) => 1,
+            })
+            .collect()
+    }
+
+    fn matches(&self, segments: &[&str]) -> Option<IndexMap<String, String>> {
+        if segments.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut captures = IndexMap::new();
+
+        for (expected, actual) in self.segments.iter().zip(segments) {
+            match expected {
+                Segment::Literal(literal) => {
+                    if literal != actual {
+                        return None;
+                    }
+                }
+                Segment::Template(parts) => {
+                    if !match_parts(parts, actual, &mut captures) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        Some(
+            captures
+                .into_iter()
+                .map(|(name, value): (String, &str)| {
+                    (
+                        name,
+                        percent_decode_str(value).decode_utf8_lossy().into_owned(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Matches a single path segment against template parts, backtracking to support segments like `{name}.{extension}`.
+fn match_parts<'s>(
+    parts: &[Part],
+    input: &'s str,
+    captures: &mut IndexMap<String, &'s str>,
+) -> bool {
+    match parts.split_first() {
+        None => input.is_empty(),
+        Some((Part::Literal(literal), rest)) => match input.strip_prefix(literal.as_str()) {
+            Some(input) => match_parts(rest, input, captures),
+            None => false,
+        },
+        Some((Part::Variable(name), rest)) => {
+            // Variables can't be empty, try the longest capture first so that `{name}.{extension}` splits on the last dot
+            let ends = input
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain(Some(input.len()))
+                .filter(|&end| end > 0)
+                .rev();
+
+            for end in ends {
+                if match_parts(rest, &input[end..], captures) {
+                    captures.insert(name.clone(), &input[..end]);
+                    return true;
+                }
+            }
+            false
+        }
+    }
+}
+
+fn parse_template(template: &str) -> Vec<Segment> {
+    template
+        .split('/')
+        .skip(1)
+        .map(|segment| {
+            if !segment.contains('{') {
+                return Segment::Literal(segment.to_string());
+            }
+
+            let mut parts = Vec::new();
+            let mut rest = segment;
+
+            while let Some(start) = rest.find('{') {
+                let end = match rest[start..].find('}') {
+                    Some(end) => start + end,
+                    None => break,
+                };
+
+                if start > 0 {
+                    parts.push(Part::Literal(rest[..start].to_string()));
+                }
+                parts.push(Part::Variable(rest[start + 1..end].to_string()));
+                rest = &rest[end + 1..];
+            }
+
+            if !rest.is_empty() {
+                parts.push(Part::Literal(rest.to_string()));
+            }
+
+            Segment::Template(parts)
+        })
+        .collect()
+}
+
+/// Extracts the path of a server URL, substituting variables with their default values.
+fn base_path(server: &ServerObject) -> String {
+    let mut url = server.url.clone();
+
+    for (name, variable) in server.variables.iter().flatten() {
+        url = url.replace(&format!("{{{}}}", name), &variable.default);
+    }
+
+    let path = match Url::parse(&url) {
+        Ok(url) => url.path().to_string(),
+        // Relative URLs are relative to where the document is served, only their path matters
+        Err(_) => url,
+    };
+
+    path.trim_end_matches('/').to_string()
+}
+
+fn strip_base_path<'p>(path: &'p str, base_path: &str) -> Option<&'p str> {
+    let path = path.strip_prefix(base_path)?;
+
+    if path.is_empty() {
+        Some("/")
+    } else if path.starts_with('/') {
+        Some(path)
+    } else {
+        None
+    }
+}
@@ -4,12 +4,12 @@ use serde_json::Value;
 use std::num::NonZeroU32;
 
 use super::{
-    DiscriminatorObject, ExternalDocumentationObject, MediaTypeObject, ReferenceObjectOr, XmlObject,
+    ContentMap, DiscriminatorObject, ExternalDocumentationObject, ReferenceObjectOr, XmlObject,
 };
 
 /// The Schema Object allows the definition of input and output data types. These types can be objects, but also primitives and arrays. This object is an extended subset of the [JSON Schema Specification Wright Draft 00](https://json-schema.org/).
 /// For more information about the properties, see [JSON Schema Core](https://tools.ietf.org/html/draft-wright-json-schema-00) and [JSON Schema Validation](https://tools.ietf.org/html/draft-wright-json-schema-validation-00). Unless stated otherwise, the property definitions follow the JSON Schema.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct SchemaObject {
     // This are all fixed fields
     #[serde(default)]
@@ -49,13 +49,92 @@ pub struct SchemaObject {
     pub pattern: Option<String>,
     #[serde(rename = "maxItems", skip_serializing_if = "Option::is_none")]
     pub max_items: Option<u32>,
-    // TODO
+    #[serde(rename = "minItems", skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<u32>,
+    #[serde(default, rename = "uniqueItems")]
+    pub unique_items: bool,
+    #[serde(rename = "maxProperties", skip_serializing_if = "Option::is_none")]
+    pub max_properties: Option<u32>,
+    #[serde(rename = "minProperties", skip_serializing_if = "Option::is_none")]
+    pub min_properties: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<Vec<String>>,
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enumeration: Option<Vec<Value>>,
+    // This fields are taken from the JSON Schema definition but their definitions were adjusted to the OpenAPI Specification
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub schema_type: Option<SchemaType>,
+    #[serde(rename = "allOf", skip_serializing_if = "Option::is_none")]
+    pub all_of: Option<Vec<ReferenceObjectOr<SchemaObject>>>,
+    #[serde(rename = "oneOf", skip_serializing_if = "Option::is_none")]
+    pub one_of: Option<Vec<ReferenceObjectOr<SchemaObject>>>,
+    #[serde(rename = "anyOf", skip_serializing_if = "Option::is_none")]
+    pub any_of: Option<Vec<ReferenceObjectOr<SchemaObject>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not: Option<Box<ReferenceObjectOr<SchemaObject>>>,
+    /// Value MUST be an object and not an array. Inline or referenced schema MUST be of a Schema Object and not a standard JSON Schema. `items` MUST be present if the `type` is `array`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<ReferenceObjectOr<SchemaObject>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<IndexMap<String, ReferenceObjectOr<SchemaObject>>>,
+    #[serde(
+        rename = "additionalProperties",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub additional_properties: Option<AdditionalProperties>,
+    /// [CommonMark syntax](https://spec.commonmark.org/) MAY be used for rich text representation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// See [Data Type Formats](https://spec.openapis.org/oas/v3.0.3#dataTypeFormat) for further details. While relying on JSON Schema's defined formats, the OAS offers a few additional predefined formats.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The default value represents what would be assumed by the consumer of the input as the value of the schema if one is not provided. Unlike JSON Schema, the value MUST conform to the defined type for the Schema Object defined at the same level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+}
+
+/// The primitive [data types](https://spec.openapis.org/oas/v3.0.3#dataTypes) a Schema Object can describe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SchemaType {
+    #[serde(rename = "string")]
+    String,
+    #[serde(rename = "number")]
+    Number,
+    #[serde(rename = "integer")]
+    Integer,
+    #[serde(rename = "boolean")]
+    Boolean,
+    #[serde(rename = "array")]
+    Array,
+    #[serde(rename = "object")]
+    Object,
+}
+
+impl SchemaType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaType::String => "string",
+            SchemaType::Number => "number",
+            SchemaType::Integer => "integer",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Array => "array",
+            SchemaType::Object => "object",
+        }
+    }
+}
+
+/// Value can be boolean or object. Inline or referenced schema MUST be of a Schema Object and not a standard JSON Schema. Consistent with JSON Schema, `additionalProperties` defaults to `true`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum AdditionalProperties {
+    Allowed(bool),
+    Schema(Box<ReferenceObjectOr<SchemaObject>>),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum SchemaOrContentObject {
     #[serde(rename = "schema")]
-    Schema(ReferenceObjectOr<SchemaObject>),
-    #[serde(rename = "conent")]
-    Content(IndexMap<String, MediaTypeObject>),
+    Schema(Box<ReferenceObjectOr<SchemaObject>>),
+    #[serde(rename = "content")]
+    Content(ContentMap),
 }
@@ -4,7 +4,7 @@ use url::Url;
 use super::OAuthFlowsObject;
 
 /// Defines a security scheme that can be used by the operations. Supported schemes are HTTP authentication, an API key (either as a header, a cookie parameter or as a query parameter), OAuth2's common flows (implicit, password, client credentials and authorization code) as defined in [RFC6749](https://tools.ietf.org/html/rfc6749), and [OpenID Connect Discovery](https://tools.ietf.org/html/draft-ietf-oauth-discovery-06).
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum SecuritySchemeObject {
     #[serde(rename = "apiKey")]
@@ -49,7 +49,7 @@ pub enum SecuritySchemeObject {
     // TODO: Specification Extensions.
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum ApiKeyLocation {
     #[serde(rename = "query")]
     Query,
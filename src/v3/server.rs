@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use super::{Extensions, ServerVariableObject};
 
 /// An object representing a Server.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct ServerObject {
     /// A URL to the target host. This URL supports Server Variables and MAY be relative, to indicate that the host location is relative to the location where the OpenAPI document is being served. Variable substitutions will be made when a variable is named in {brackets}.
     pub url: String,
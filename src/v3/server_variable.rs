@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::Extensions;
 
 /// An object representing a Server Variable for server URL template substitution.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct ServerVariableObject {
     /// An enumeration of string values to be used if the substitution options are from a limited set. The array SHOULD NOT be empty.
     #[serde(rename = "enum")]
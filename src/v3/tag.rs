@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::{Extensions, ExternalDocumentationObject};
 
 /// Adds metadata to a single tag that is used by the [Operation Object](OperationObject). It is not mandatory to have a Tag Object per tag defined in the Operation Object instances.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct TagObject {
     /// The name of the tag.
     pub name: String,
@@ -0,0 +1,474 @@
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::{
+    AdditionalProperties, ComponentKind, ComponentRef, ComponentsObject, ReferenceObjectOr,
+    SchemaObject, SchemaType,
+};
+
+#[cfg(feature = "derive")]
+pub use oa_derive::ToSchema;
+
+/// A Rust type described by a schema.
+///
+/// With the `derive` feature, it can be derived for structs and enums, honoring their serde attributes: `rename`, `rename_all`, `skip`, `flatten`, `default`, `tag`, `content` and `untagged`.
+/// Doc comments become descriptions, and `Option` fields aren't required.
+/// Types without generic parameters are named components, which other schemas reference.
+pub trait ToSchema {
+    /// The name of the component schema of the type, or `None` if it is inlined where it is used.
+    fn schema_name() -> Option<String> {
+        None
+    }
+
+    /// The schema of the type.
+    fn schema() -> SchemaObject;
+
+    /// Adds the component schemas of this type and of the types it is made of to `components`, keeping those that are already there.
+    fn register(components: &mut ComponentsObject) {
+        let _ = components;
+    }
+
+    /// A reference to the component schema of the type, or its schema if it is inlined.
+    fn reference_or_schema() -> ReferenceObjectOr<SchemaObject> {
+        match Self::schema_name() {
+            Some(name) => ReferenceObjectOr::ReferenceObject {
+                reference: ComponentRef::new(ComponentKind::Schemas, name).to_reference(),
+            },
+            None => ReferenceObjectOr::Object(Self::schema()),
+        }
+    }
+}
+
+impl ComponentsObject {
+    /// Adds the component schemas of `T` and of the types it is made of, keeping those that are already there.
+    pub fn register<T: ToSchema + ?Sized>(&mut self) {
+        T::register(self);
+    }
+}
+
+fn typed(schema_type: SchemaType, format: Option<&str>) -> SchemaObject {
+    SchemaObject {
+        schema_type: Some(schema_type),
+        format: format.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty => $schema_type:ident $(($format:literal))?),* $(,)?) => {
+        $(
+            impl ToSchema for $ty {
+                fn schema() -> SchemaObject {
+                    typed(SchemaType::$schema_type, None $(.or(Some($format)))?)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    bool => Boolean,
+    i8 => Integer("int32"),
+    i16 => Integer("int32"),
+    i32 => Integer("int32"),
+    i64 => Integer("int64"),
+    isize => Integer("int64"),
+    u8 => Integer("int32"),
+    u16 => Integer("int32"),
+    u32 => Integer("int64"),
+    u64 => Integer("int64"),
+    usize => Integer("int64"),
+    f32 => Number("float"),
+    f64 => Number("double"),
+    char => String,
+    str => String,
+    String => String,
+}
+
+impl ToSchema for Value {
+    fn schema() -> SchemaObject {
+        SchemaObject::default()
+    }
+}
+
+impl ToSchema for () {
+    fn schema() -> SchemaObject {
+        __private::unit()
+    }
+}
+
+impl<T: ToSchema + ?Sized> ToSchema for &T {
+    fn schema_name() -> Option<String> {
+        T::schema_name()
+    }
+
+    fn schema() -> SchemaObject {
+        T::schema()
+    }
+
+    fn register(components: &mut ComponentsObject) {
+        T::register(components);
+    }
+}
+
+impl<T: ToSchema + ?Sized> ToSchema for Box<T> {
+    fn schema_name() -> Option<String> {
+        T::schema_name()
+    }
+
+    fn schema() -> SchemaObject {
+        T::schema()
+    }
+
+    fn register(components: &mut ComponentsObject) {
+        T::register(components);
+    }
+}
+
+impl<T: ToSchema> ToSchema for Option<T> {
+    fn schema() -> SchemaObject {
+        SchemaObject {
+            nullable: true,
+            ..__private::inline(T::reference_or_schema())
+        }
+    }
+
+    fn register(components: &mut ComponentsObject) {
+        T::register(components);
+    }
+}
+
+macro_rules! impl_array {
+    ($($ty:ident, $unique:literal;)*) => {
+        $(
+            impl<T: ToSchema> ToSchema for $ty<T> {
+                fn schema() -> SchemaObject {
+                    SchemaObject {
+                        items: Some(Box::new(T::reference_or_schema())),
+                        unique_items: $unique,
+                        ..typed(SchemaType::Array, None)
+                    }
+                }
+
+                fn register(components: &mut ComponentsObject) {
+                    T::register(components);
+                }
+            }
+        )*
+    };
+}
+
+impl_array! {
+    Vec, false;
+    HashSet, true;
+    BTreeSet, true;
+}
+
+impl<T: ToSchema> ToSchema for [T] {
+    fn schema() -> SchemaObject {
+        Vec::<T>::schema()
+    }
+
+    fn register(components: &mut ComponentsObject) {
+        T::register(components);
+    }
+}
+
+macro_rules! impl_map {
+    ($($ty:ident),*) => {
+        $(
+            impl<K, V: ToSchema, S> ToSchema for $ty<K, V, S> {
+                fn schema() -> SchemaObject {
+                    map::<V>()
+                }
+
+                fn register(components: &mut ComponentsObject) {
+                    V::register(components);
+                }
+            }
+        )*
+    };
+}
+
+impl_map!(HashMap, IndexMap);
+
+impl<K, V: ToSchema> ToSchema for BTreeMap<K, V> {
+    fn schema() -> SchemaObject {
+        map::<V>()
+    }
+
+    fn register(components: &mut ComponentsObject) {
+        V::register(components);
+    }
+}
+
+/// The schema of a map from strings to `V`, which is how serde serializes any map.
+fn map<V: ToSchema>() -> SchemaObject {
+    SchemaObject {
+        additional_properties: Some(AdditionalProperties::Schema(Box::new(
+            V::reference_or_schema(),
+        ))),
+        ..typed(SchemaType::Object, None)
+    }
+}
+
+/// Helpers for the code generated by the derive and attribute macros, which isn't part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    use http::StatusCode;
+    use indexmap::IndexMap;
+    use serde_json::Value;
+
+    use crate::v3::{
+        builder::json_content, ComponentsObject, ParameterLocation, ParameterObject,
+        ParameterObjectBuilder, ReferenceObjectOr, RequestBodyObject, ResponseKey, ResponseObject,
+        ResponsesObject, SchemaObject, SchemaType,
+    };
+
+    pub use http::Method;
+
+    /// Adds the component schema `name` unless it is already there, returning whether it was added.
+    pub fn insert_schema(
+        components: &mut ComponentsObject,
+        name: String,
+        schema: impl FnOnce() -> SchemaObject,
+    ) -> bool {
+        let schemas = components.schemas.get_or_insert_with(Default::default);
+        if schemas.contains_key(&name) {
+            return false;
+        }
+
+        schemas.insert(name, ReferenceObjectOr::Object(schema()));
+        true
+    }
+
+    /// `schema` itself, or a schema made of the single schema it references.
+    pub fn inline(schema: ReferenceObjectOr<SchemaObject>) -> SchemaObject {
+        match schema {
+            ReferenceObjectOr::Object(schema) => schema,
+            reference => SchemaObject {
+                all_of: Some(vec![reference]),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// `schema` described by `description`, which references can't be, so they are wrapped in an `allOf`.
+    pub fn described(
+        schema: ReferenceObjectOr<SchemaObject>,
+        description: &str,
+    ) -> ReferenceObjectOr<SchemaObject> {
+        ReferenceObjectOr::Object(SchemaObject {
+            description: Some(description.to_string()),
+            ..inline(schema)
+        })
+    }
+
+    /// An object with `properties`, given along with whether they are required.
+    pub fn object(properties: Vec<(&str, ReferenceObjectOr<SchemaObject>, bool)>) -> SchemaObject {
+        let required: Vec<String> = properties
+            .iter()
+            .filter(|(_, _, required)| *required)
+            .map(|(name, _, _)| name.to_string())
+            .collect();
+
+        SchemaObject {
+            schema_type: Some(SchemaType::Object),
+            properties: Some(
+                properties
+                    .into_iter()
+                    .map(|(name, schema, _)| (name.to_string(), schema))
+                    .collect(),
+            ),
+            required: if required.is_empty() {
+                None
+            } else {
+                Some(required)
+            },
+            ..Default::default()
+        }
+    }
+
+    /// `object` along with the flattened schemas.
+    pub fn flattened(
+        object: SchemaObject,
+        flattened: Vec<ReferenceObjectOr<SchemaObject>>,
+    ) -> SchemaObject {
+        if flattened.is_empty() {
+            return object;
+        }
+
+        let mut all_of = vec![ReferenceObjectOr::Object(object)];
+        all_of.extend(flattened);
+        SchemaObject {
+            all_of: Some(all_of),
+            ..Default::default()
+        }
+    }
+
+    /// A fixed size array of `items`, like tuples.
+    pub fn tuple(items: Vec<ReferenceObjectOr<SchemaObject>>) -> SchemaObject {
+        let len = items.len() as u32;
+        let mut distinct: Vec<ReferenceObjectOr<SchemaObject>> = Vec::new();
+        for item in items {
+            if !distinct.contains(&item) {
+                distinct.push(item);
+            }
+        }
+
+        let items = if distinct.len() == 1 {
+            distinct.remove(0)
+        } else {
+            ReferenceObjectOr::Object(SchemaObject {
+                one_of: Some(distinct),
+                ..Default::default()
+            })
+        };
+        SchemaObject {
+            schema_type: Some(SchemaType::Array),
+            items: Some(Box::new(items)),
+            min_items: Some(len),
+            max_items: Some(len),
+            ..Default::default()
+        }
+    }
+
+    /// The schema of `null`, which is how serde serializes unit structs and untagged unit variants.
+    pub fn unit() -> SchemaObject {
+        SchemaObject {
+            nullable: true,
+            enumeration: Some(vec![Value::Null]),
+            ..Default::default()
+        }
+    }
+
+    /// A string that is one of `values`.
+    pub fn string_enum(values: &[&str]) -> SchemaObject {
+        SchemaObject {
+            schema_type: Some(SchemaType::String),
+            enumeration: Some(values.iter().map(|value| Value::from(*value)).collect()),
+            ..Default::default()
+        }
+    }
+
+    /// A variant of an externally tagged enum: an object with a single property, `name`, holding `content`, or the string `name` for unit variants.
+    pub fn externally_tagged(
+        name: &str,
+        content: Option<ReferenceObjectOr<SchemaObject>>,
+    ) -> ReferenceObjectOr<SchemaObject> {
+        ReferenceObjectOr::Object(match content {
+            Some(content) => object(vec![(name, content, true)]),
+            None => string_enum(&[name]),
+        })
+    }
+
+    /// A variant of an internally tagged enum: `content` with a `tag` property of `name`.
+    pub fn internally_tagged(
+        tag: &str,
+        name: &str,
+        content: Option<ReferenceObjectOr<SchemaObject>>,
+    ) -> ReferenceObjectOr<SchemaObject> {
+        let tag_property = (tag, ReferenceObjectOr::Object(string_enum(&[name])), true);
+
+        ReferenceObjectOr::Object(match content {
+            None => object(vec![tag_property]),
+            Some(ReferenceObjectOr::Object(mut content)) if content.all_of.is_none() => {
+                let (tag, schema, _) = tag_property;
+                let mut properties = IndexMap::new();
+                properties.insert(tag.to_string(), schema);
+                properties.extend(content.properties.take().into_iter().flatten());
+                content.properties = Some(properties);
+                content
+                    .required
+                    .get_or_insert_with(Vec::new)
+                    .insert(0, tag.to_string());
+                content
+            }
+            Some(content) => SchemaObject {
+                all_of: Some(vec![
+                    ReferenceObjectOr::Object(object(vec![tag_property])),
+                    content,
+                ]),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// A variant of an adjacently tagged enum: an object with a `tag` property of `name` and a `content` property.
+    pub fn adjacently_tagged(
+        tag: &str,
+        content_name: &str,
+        name: &str,
+        content: Option<ReferenceObjectOr<SchemaObject>>,
+    ) -> ReferenceObjectOr<SchemaObject> {
+        let mut properties = vec![(tag, ReferenceObjectOr::Object(string_enum(&[name])), true)];
+        if let Some(content) = content {
+            properties.push((content_name, content, true));
+        }
+        ReferenceObjectOr::Object(object(properties))
+    }
+
+    /// A variant of an untagged enum: its content, or `null` for unit variants.
+    pub fn untagged(
+        content: Option<ReferenceObjectOr<SchemaObject>>,
+    ) -> ReferenceObjectOr<SchemaObject> {
+        content.unwrap_or_else(|| ReferenceObjectOr::Object(unit()))
+    }
+
+    /// A schema that is one of `variants`.
+    pub fn one_of(variants: Vec<ReferenceObjectOr<SchemaObject>>) -> SchemaObject {
+        SchemaObject {
+            one_of: Some(variants),
+            ..Default::default()
+        }
+    }
+
+    /// A parameter in `location` with the given schema.
+    pub fn parameter(
+        location: ParameterLocation,
+        name: &str,
+        required: bool,
+        schema: ReferenceObjectOr<SchemaObject>,
+    ) -> ReferenceObjectOr<ParameterObject> {
+        let mut parameter = ParameterObjectBuilder::new(location, name).schema(schema);
+        if required {
+            parameter = parameter.required();
+        }
+
+        ReferenceObjectOr::Object(parameter.build().expect("the schema is set"))
+    }
+
+    /// A JSON request body with the given schema.
+    pub fn json_body(
+        schema: ReferenceObjectOr<SchemaObject>,
+        required: bool,
+    ) -> ReferenceObjectOr<RequestBodyObject> {
+        ReferenceObjectOr::Object(RequestBodyObject {
+            description: None,
+            content: json_content(schema),
+            required,
+        })
+    }
+
+    /// The single response with `status`, holding JSON with the given schema if any.
+    pub fn json_response(
+        status: u16,
+        schema: Option<ReferenceObjectOr<SchemaObject>>,
+    ) -> ResponsesObject {
+        let status = StatusCode::from_u16(status).unwrap();
+        let response = ResponseObject {
+            description: status.canonical_reason().unwrap_or_default().to_string(),
+            headers: None,
+            content: schema.map(json_content),
+            links: None,
+        };
+
+        let mut responses = ResponsesObject::default();
+        responses.responses.insert(
+            ResponseKey::Status(status),
+            ReferenceObjectOr::Object(response),
+        );
+        responses
+    }
+}
@@ -0,0 +1,107 @@
+use super::{filter::reachable_components, ComponentRef, OpenAPIObject};
+
+/// A component that can't be reached from the paths of its document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedComponent {
+    pub component: ComponentRef,
+    /// The [JSON Pointer](https://tools.ietf.org/html/rfc6901) to the component, e.g. `/components/schemas/Pet`.
+    pub pointer: String,
+}
+
+impl OpenAPIObject {
+    /// Lists the components that are neither used by the paths, directly or through other components, nor required as security schemes.
+    ///
+    /// Components only referenced by other unused components are reported too, including cycles of unused schemas.
+    pub fn unused_components(&self) -> Vec<UnusedComponent> {
+        let reachable = reachable_components(self);
+
+        self.components
+            .iter()
+            .flat_map(|components| components.component_refs())
+            .filter(|component| !reachable.contains(component))
+            .map(|component| UnusedComponent {
+                pointer: component.to_pointer(),
+                component,
+            })
+            .collect()
+    }
+
+    /// Removes the [unused components](Self::unused_components), returning what was removed.
+    pub fn remove_unused(&mut self) -> Vec<ComponentRef> {
+        let removed: Vec<ComponentRef> = self
+            .unused_components()
+            .into_iter()
+            .map(|unused| unused.component)
+            .collect();
+
+        if let Some(components) = &mut self.components {
+            components.retain(|component| !removed.contains(component));
+
+            if components.is_empty() {
+                self.components = None;
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::ComponentKind;
+
+    #[test]
+    fn remove_unused() {
+        let mut document: OpenAPIObject = serde_yaml::from_str(
+            r##"
+openapi: 3.0.3
+info: {title: Pets, version: "1"}
+servers: []
+security: [{apiKey: []}]
+paths:
+  /pets:
+    get:
+      responses: {"200": {$ref: "#/components/responses/Pets"}}
+components:
+  responses:
+    Pets:
+      description: Pets
+      content: {application/json: {schema: {$ref: "#/components/schemas/Pets"}}}
+  schemas:
+    Pets: {type: array, items: {$ref: "#/components/schemas/Pet"}}
+    Pet: {type: object}
+    Owner: {type: object, properties: {pets: {$ref: "#/components/schemas/Pets"}, home: {$ref: "#/components/schemas/Home"}}}
+    Home: {type: object, properties: {owner: {$ref: "#/components/schemas/Owner"}}}
+  securitySchemes:
+    apiKey: {type: apiKey, name: key, in: header}
+    oauth: {type: http, scheme: bearer}
+"##,
+        )
+        .unwrap();
+
+        // Chains and cycles of unused components are removed at once
+        let schema = |name: &str| ComponentRef::new(ComponentKind::Schemas, name);
+        assert_eq!(
+            document.remove_unused(),
+            [
+                schema("Owner"),
+                schema("Home"),
+                ComponentRef::new(ComponentKind::SecuritySchemes, "oauth"),
+            ]
+        );
+        assert!(document.unused_components().is_empty());
+        assert!(document.remove_unused().is_empty());
+
+        let components = document.components.as_ref().unwrap();
+        assert_eq!(
+            components.component_refs(),
+            [
+                schema("Pets"),
+                schema("Pet"),
+                ComponentRef::new(ComponentKind::Responses, "Pets"),
+                ComponentRef::new(ComponentKind::SecuritySchemes, "apiKey"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use http::StatusCode;
+use std::fmt;
+
+use crate::v3::{router::RouteError, ResolveError, SchemaType};
+
+/// A single problem found while validating a value, located by a [JSON Pointer](https://tools.ietf.org/html/rfc6901) into the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub pointer: String,
+    pub kind: ValidationErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    /// The value is not of the declared `type`, or is `null` while the schema isn't `nullable`.
+    Type {
+        expected: SchemaType,
+        found: &'static str,
+    },
+    /// The registered validator for `format` rejected the value.
+    Format(String),
+    /// No validator is registered for `format`, only reported if the registry is configured to do so.
+    UnknownFormat(String),
+    /// The value is not one of the `enum` values.
+    Enum,
+    MultipleOf(u32),
+    Maximum {
+        maximum: i32,
+        exclusive: bool,
+    },
+    Minimum {
+        minimum: i32,
+        exclusive: bool,
+    },
+    MaxLength(u32),
+    MinLength(u32),
+    /// The value does not match `pattern`.
+    Pattern(String),
+    /// `pattern` is not a valid regular expression.
+    InvalidPattern(String),
+    MaxItems(u32),
+    MinItems(u32),
+    UniqueItems,
+    MaxProperties(u32),
+    MinProperties(u32),
+    /// A `required` property is missing.
+    Required(String),
+    /// A property is not allowed by `additionalProperties`.
+    AdditionalProperty(String),
+    /// The value matches none or more than one of the `oneOf` schemas.
+    OneOf {
+        matched: usize,
+    },
+    /// The value matches none of the `anyOf` schemas.
+    AnyOf,
+    /// The value matches the `not` schema.
+    Not,
+    /// A referenced object could not be resolved.
+    Reference(ResolveError),
+    /// The request could not be routed to an operation.
+    Route(RouteError),
+    /// A required parameter is missing.
+    MissingParameter,
+    /// A required request body is missing.
+    MissingBody,
+    /// A body was sent without a `Content-Type`.
+    MissingContentType,
+    /// The `Content-Type` of the body is not described by the content map.
+    UnsupportedMediaType(String),
+    /// The body could not be decoded according to its `Content-Type`.
+    InvalidBody(String),
+    /// A body was sent where none is described.
+    UnexpectedBody,
+    /// No response is declared for the status code, and there is no `default` response.
+    UndocumentedStatus(StatusCode),
+    /// A required response header is missing.
+    MissingHeader,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pointer.is_empty() {
+            self.kind.fmt(f)
+        } else {
+            write!(f, "{}: {}", self.pointer, self.kind)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationErrorKind::Type { expected, found } => {
+                write!(f, "expected {}, found {}", expected.as_str(), found)
+            }
+            ValidationErrorKind::Format(format) => {
+                write!(f, "value is not a valid `{}`", format)
+            }
+            ValidationErrorKind::UnknownFormat(format) => write!(f, "unknown format `{}`", format),
+            ValidationErrorKind::Enum => f.write_str("value is not one of the allowed values"),
+            ValidationErrorKind::MultipleOf(multiple_of) => {
+                write!(f, "value is not a multiple of {}", multiple_of)
+            }
+            ValidationErrorKind::Maximum { maximum, exclusive } => {
+                let operator = if *exclusive { "less than" } else { "at most" };
+                write!(f, "value must be {} {}", operator, maximum)
+            }
+            ValidationErrorKind::Minimum { minimum, exclusive } => {
+                let operator = if *exclusive {
+                    "greater than"
+                } else {
+                    "at least"
+                };
+                write!(f, "value must be {} {}", operator, minimum)
+            }
+            ValidationErrorKind::MaxLength(max) => {
+                write!(f, "string must be at most {} characters long", max)
+            }
+            ValidationErrorKind::MinLength(min) => {
+                write!(f, "string must be at least {} characters long", min)
+            }
+            ValidationErrorKind::Pattern(pattern) => {
+                write!(f, "string does not match `{}`", pattern)
+            }
+            ValidationErrorKind::InvalidPattern(pattern) => {
+                write!(f, "`{}` is not a valid pattern", pattern)
+            }
+            ValidationErrorKind::MaxItems(max) => {
+                write!(f, "array must have at most {} items", max)
+            }
+            ValidationErrorKind::MinItems(min) => {
+                write!(f, "array must have at least {} items", min)
+            }
+            ValidationErrorKind::UniqueItems => f.write_str("array items must be unique"),
+            ValidationErrorKind::MaxProperties(max) => {
+                write!(f, "object must have at most {} properties", max)
+            }
+            ValidationErrorKind::MinProperties(min) => {
+                write!(f, "object must have at least {} properties", min)
+            }
+            ValidationErrorKind::Required(property) => {
+                write!(f, "missing required property `{}`", property)
+            }
+            ValidationErrorKind::AdditionalProperty(property) => {
+                write!(f, "property `{}` is not allowed", property)
+            }
+            ValidationErrorKind::OneOf { matched } => write!(
+                f,
+                "value must match exactly one schema in `oneOf`, but matched {}",
+                matched
+            ),
+            ValidationErrorKind::AnyOf => {
+                f.write_str("value must match at least one schema in `anyOf`")
+            }
+            ValidationErrorKind::Not => f.write_str("value must not match the `not` schema"),
+            ValidationErrorKind::Reference(error) => error.fmt(f),
+            ValidationErrorKind::Route(error) => error.fmt(f),
+            ValidationErrorKind::MissingParameter => f.write_str("missing required parameter"),
+            ValidationErrorKind::MissingBody => f.write_str("missing required body"),
+            ValidationErrorKind::MissingContentType => f.write_str("missing content type"),
+            ValidationErrorKind::UnsupportedMediaType(media_type) => {
+                write!(f, "unsupported media type `{}`", media_type)
+            }
+            ValidationErrorKind::InvalidBody(error) => write!(f, "invalid body: {}", error),
+            ValidationErrorKind::UnexpectedBody => f.write_str("unexpected body"),
+            ValidationErrorKind::UndocumentedStatus(status) => {
+                write!(f, "undocumented status code {}", status.as_u16())
+            }
+            ValidationErrorKind::MissingHeader => f.write_str("missing required header"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// The outcome of a validation: every error and warning found.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Converts the report into a [`Result`], dropping the warnings.
+    pub fn into_result(self) -> Result<(), Vec<ValidationError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    pub(crate) fn error(&mut self, pointer: &str, kind: ValidationErrorKind) {
+        self.errors.push(ValidationError {
+            pointer: pointer.to_string(),
+            kind,
+        });
+    }
+
+    pub(crate) fn warning(&mut self, pointer: &str, kind: ValidationErrorKind) {
+        self.warnings.push(ValidationError {
+            pointer: pointer.to_string(),
+            kind,
+        });
+    }
+}
@@ -0,0 +1,355 @@
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::fmt;
+
+use super::{ValidationError, Validator};
+use crate::utils::push_pointer;
+use crate::v3::{
+    AdditionalProperties, CallbackObject, ContentMap, ExampleObject, HeaderObject, OpenAPIObject,
+    ParameterObject, PathsItemObject, ReferenceObjectOr, ResponseObject, SchemaObject,
+    SchemaOrContentObject,
+};
+
+/// A problem with an example embedded in a document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExampleError {
+    /// The example doesn't satisfy the schema next to it, or its own schema for the `example` of a Schema Object.
+    Mismatch {
+        /// A [JSON Pointer](https://tools.ietf.org/html/rfc6901) to the example value in the document.
+        example: String,
+        /// A pointer to the schema in the document, the referenced one if the schema is a local `$ref`.
+        schema: String,
+        /// The errors, located in the example value.
+        errors: Vec<ValidationError>,
+    },
+    /// Both `example` and `examples` are set on the object at `pointer`, while they are mutually exclusive.
+    Conflict { pointer: String },
+}
+
+impl fmt::Display for ExampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExampleError::Mismatch {
+                example,
+                schema,
+                errors,
+            } => {
+                write!(f, "{} doesn't match the schema at {}", example, schema)?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
+            ExampleError::Conflict { pointer } => {
+                write!(f, "{} sets both `example` and `examples`", pointer)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExampleError {}
+
+/// Validates every example embedded in `document` against its schema.
+///
+/// This is a shorthand for [`Validator::validate_examples`] with the default formats.
+pub fn validate_examples(document: &OpenAPIObject) -> Vec<ExampleError> {
+    Validator::new().validate_examples(document)
+}
+
+impl<'a> Validator<'a> {
+    /// Validates the inline `example` and `examples` values of the media types, parameters, headers and schemas of `document`, resolving references against it.
+    ///
+    /// Media types, parameters and headers have their examples validated against their schema, schemas against themselves. Examples without a schema,
+    /// and those only given by an `externalValue`, aren't validated.
+    pub fn validate_examples(&self, document: &OpenAPIObject) -> Vec<ExampleError> {
+        let mut checker = ExampleChecker {
+            validator: self.clone().with_document(document),
+            errors: Vec::new(),
+        };
+        checker.document(document);
+        checker.errors
+    }
+}
+
+struct ExampleChecker<'d> {
+    validator: Validator<'d>,
+    errors: Vec<ExampleError>,
+}
+
+impl ExampleChecker<'_> {
+    fn document(&mut self, document: &OpenAPIObject) {
+        for (template, path_item) in &document.paths {
+            if let ReferenceObjectOr::Object(path_item) = path_item {
+                self.path_item(path_item, &push_pointer("/paths", template));
+            }
+        }
+
+        let components = match &document.components {
+            Some(components) => components,
+            None => return,
+        };
+        for (name, schema) in components.schemas.iter().flatten() {
+            self.schema(schema, &push_pointer("/components/schemas", name));
+        }
+        for (name, response) in components.responses.iter().flatten() {
+            if let ReferenceObjectOr::Object(response) = response {
+                self.response(response, &push_pointer("/components/responses", name));
+            }
+        }
+        for (name, parameter) in components.parameters.iter().flatten() {
+            if let ReferenceObjectOr::Object(parameter) = parameter {
+                self.parameter(parameter, &push_pointer("/components/parameters", name));
+            }
+        }
+        for (name, request_body) in components.request_bodies.iter().flatten() {
+            if let ReferenceObjectOr::Object(request_body) = request_body {
+                let pointer = push_pointer("/components/requestBodies", name);
+                self.content(&request_body.content, &push_pointer(&pointer, "content"));
+            }
+        }
+        for (name, header) in components.headers.iter().flatten() {
+            if let ReferenceObjectOr::Object(header) = header {
+                self.header(header, &push_pointer("/components/headers", name));
+            }
+        }
+        for (name, callback) in components.callbacks.iter().flatten() {
+            if let ReferenceObjectOr::Object(callback) = callback {
+                self.callback(callback, &push_pointer("/components/callbacks", name));
+            }
+        }
+    }
+
+    fn path_item(&mut self, path_item: &PathsItemObject, pointer: &str) {
+        self.parameters(path_item.parameters.as_ref(), pointer);
+
+        for (method, operation) in path_item.operations() {
+            let pointer = push_pointer(pointer, &method.as_str().to_lowercase());
+
+            self.parameters(operation.parameters.as_ref(), &pointer);
+            if let Some(ReferenceObjectOr::Object(request_body)) = &operation.request_body {
+                let pointer = push_pointer(&push_pointer(&pointer, "requestBody"), "content");
+                self.content(&request_body.content, &pointer);
+            }
+
+            let responses_pointer = push_pointer(&pointer, "responses");
+            if let Some(ReferenceObjectOr::Object(response)) = &operation.responses.default {
+                self.response(response, &push_pointer(&responses_pointer, "default"));
+            }
+            for (key, response) in &operation.responses.responses {
+                if let ReferenceObjectOr::Object(response) = response {
+                    self.response(
+                        response,
+                        &push_pointer(&responses_pointer, &key.to_string()),
+                    );
+                }
+            }
+
+            for (name, callback) in operation.callbacks.iter().flatten() {
+                if let ReferenceObjectOr::Object(callback) = callback {
+                    let pointer = push_pointer(&push_pointer(&pointer, "callbacks"), name);
+                    self.callback(callback, &pointer);
+                }
+            }
+        }
+    }
+
+    fn callback(&mut self, callback: &CallbackObject, pointer: &str) {
+        for (expression, path_item) in callback {
+            self.path_item(path_item, &push_pointer(pointer, expression));
+        }
+    }
+
+    fn parameters(
+        &mut self,
+        parameters: Option<&Vec<ReferenceObjectOr<ParameterObject>>>,
+        pointer: &str,
+    ) {
+        let pointer = push_pointer(pointer, "parameters");
+        for (i, parameter) in parameters.into_iter().flatten().enumerate() {
+            if let ReferenceObjectOr::Object(parameter) = parameter {
+                self.parameter(parameter, &push_pointer(&pointer, &i.to_string()));
+            }
+        }
+    }
+
+    fn parameter(&mut self, parameter: &ParameterObject, pointer: &str) {
+        let fixed_fields = parameter.fixed_fields();
+        self.schema_or_content(
+            &fixed_fields.schema,
+            fixed_fields.example.as_ref(),
+            fixed_fields.examples.as_ref(),
+            pointer,
+        );
+    }
+
+    fn response(&mut self, response: &ResponseObject, pointer: &str) {
+        for (name, header) in response.headers.iter().flatten() {
+            if let ReferenceObjectOr::Object(header) = header {
+                self.header(
+                    header,
+                    &push_pointer(&push_pointer(pointer, "headers"), name),
+                );
+            }
+        }
+        if let Some(content) = &response.content {
+            self.content(content, &push_pointer(pointer, "content"));
+        }
+    }
+
+    fn header(&mut self, header: &HeaderObject, pointer: &str) {
+        self.schema_or_content(
+            &header.schema,
+            header.example.as_ref(),
+            header.examples.as_ref(),
+            pointer,
+        );
+    }
+
+    /// Checks a parameter or a header, whose examples are validated against the schema of its single media type when it has `content`.
+    fn schema_or_content(
+        &mut self,
+        schema: &SchemaOrContentObject,
+        example: Option<&Value>,
+        examples: Option<&IndexMap<String, ExampleObject>>,
+        pointer: &str,
+    ) {
+        match schema {
+            SchemaOrContentObject::Schema(schema) => {
+                let schema_pointer = push_pointer(pointer, "schema");
+                self.examples(Some(schema), &schema_pointer, example, examples, pointer);
+                self.schema(schema, &schema_pointer);
+            }
+            SchemaOrContentObject::Content(content) => {
+                let content_pointer = push_pointer(pointer, "content");
+                let media = content.iter().next().map(|(media_type, media)| {
+                    let pointer = push_pointer(&content_pointer, &media_type.to_string());
+                    (media.schema.as_ref(), push_pointer(&pointer, "schema"))
+                });
+                if let Some((schema, schema_pointer)) = media {
+                    self.examples(schema, &schema_pointer, example, examples, pointer);
+                }
+                self.content(content, &content_pointer);
+            }
+        }
+    }
+
+    fn content(&mut self, content: &ContentMap, pointer: &str) {
+        for (media_type, media) in content {
+            let pointer = push_pointer(pointer, &media_type.to_string());
+            let schema_pointer = push_pointer(&pointer, "schema");
+            self.examples(
+                media.schema.as_ref(),
+                &schema_pointer,
+                media.example.as_ref(),
+                media.examples.as_ref(),
+                &pointer,
+            );
+            if let Some(schema) = &media.schema {
+                self.schema(schema, &schema_pointer);
+            }
+        }
+    }
+
+    /// Checks the `example` and `examples` of the object at `pointer`.
+    fn examples(
+        &mut self,
+        schema: Option<&ReferenceObjectOr<SchemaObject>>,
+        schema_pointer: &str,
+        example: Option<&Value>,
+        examples: Option<&IndexMap<String, ExampleObject>>,
+        pointer: &str,
+    ) {
+        if example.is_some() && examples.is_some() {
+            self.errors.push(ExampleError::Conflict {
+                pointer: pointer.to_string(),
+            });
+        }
+
+        let schema = match schema {
+            Some(schema) => schema,
+            None => return,
+        };
+        if let Some(example) = example {
+            self.validate(
+                schema,
+                schema_pointer,
+                example,
+                &push_pointer(pointer, "example"),
+            );
+        }
+        for (name, example) in examples.into_iter().flatten() {
+            if let Some(value) = &example.value {
+                let pointer = push_pointer(
+                    &push_pointer(&push_pointer(pointer, "examples"), name),
+                    "value",
+                );
+                self.validate(schema, schema_pointer, value, &pointer);
+            }
+        }
+    }
+
+    fn validate(
+        &mut self,
+        schema: &ReferenceObjectOr<SchemaObject>,
+        schema_pointer: &str,
+        value: &Value,
+        pointer: &str,
+    ) {
+        let report = self.validator.validate(schema, value);
+        if report.is_valid() {
+            return;
+        }
+
+        let schema = match schema {
+            ReferenceObjectOr::ReferenceObject { reference } if reference.starts_with('#') => {
+                reference[1..].to_string()
+            }
+            _ => schema_pointer.to_string(),
+        };
+        self.errors.push(ExampleError::Mismatch {
+            example: pointer.to_string(),
+            schema,
+            errors: report.errors,
+        });
+    }
+
+    /// Checks the `example` of `schema` and of the schemas nested in it. References are checked where they point to.
+    fn schema(&mut self, schema: &ReferenceObjectOr<SchemaObject>, pointer: &str) {
+        let object = match schema {
+            ReferenceObjectOr::Object(object) => object,
+            ReferenceObjectOr::ReferenceObject { .. } => return,
+        };
+
+        if let Some(example) = &object.example {
+            self.validate(schema, pointer, example, &push_pointer(pointer, "example"));
+        }
+
+        for (field, schemas) in [
+            ("allOf", &object.all_of),
+            ("oneOf", &object.one_of),
+            ("anyOf", &object.any_of),
+        ] {
+            for (i, schema) in schemas.iter().flatten().enumerate() {
+                self.schema(
+                    schema,
+                    &push_pointer(&push_pointer(pointer, field), &i.to_string()),
+                );
+            }
+        }
+        if let Some(not) = &object.not {
+            self.schema(not, &push_pointer(pointer, "not"));
+        }
+        if let Some(items) = &object.items {
+            self.schema(items, &push_pointer(pointer, "items"));
+        }
+        for (name, property) in object.properties.iter().flatten() {
+            self.schema(
+                property,
+                &push_pointer(&push_pointer(pointer, "properties"), name),
+            );
+        }
+        if let Some(AdditionalProperties::Schema(additional)) = &object.additional_properties {
+            self.schema(additional, &push_pointer(pointer, "additionalProperties"));
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+};
+use url::Url;
+
+/// Checks whether a value conforms to a `format`.
+///
+/// Validators receive every value whose schema declares their format, so they should accept values of types the format does not apply to,
+/// e.g. a string format should let numbers through and leave them to the `type` check.
+pub trait FormatValidator: Send + Sync {
+    fn validate(&self, value: &Value) -> bool;
+}
+
+impl<F> FormatValidator for F
+where
+    F: Fn(&Value) -> bool + Send + Sync,
+{
+    fn validate(&self, value: &Value) -> bool {
+        self(value)
+    }
+}
+
+/// What to do when a schema declares a format no validator is registered for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownFormatPolicy {
+    /// Treat the format as an annotation, this is what JSON Schema mandates.
+    #[default]
+    Ignore,
+    /// Report a warning but consider the value valid.
+    Warn,
+    /// Report an error.
+    Error,
+}
+
+/// A set of named [`FormatValidator`]s.
+///
+/// The default registry knows about every format defined by the OpenAPI Specification plus the most common JSON Schema ones:
+/// `date`, `date-time`, `email`, `uuid`, `uri`, `ipv4`, `ipv6`, `byte`, `binary`, `password`, `int32`, `int64`, `float` and `double`.
+#[derive(Clone)]
+pub struct FormatRegistry {
+    validators: HashMap<String, Arc<dyn FormatValidator>>,
+    unknown: UnknownFormatPolicy,
+}
+
+impl FormatRegistry {
+    /// Creates a registry without any format.
+    pub fn empty() -> Self {
+        Self {
+            validators: HashMap::new(),
+            unknown: UnknownFormatPolicy::default(),
+        }
+    }
+
+    /// Registers a validator for `format`, replacing any previous one.
+    pub fn register<V>(&mut self, format: impl Into<String>, validator: V) -> &mut Self
+    where
+        V: FormatValidator + 'static,
+    {
+        self.validators.insert(format.into(), Arc::new(validator));
+        self
+    }
+
+    /// Sets what happens when a format has no registered validator.
+    pub fn unknown_formats(&mut self, policy: UnknownFormatPolicy) -> &mut Self {
+        self.unknown = policy;
+        self
+    }
+
+    pub fn unknown_format_policy(&self) -> UnknownFormatPolicy {
+        self.unknown
+    }
+
+    pub fn get(&self, format: &str) -> Option<&dyn FormatValidator> {
+        self.validators.get(format).map(|validator| &**validator)
+    }
+
+    pub fn contains(&self, format: &str) -> bool {
+        self.validators.contains_key(format)
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry
+            .register("date", string_format(is_date))
+            .register("date-time", string_format(is_date_time))
+            .register("email", string_format(is_email))
+            .register("uuid", string_format(is_uuid))
+            .register("uri", string_format(|s| Url::parse(s).is_ok()))
+            .register("ipv4", string_format(|s| s.parse::<Ipv4Addr>().is_ok()))
+            .register("ipv6", string_format(|s| s.parse::<Ipv6Addr>().is_ok()))
+            .register("byte", string_format(is_base64))
+            .register("binary", any)
+            .register("password", any)
+            .register("int32", integer_format(i32::MIN.into(), i32::MAX.into()))
+            .register("int64", integer_format(i64::MIN, i64::MAX))
+            .register("float", any)
+            .register("double", any);
+
+        registry
+    }
+}
+
+impl fmt::Debug for FormatRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut formats: Vec<_> = self.validators.keys().collect();
+        formats.sort();
+
+        f.debug_struct("FormatRegistry")
+            .field("formats", &formats)
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+fn any(_: &Value) -> bool {
+    true
+}
+
+fn string_format(check: fn(&str) -> bool) -> impl Fn(&Value) -> bool + Send + Sync {
+    move |value| value.as_str().is_none_or(check)
+}
+
+fn integer_format(min: i64, max: i64) -> impl Fn(&Value) -> bool + Send + Sync {
+    move |value| match value {
+        Value::Number(number) => {
+            if let Some(n) = number.as_i64() {
+                n >= min && n <= max
+            } else if number.is_u64() {
+                // Anything that doesn't fit an i64 is out of range for both formats
+                false
+            } else {
+                let n = number.as_f64().unwrap_or(f64::NAN);
+                n.fract() == 0.0 && n >= min as f64 && n <= max as f64
+            }
+        }
+        _ => true,
+    }
+}
+
+fn digits(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+/// `full-date` as defined by [RFC3339](https://tools.ietf.org/html/rfc3339#section-5.6).
+pub(crate) fn is_date(s: &str) -> bool {
+    if !s.is_ascii() || s.len() != 10 || &s[4..5] != "-" || &s[7..8] != "-" {
+        return false;
+    }
+
+    let (year, month, day) = match (digits(&s[0..4]), digits(&s[5..7]), digits(&s[8..10])) {
+        (Some(year), Some(month), Some(day)) => (year, month, day),
+        _ => return false,
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    day >= 1 && day <= days
+}
+
+/// `date-time` as defined by [RFC3339](https://tools.ietf.org/html/rfc3339#section-5.6).
+pub(crate) fn is_date_time(s: &str) -> bool {
+    if !s.is_ascii() || s.len() < 20 || !matches!(&s[10..11], "T" | "t") {
+        return false;
+    }
+
+    if !is_date(&s[..10]) {
+        return false;
+    }
+
+    let time = &s[11..];
+    let (time, offset) = match time.strip_suffix(|c| c == 'Z' || c == 'z') {
+        Some(time) => (time, None),
+        None if time.len() > 6 => {
+            let (time, offset) = time.split_at(time.len() - 6);
+            (time, Some(offset))
+        }
+        None => return false,
+    };
+
+    if let Some(offset) = offset {
+        if !matches!(&offset[..1], "+" | "-") || &offset[3..4] != ":" {
+            return false;
+        }
+        match (digits(&offset[1..3]), digits(&offset[4..6])) {
+            (Some(hours), Some(minutes)) if hours < 24 && minutes < 60 => {}
+            _ => return false,
+        }
+    }
+
+    let time = match time.split_once('.') {
+        Some((time, fraction)) => {
+            if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return false;
+            }
+            time
+        }
+        None => time,
+    };
+
+    if time.len() != 8 || &time[2..3] != ":" || &time[5..6] != ":" {
+        return false;
+    }
+
+    // Seconds can be 60 during a leap second
+    matches!(
+        (digits(&time[0..2]), digits(&time[3..5]), digits(&time[6..8])),
+        (Some(hours), Some(minutes), Some(seconds)) if hours < 24 && minutes < 60 && seconds <= 60
+    )
+}
+
+/// A pragmatic subset of the `addr-spec` defined by [RFC5322](https://tools.ietf.org/html/rfc5322#section-3.4.1).
+pub(crate) fn is_email(s: &str) -> bool {
+    let (local, domain) = match s.rsplit_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    !local.is_empty()
+        && local.len() <= 64
+        && !local.contains(char::is_whitespace)
+        && !local.contains('@')
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
+
+pub(crate) fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.bytes().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// Base64 encoded characters, as defined by [RFC4648](https://tools.ietf.org/html/rfc4648#section-4).
+pub(crate) fn is_base64(s: &str) -> bool {
+    if !s.len().is_multiple_of(4) {
+        return false;
+    }
+
+    let data = s.trim_end_matches('=');
+
+    s.len() - data.len() <= 2
+        && data
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::v3::{validation::Validator, SchemaObject};
+
+    #[test]
+    fn dates() {
+        assert!(is_date("2021-02-28"));
+        assert!(is_date("2020-02-29"));
+        assert!(is_date("2000-02-29"));
+        assert!(!is_date("1900-02-29"));
+        assert!(!is_date("2021-04-31"));
+        assert!(!is_date("2021-13-01"));
+        assert!(!is_date("2021-00-10"));
+        assert!(!is_date("2021-1-01"));
+        assert!(!is_date("2021/01/01"));
+        assert!(!is_date("２０２１-01-01"));
+    }
+
+    #[test]
+    fn date_times() {
+        assert!(is_date_time("2021-01-01T00:00:00Z"));
+        assert!(is_date_time("2021-01-01t23:59:60z"));
+        assert!(is_date_time("2021-01-01T12:30:00.123+02:00"));
+        assert!(is_date_time("2021-01-01T12:30:00-23:59"));
+        assert!(!is_date_time("2021-01-01"));
+        assert!(!is_date_time("2021-01-01 12:30:00Z"));
+        assert!(!is_date_time("2021-01-01T24:00:00Z"));
+        assert!(!is_date_time("2021-01-01T12:30:00.Z"));
+        assert!(!is_date_time("2021-01-01T12:30:00+0200"));
+        assert!(!is_date_time("2021-01-01T12:30:00+24:00"));
+        assert!(!is_date_time("2021-02-30T12:30:00Z"));
+    }
+
+    #[test]
+    fn emails() {
+        assert!(is_email("user@example.com"));
+        assert!(is_email("first.last+tag@mail.example-domain.org"));
+        assert!(!is_email("user"));
+        assert!(!is_email("@example.com"));
+        assert!(!is_email("user@localhost"));
+        assert!(!is_email("us er@example.com"));
+        assert!(!is_email("user@-example.com"));
+        assert!(!is_email("user@example..com"));
+        assert!(!is_email(&format!("{}@example.com", "a".repeat(65))));
+    }
+
+    #[test]
+    fn base64() {
+        assert!(is_base64(""));
+        assert!(is_base64("c3RyaW5n"));
+        assert!(is_base64("YQ=="));
+        assert!(is_base64("YWI="));
+        assert!(is_base64("+/9a"));
+        assert!(!is_base64("YQ"));
+        assert!(!is_base64("Y==="));
+        assert!(!is_base64("YQ=a"));
+        assert!(!is_base64("c3Ry aW5n"));
+    }
+
+    #[test]
+    fn unknown_formats() {
+        let schema: SchemaObject =
+            serde_json::from_value(json!({"type": "string", "format": "zip-code"})).unwrap();
+        let value = json!("12345");
+
+        let mut formats = FormatRegistry::default();
+        let report = Validator::new()
+            .with_formats(formats.clone())
+            .validate_schema(&schema, &value);
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+
+        formats.unknown_formats(UnknownFormatPolicy::Warn);
+        let report = Validator::new()
+            .with_formats(formats.clone())
+            .validate_schema(&schema, &value);
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 1);
+
+        formats.unknown_formats(UnknownFormatPolicy::Error);
+        let report = Validator::new()
+            .with_formats(formats.clone())
+            .validate_schema(&schema, &value);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors[0].kind.to_string(),
+            "unknown format `zip-code`"
+        );
+
+        formats.register("zip-code", string_format(|s| s.len() == 5));
+        let report = Validator::new()
+            .with_formats(formats)
+            .validate_schema(&schema, &json!("1234"));
+        assert_eq!(report.errors.len(), 1);
+    }
+}
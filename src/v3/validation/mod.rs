@@ -0,0 +1,13 @@
+mod error;
+mod examples;
+mod format;
+mod request;
+mod response;
+mod validator;
+
+pub use error::*;
+pub use examples::*;
+pub use format::*;
+pub use request::*;
+pub use response::*;
+pub use validator::*;
@@ -0,0 +1,489 @@
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, HeaderMap, Request};
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+use url::form_urlencoded;
+
+use super::{Direction, Scope, ValidationErrorKind, ValidationReport, Validator};
+use crate::utils::push_pointer;
+use crate::v3::{
+    negotiate_content_type,
+    router::{RouteMatch, Router},
+    ContentMap, MediaType, MediaTypeObject, OpenAPIObject, ParameterLocation, ParameterObject,
+    ReferenceObjectOr, RequestBodyObject, SchemaObject, SchemaOrContentObject, SchemaType,
+};
+
+/// Validates `request` against the operation of `document` it's routed to.
+///
+/// This is a shorthand for [`Validator::validate_request`] with the default formats, when validating many requests prefer building the [`Router`] once.
+pub fn validate_request(document: &OpenAPIObject, request: &Request<Bytes>) -> ValidationReport {
+    Validator::new()
+        .with_document(document)
+        .validate_request(&Router::new(document), request)
+}
+
+impl<'a> Validator<'a> {
+    /// Routes `request` and validates its parameters and body against the matched operation.
+    pub fn validate_request(
+        &self,
+        router: &Router<'_>,
+        request: &Request<Bytes>,
+    ) -> ValidationReport {
+        match router.find(request.method(), request.uri().path()) {
+            Ok(route) => self.validate_routed_request(&route, request),
+            Err(error) => {
+                let mut report = ValidationReport::default();
+                report.error("", ValidationErrorKind::Route(error));
+                report
+            }
+        }
+    }
+
+    /// Validates a request that was already routed to an operation.
+    ///
+    /// Errors are located by pointers such as `/query/limit` or `/body/items/0`.
+    pub fn validate_routed_request(
+        &self,
+        route: &RouteMatch<'_>,
+        request: &Request<Bytes>,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let query: Vec<(String, String)> = request
+            .uri()
+            .query()
+            .map(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let cookies = parse_cookies(request.headers());
+
+        for parameter in self.parameters(route, &mut report) {
+            let fixed_fields = parameter.fixed_fields();
+            let location = parameter.location();
+            let pointer = push_pointer(&format!("/{}", location.as_str()), &fixed_fields.name);
+
+            let raw: Vec<String> = match location {
+                ParameterLocation::Path => route
+                    .path_parameters
+                    .get(&fixed_fields.name)
+                    .cloned()
+                    .into_iter()
+                    .collect(),
+                ParameterLocation::Query => query
+                    .iter()
+                    .filter(|(name, _)| *name == fixed_fields.name)
+                    .map(|(_, value)| value.clone())
+                    .collect(),
+                ParameterLocation::Header => {
+                    // These are described by other fields of the operation and SHALL be ignored
+                    if ["accept", "content-type", "authorization"]
+                        .contains(&fixed_fields.name.to_ascii_lowercase().as_str())
+                    {
+                        continue;
+                    }
+
+                    request
+                        .headers()
+                        .get_all(fixed_fields.name.as_str())
+                        .iter()
+                        .filter_map(|value| value.to_str().ok())
+                        .map(str::to_string)
+                        .collect()
+                }
+                ParameterLocation::Cookie => cookies
+                    .iter()
+                    .filter(|(name, _)| *name == fixed_fields.name)
+                    .map(|(_, value)| value.clone())
+                    .collect(),
+            };
+
+            match &fixed_fields.schema {
+                SchemaOrContentObject::Schema(schema) => {
+                    let resolved = self.resolve(schema, &pointer, &mut report);
+                    let value = match resolved {
+                        Some(resolved) => parse_parameter(
+                            location,
+                            fixed_fields.explode,
+                            &raw,
+                            resolved,
+                            &query,
+                            |schema| self.schema_type(schema),
+                        ),
+                        None => continue,
+                    };
+
+                    match value {
+                        Some(value) => self.validate_at(
+                            schema,
+                            &value,
+                            &pointer,
+                            &mut Scope::new(Direction::Request),
+                            &mut report,
+                        ),
+                        None if parameter.is_required() => {
+                            report.error(&pointer, ValidationErrorKind::MissingParameter)
+                        }
+                        None => {}
+                    }
+                }
+                SchemaOrContentObject::Content(content) => match raw.first() {
+                    Some(raw) => {
+                        // The map MUST only contain one entry
+                        if let Some((media_type, media)) = content.iter().next() {
+                            self.validate_content(
+                                media_type,
+                                media,
+                                raw.as_bytes(),
+                                &pointer,
+                                Direction::Request,
+                                &mut report,
+                            );
+                        }
+                    }
+                    None if parameter.is_required() => {
+                        report.error(&pointer, ValidationErrorKind::MissingParameter)
+                    }
+                    None => {}
+                },
+            }
+        }
+
+        if let Some(request_body) = &route.operation.request_body {
+            if let Some(request_body) = self.resolve(request_body, "/body", &mut report) {
+                self.validate_request_body(request_body, request, &mut report);
+            }
+        }
+
+        report
+    }
+
+    /// Collects the parameters of the path item and operation, the latter overriding the former.
+    fn parameters<'r>(
+        &self,
+        route: &'r RouteMatch<'_>,
+        report: &mut ValidationReport,
+    ) -> Vec<&'r ParameterObject>
+    where
+        'a: 'r,
+    {
+        let mut parameters: IndexMap<(ParameterLocation, &str), &ParameterObject> = IndexMap::new();
+
+        let declared = route
+            .path_item
+            .parameters
+            .iter()
+            .flatten()
+            .chain(route.operation.parameters.iter().flatten());
+
+        for parameter in declared {
+            if let Some(parameter) = self.resolve(parameter, "", report) {
+                parameters.insert((parameter.location(), parameter.name()), parameter);
+            }
+        }
+
+        parameters
+            .into_iter()
+            .map(|(_, parameter)| parameter)
+            .collect()
+    }
+
+    fn validate_request_body(
+        &self,
+        request_body: &RequestBodyObject,
+        request: &Request<Bytes>,
+        report: &mut ValidationReport,
+    ) {
+        if request.body().is_empty() {
+            if request_body.required {
+                report.error("/body", ValidationErrorKind::MissingBody);
+            }
+            return;
+        }
+
+        self.validate_body(
+            &request_body.content,
+            request.headers(),
+            request.body(),
+            Direction::Request,
+            report,
+        );
+    }
+
+    /// Validates a body against the entry of `content` matching its `Content-Type`.
+    pub(crate) fn validate_body(
+        &self,
+        content: &ContentMap,
+        headers: &HeaderMap,
+        body: &[u8],
+        direction: Direction,
+        report: &mut ValidationReport,
+    ) {
+        let content_type = match headers.get(CONTENT_TYPE) {
+            Some(content_type) => content_type,
+            None => {
+                report.error("/body", ValidationErrorKind::MissingContentType);
+                return;
+            }
+        };
+
+        let content_type: MediaType = match content_type
+            .to_str()
+            .ok()
+            .and_then(|content_type| content_type.parse().ok())
+        {
+            Some(content_type) => content_type,
+            None => {
+                report.error(
+                    "/body",
+                    ValidationErrorKind::UnsupportedMediaType(
+                        String::from_utf8_lossy(content_type.as_bytes()).into_owned(),
+                    ),
+                );
+                return;
+            }
+        };
+
+        match negotiate_content_type(content, &content_type) {
+            Some((_, media)) => {
+                self.validate_content(&content_type, media, body, "/body", direction, report)
+            }
+            None => report.error(
+                "/body",
+                ValidationErrorKind::UnsupportedMediaType(content_type.to_string()),
+            ),
+        }
+    }
+
+    /// Decodes `body` according to `media_type` and validates it against the schema of `media`.
+    ///
+    /// Only JSON, `application/x-www-form-urlencoded` and textual bodies can be decoded, anything else is accepted as is.
+    pub(crate) fn validate_content(
+        &self,
+        media_type: &MediaType,
+        media: &MediaTypeObject,
+        body: &[u8],
+        pointer: &str,
+        direction: Direction,
+        report: &mut ValidationReport,
+    ) {
+        let schema = match &media.schema {
+            Some(schema) => schema,
+            None => return,
+        };
+
+        let value = if media_type.is_json() {
+            match serde_json::from_slice(body) {
+                Ok(value) => value,
+                Err(error) => {
+                    report.error(pointer, ValidationErrorKind::InvalidBody(error.to_string()));
+                    return;
+                }
+            }
+        } else if media_type.type_name == "application"
+            && media_type.subtype == "x-www-form-urlencoded"
+        {
+            let resolved = match self.resolve(schema, pointer, report) {
+                Some(resolved) => resolved,
+                None => return,
+            };
+
+            let mut object = Map::new();
+            for (name, value) in form_urlencoded::parse(body) {
+                let property = resolved
+                    .properties
+                    .as_ref()
+                    .and_then(|properties| properties.get(name.as_ref()));
+                let schema_type = property.and_then(|property| self.schema_type(property));
+                object.insert(name.into_owned(), coerce(&value, schema_type));
+            }
+            Value::Object(object)
+        } else if media_type.type_name == "text" {
+            match std::str::from_utf8(body) {
+                Ok(text) => Value::String(text.to_string()),
+                Err(error) => {
+                    report.error(pointer, ValidationErrorKind::InvalidBody(error.to_string()));
+                    return;
+                }
+            }
+        } else {
+            return;
+        };
+
+        self.validate_at(schema, &value, pointer, &mut Scope::new(direction), report);
+    }
+
+    pub(crate) fn resolve<'r, T: crate::v3::Component>(
+        &self,
+        object: &'r ReferenceObjectOr<T>,
+        pointer: &str,
+        report: &mut ValidationReport,
+    ) -> Option<&'r T>
+    where
+        'a: 'r,
+    {
+        let resolved = match (object, self.document()) {
+            (ReferenceObjectOr::Object(object), _) => Ok(object),
+            (object, Some(document)) => object.resolve(document),
+            (ReferenceObjectOr::ReferenceObject { reference }, None) => {
+                Err(crate::v3::ResolveError::Unsupported(reference.clone()))
+            }
+        };
+
+        match resolved {
+            Ok(object) => Some(object),
+            Err(error) => {
+                report.error(pointer, ValidationErrorKind::Reference(error));
+                None
+            }
+        }
+    }
+
+    /// The type of a schema, looking into composed schemas when it isn't declared.
+    pub(crate) fn schema_type(
+        &self,
+        schema: &ReferenceObjectOr<SchemaObject>,
+    ) -> Option<SchemaType> {
+        self.schema_type_visiting(schema, &mut Vec::new())
+    }
+
+    /// The type of a schema, `visiting` holding the references followed to reach it so that cycles end.
+    fn schema_type_visiting<'r>(
+        &self,
+        schema: &'r ReferenceObjectOr<SchemaObject>,
+        visiting: &mut Vec<&'r str>,
+    ) -> Option<SchemaType>
+    where
+        'a: 'r,
+    {
+        let schema = match (schema, self.document()) {
+            (ReferenceObjectOr::Object(schema), _) => schema,
+            (ReferenceObjectOr::ReferenceObject { reference }, Some(document)) => {
+                if visiting.contains(&reference.as_str()) {
+                    return None;
+                }
+                visiting.push(reference);
+                schema.resolve(document).ok()?
+            }
+            (_, None) => return None,
+        };
+
+        schema.schema_type.or_else(|| {
+            schema
+                .all_of
+                .iter()
+                .chain(&schema.one_of)
+                .chain(&schema.any_of)
+                .flatten()
+                .find_map(|member| self.schema_type_visiting(member, visiting))
+        })
+    }
+}
+
+/// Turns the raw values of a parameter into a JSON value, following the default serialization `style` of its location.
+pub(crate) fn parse_parameter(
+    location: ParameterLocation,
+    explode: Option<bool>,
+    raw: &[String],
+    schema: &SchemaObject,
+    query: &[(String, String)],
+    schema_type: impl Fn(&ReferenceObjectOr<SchemaObject>) -> Option<SchemaType>,
+) -> Option<Value> {
+    // Only the `form` style, used by queries and cookies, explodes by default
+    let explode = explode.unwrap_or(matches!(
+        location,
+        ParameterLocation::Query | ParameterLocation::Cookie
+    ));
+
+    let item_type = schema.items.as_deref().and_then(&schema_type);
+
+    match schema.schema_type {
+        Some(SchemaType::Array) => {
+            if explode && location == ParameterLocation::Query {
+                if raw.is_empty() {
+                    return None;
+                }
+                Some(Value::Array(
+                    raw.iter().map(|item| coerce(item, item_type)).collect(),
+                ))
+            } else {
+                let raw = raw.first()?;
+                Some(Value::Array(
+                    raw.split(',').map(|item| coerce(item, item_type)).collect(),
+                ))
+            }
+        }
+        Some(SchemaType::Object) => {
+            let property_type = |name: &str| {
+                schema
+                    .properties
+                    .as_ref()
+                    .and_then(|properties| properties.get(name))
+                    .and_then(&schema_type)
+            };
+            let mut object = Map::new();
+
+            if explode && location == ParameterLocation::Query {
+                // Every property is a separate query parameter
+                for (name, value) in query {
+                    if schema
+                        .properties
+                        .as_ref()
+                        .is_some_and(|properties| properties.contains_key(name))
+                    {
+                        object.insert(name.clone(), coerce(value, property_type(name)));
+                    }
+                }
+
+                if object.is_empty() {
+                    return None;
+                }
+            } else if explode {
+                for pair in raw.first()?.split(',') {
+                    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    object.insert(name.to_string(), coerce(value, property_type(name)));
+                }
+            } else {
+                let mut items = raw.first()?.split(',');
+                while let Some(name) = items.next() {
+                    let value = items.next().unwrap_or_default();
+                    object.insert(name.to_string(), coerce(value, property_type(name)));
+                }
+            }
+
+            Some(Value::Object(object))
+        }
+        schema_type => raw.first().map(|raw| coerce(raw, schema_type)),
+    }
+}
+
+/// Converts a raw string to the JSON type described by the schema, leaving it as a string when that's not possible so that validation reports the mismatch.
+fn coerce(raw: &str, schema_type: Option<SchemaType>) -> Value {
+    let coerced = match schema_type {
+        Some(SchemaType::Integer) => raw.parse::<i64>().ok().map(Value::from),
+        Some(SchemaType::Number) => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        Some(SchemaType::Boolean) => raw.parse::<bool>().ok().map(Value::Bool),
+        _ => None,
+    };
+
+    coerced.unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn parse_cookies(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|cookie| cookie.to_str().ok())
+        .flat_map(|cookie| cookie.split(';'))
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+use bytes::Bytes;
+use http::Response;
+
+use super::{parse_parameter, Direction, Scope, ValidationErrorKind, ValidationReport, Validator};
+use crate::utils::push_pointer;
+use crate::v3::{
+    OpenAPIObject, OperationObject, ParameterLocation, ResponseObject, SchemaOrContentObject,
+};
+
+/// Validates `response` against the responses declared by `operation`, resolving references against `document`.
+///
+/// This is a shorthand for [`Validator::validate_response`] with the default formats.
+pub fn validate_response(
+    document: &OpenAPIObject,
+    operation: &OperationObject,
+    response: &Response<Bytes>,
+) -> ValidationReport {
+    Validator::new()
+        .with_document(document)
+        .validate_response(operation, response)
+}
+
+impl<'a> Validator<'a> {
+    /// Validates the headers and body of `response` against the response `operation` declares for its status code.
+    ///
+    /// The response is looked up by exact status code first, then by range (e.g. `2XX`) and finally the `default` response is used.
+    /// Errors are located by pointers such as `/status`, `/header/X-Rate-Limit` or `/body/items/0`.
+    pub fn validate_response(
+        &self,
+        operation: &OperationObject,
+        response: &Response<Bytes>,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let declared = match operation.responses.for_status(response.status()) {
+            Some(declared) => declared,
+            None => {
+                report.error(
+                    "/status",
+                    ValidationErrorKind::UndocumentedStatus(response.status()),
+                );
+                return report;
+            }
+        };
+
+        let declared = match self.resolve(declared, "/status", &mut report) {
+            Some(declared) => declared,
+            None => return report,
+        };
+
+        self.validate_response_headers(declared, response, &mut report);
+        self.validate_response_body(declared, response, &mut report);
+
+        report
+    }
+
+    fn validate_response_headers(
+        &self,
+        declared: &ResponseObject,
+        response: &Response<Bytes>,
+        report: &mut ValidationReport,
+    ) {
+        for (name, header) in declared.headers.iter().flatten() {
+            if name.eq_ignore_ascii_case("content-type") {
+                continue;
+            }
+
+            let pointer = push_pointer("/header", name);
+            let header = match self.resolve(header, &pointer, report) {
+                Some(header) => header,
+                None => continue,
+            };
+
+            let raw: Vec<String> = response
+                .headers()
+                .get_all(name.as_str())
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(str::to_string)
+                .collect();
+
+            if raw.is_empty() {
+                if header.required {
+                    report.error(&pointer, ValidationErrorKind::MissingHeader);
+                }
+                continue;
+            }
+
+            match &header.schema {
+                SchemaOrContentObject::Schema(schema) => {
+                    let resolved = match self.resolve(schema, &pointer, report) {
+                        Some(resolved) => resolved,
+                        None => continue,
+                    };
+
+                    if let Some(value) = parse_parameter(
+                        ParameterLocation::Header,
+                        header.explode,
+                        &raw,
+                        resolved,
+                        &[],
+                        |schema| self.schema_type(schema),
+                    ) {
+                        self.validate_at(
+                            schema,
+                            &value,
+                            &pointer,
+                            &mut Scope::new(Direction::Response),
+                            report,
+                        );
+                    }
+                }
+                SchemaOrContentObject::Content(content) => {
+                    if let Some((media_type, media)) = content.iter().next() {
+                        self.validate_content(
+                            media_type,
+                            media,
+                            raw[0].as_bytes(),
+                            &pointer,
+                            Direction::Response,
+                            report,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_response_body(
+        &self,
+        declared: &ResponseObject,
+        response: &Response<Bytes>,
+        report: &mut ValidationReport,
+    ) {
+        let content = match &declared.content {
+            Some(content) if !content.is_empty() => content,
+            // Without content the response is not expected to have a body
+            _ => {
+                if !response.body().is_empty() {
+                    report.error("/body", ValidationErrorKind::UnexpectedBody);
+                }
+                return;
+            }
+        };
+
+        if response.body().is_empty() {
+            report.error("/body", ValidationErrorKind::MissingBody);
+            return;
+        }
+
+        self.validate_body(
+            content,
+            response.headers(),
+            response.body(),
+            Direction::Response,
+            report,
+        );
+    }
+}
@@ -0,0 +1,513 @@
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use super::{FormatRegistry, UnknownFormatPolicy, ValidationErrorKind, ValidationReport};
+use crate::utils::push_pointer;
+use crate::v3::{AdditionalProperties, OpenAPIObject, ReferenceObjectOr, SchemaObject, SchemaType};
+
+/// Validates values against [Schema Objects](SchemaObject).
+///
+/// References are resolved against the document set with [`Validator::with_document`], without a document every `$ref` is reported as an error.
+/// Formats are checked through a [`FormatRegistry`], which by default knows about every format defined by the OpenAPI Specification.
+#[derive(Debug, Clone, Default)]
+pub struct Validator<'a> {
+    document: Option<&'a OpenAPIObject>,
+    formats: FormatRegistry,
+    patterns: PatternCache,
+}
+
+/// The compiled `pattern`s, shared by the clones of a validator so each one is compiled once.
+#[derive(Debug, Clone, Default)]
+struct PatternCache(Arc<Mutex<HashMap<String, Option<Regex>>>>);
+
+impl PatternCache {
+    /// The compiled `pattern`, or `None` if it isn't a valid regular expression.
+    fn get(&self, pattern: &str) -> Option<Regex> {
+        let mut patterns = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(regex) = patterns.get(pattern) {
+            return regex.clone();
+        }
+
+        let regex = Regex::new(pattern).ok();
+        patterns.insert(pattern.to_string(), regex.clone());
+        regex
+    }
+}
+
+impl<'a> Validator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `document` to resolve references.
+    pub fn with_document(mut self, document: &'a OpenAPIObject) -> Self {
+        self.document = Some(document);
+        self
+    }
+
+    /// Replaces the default format registry, e.g. to add custom formats.
+    pub fn with_formats(mut self, formats: FormatRegistry) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    pub fn document(&self) -> Option<&'a OpenAPIObject> {
+        self.document
+    }
+
+    pub fn formats(&self) -> &FormatRegistry {
+        &self.formats
+    }
+
+    pub fn formats_mut(&mut self) -> &mut FormatRegistry {
+        &mut self.formats
+    }
+
+    /// Validates `value` against a schema or a reference to one.
+    pub fn validate(
+        &self,
+        schema: &ReferenceObjectOr<SchemaObject>,
+        value: &Value,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        self.validate_at(schema, value, "", &mut Scope::default(), &mut report);
+        report
+    }
+
+    /// Validates `value` against an inline schema.
+    pub fn validate_schema(&self, schema: &SchemaObject, value: &Value) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        self.validate_object(schema, value, "", &mut Scope::default(), &mut report);
+        report
+    }
+
+    pub(crate) fn validate_at<'s>(
+        &'s self,
+        schema: &'s ReferenceObjectOr<SchemaObject>,
+        value: &Value,
+        pointer: &str,
+        scope: &mut Scope<'s>,
+        report: &mut ValidationReport,
+    ) {
+        let schema = match schema {
+            ReferenceObjectOr::Object(schema) => schema,
+            ReferenceObjectOr::ReferenceObject { reference } => {
+                if scope.visited.contains(&reference.as_str()) {
+                    return;
+                }
+                scope.visited.push(reference);
+
+                let resolved = match self.document {
+                    Some(document) => schema.resolve(document),
+                    None => Err(crate::v3::ResolveError::Unsupported(reference.clone())),
+                };
+
+                match resolved {
+                    Ok(schema) => self.validate_object(schema, value, pointer, scope, report),
+                    Err(error) => report.error(pointer, ValidationErrorKind::Reference(error)),
+                }
+
+                scope.visited.pop();
+                return;
+            }
+        };
+
+        self.validate_object(schema, value, pointer, scope, report)
+    }
+
+    fn validate_object<'s>(
+        &'s self,
+        schema: &'s SchemaObject,
+        value: &Value,
+        pointer: &str,
+        scope: &mut Scope<'s>,
+        report: &mut ValidationReport,
+    ) {
+        if value.is_null() {
+            if schema.nullable {
+                return;
+            }
+
+            if let Some(expected) = schema.schema_type {
+                report.error(
+                    pointer,
+                    ValidationErrorKind::Type {
+                        expected,
+                        found: "null",
+                    },
+                );
+                return;
+            }
+        }
+
+        if let Some(expected) = schema.schema_type {
+            if !is_of_type(value, expected) {
+                report.error(
+                    pointer,
+                    ValidationErrorKind::Type {
+                        expected,
+                        found: type_name(value),
+                    },
+                );
+                // Every other keyword would just repeat the same mistake
+                return;
+            }
+        }
+
+        if let Some(enumeration) = &schema.enumeration {
+            if !enumeration.iter().any(|allowed| allowed == value) {
+                report.error(pointer, ValidationErrorKind::Enum);
+            }
+        }
+
+        if let Some(format) = &schema.format {
+            self.validate_format(format, value, pointer, report);
+        }
+
+        match value {
+            Value::Number(number) => {
+                validate_number(schema, number.as_f64().unwrap_or_default(), pointer, report)
+            }
+            Value::String(string) => self.validate_string(schema, string, pointer, report),
+            Value::Array(items) => self.validate_array(schema, items, pointer, scope, report),
+            Value::Object(properties) => {
+                self.validate_properties(schema, properties, pointer, scope, report)
+            }
+            _ => {}
+        }
+
+        if let Some(all_of) = &schema.all_of {
+            for member in all_of {
+                self.validate_at(member, value, pointer, scope, report);
+            }
+        }
+
+        if let Some(one_of) = &schema.one_of {
+            let matched = one_of
+                .iter()
+                .filter(|member| self.matches(member, value, pointer, scope))
+                .count();
+
+            if matched != 1 {
+                report.error(pointer, ValidationErrorKind::OneOf { matched });
+            }
+        }
+
+        if let Some(any_of) = &schema.any_of {
+            if !any_of
+                .iter()
+                .any(|member| self.matches(member, value, pointer, scope))
+            {
+                report.error(pointer, ValidationErrorKind::AnyOf);
+            }
+        }
+
+        if let Some(not) = &schema.not {
+            if self.matches(not, value, pointer, scope) {
+                report.error(pointer, ValidationErrorKind::Not);
+            }
+        }
+    }
+
+    fn matches<'s>(
+        &'s self,
+        schema: &'s ReferenceObjectOr<SchemaObject>,
+        value: &Value,
+        pointer: &str,
+        scope: &mut Scope<'s>,
+    ) -> bool {
+        let mut report = ValidationReport::default();
+        self.validate_at(schema, value, pointer, scope, &mut report);
+        report.is_valid()
+    }
+
+    fn validate_string(
+        &self,
+        schema: &SchemaObject,
+        string: &str,
+        pointer: &str,
+        report: &mut ValidationReport,
+    ) {
+        // JSON Schema counts characters, not bytes
+        let len = string.chars().count() as u64;
+
+        if let Some(max) = schema.max_length {
+            if len > max.into() {
+                report.error(pointer, ValidationErrorKind::MaxLength(max));
+            }
+        }
+
+        if let Some(min) = schema.min_length {
+            if len < min.into() {
+                report.error(pointer, ValidationErrorKind::MinLength(min));
+            }
+        }
+
+        if let Some(pattern) = &schema.pattern {
+            match self.patterns.get(pattern) {
+                Some(regex) => {
+                    if !regex.is_match(string) {
+                        report.error(pointer, ValidationErrorKind::Pattern(pattern.clone()));
+                    }
+                }
+                None => report.error(
+                    pointer,
+                    ValidationErrorKind::InvalidPattern(pattern.clone()),
+                ),
+            }
+        }
+    }
+
+    fn validate_format(
+        &self,
+        format: &str,
+        value: &Value,
+        pointer: &str,
+        report: &mut ValidationReport,
+    ) {
+        match self.formats.get(format) {
+            Some(validator) => {
+                if !validator.validate(value) {
+                    report.error(pointer, ValidationErrorKind::Format(format.to_string()));
+                }
+            }
+            None => match self.formats.unknown_format_policy() {
+                UnknownFormatPolicy::Ignore => {}
+                UnknownFormatPolicy::Warn => report.warning(
+                    pointer,
+                    ValidationErrorKind::UnknownFormat(format.to_string()),
+                ),
+                UnknownFormatPolicy::Error => report.error(
+                    pointer,
+                    ValidationErrorKind::UnknownFormat(format.to_string()),
+                ),
+            },
+        }
+    }
+
+    fn validate_array(
+        &self,
+        schema: &SchemaObject,
+        items: &[Value],
+        pointer: &str,
+        scope: &Scope<'_>,
+        report: &mut ValidationReport,
+    ) {
+        let len = items.len() as u64;
+
+        if let Some(max) = schema.max_items {
+            if len > max.into() {
+                report.error(pointer, ValidationErrorKind::MaxItems(max));
+            }
+        }
+
+        if let Some(min) = schema.min_items {
+            if len < min.into() {
+                report.error(pointer, ValidationErrorKind::MinItems(min));
+            }
+        }
+
+        if schema.unique_items
+            && items
+                .iter()
+                .enumerate()
+                .any(|(i, item)| items[..i].contains(item))
+        {
+            report.error(pointer, ValidationErrorKind::UniqueItems);
+        }
+
+        if let Some(item_schema) = &schema.items {
+            for (i, item) in items.iter().enumerate() {
+                let pointer = push_pointer(pointer, &i.to_string());
+                self.validate_at(item_schema, item, &pointer, &mut scope.child(), report);
+            }
+        }
+    }
+
+    fn validate_properties(
+        &self,
+        schema: &SchemaObject,
+        properties: &Map<String, Value>,
+        pointer: &str,
+        scope: &Scope<'_>,
+        report: &mut ValidationReport,
+    ) {
+        let len = properties.len() as u64;
+
+        if let Some(max) = schema.max_properties {
+            if len > max.into() {
+                report.error(pointer, ValidationErrorKind::MaxProperties(max));
+            }
+        }
+
+        if let Some(min) = schema.min_properties {
+            if len < min.into() {
+                report.error(pointer, ValidationErrorKind::MinProperties(min));
+            }
+        }
+
+        for required in schema.required.iter().flatten() {
+            if !properties.contains_key(required) && !self.is_hidden(schema, required, scope) {
+                report.error(pointer, ValidationErrorKind::Required(required.clone()));
+            }
+        }
+
+        for (name, value) in properties {
+            let property_pointer = push_pointer(pointer, name);
+
+            match schema
+                .properties
+                .as_ref()
+                .and_then(|properties| properties.get(name))
+            {
+                Some(property_schema) => self.validate_at(
+                    property_schema,
+                    value,
+                    &property_pointer,
+                    &mut scope.child(),
+                    report,
+                ),
+                None => match &schema.additional_properties {
+                    None | Some(AdditionalProperties::Allowed(true)) => {}
+                    Some(AdditionalProperties::Allowed(false)) => report.error(
+                        pointer,
+                        ValidationErrorKind::AdditionalProperty(name.clone()),
+                    ),
+                    Some(AdditionalProperties::Schema(additional)) => self.validate_at(
+                        additional,
+                        value,
+                        &property_pointer,
+                        &mut scope.child(),
+                        report,
+                    ),
+                },
+            }
+        }
+    }
+
+    /// Whether a property is `readOnly` in a request or `writeOnly` in a response, in which case it's allowed to be missing even if `required`.
+    fn is_hidden(&self, schema: &SchemaObject, property: &str, scope: &Scope<'_>) -> bool {
+        let property = match schema
+            .properties
+            .as_ref()
+            .and_then(|properties| properties.get(property))
+        {
+            Some(property) => property,
+            None => return false,
+        };
+
+        let property = match (property, self.document) {
+            (ReferenceObjectOr::Object(property), _) => property,
+            (property, Some(document)) => match property.resolve(document) {
+                Ok(property) => property,
+                Err(_) => return false,
+            },
+            (_, None) => return false,
+        };
+
+        match scope.direction {
+            Direction::Any => false,
+            Direction::Request => property.read_only,
+            Direction::Response => property.write_only,
+        }
+    }
+}
+
+/// Whether the value being validated is sent by a client or by a server, which affects `readOnly` and `writeOnly` properties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Any,
+    Request,
+    Response,
+}
+
+#[derive(Debug)]
+pub(crate) struct Scope<'s> {
+    pub(crate) direction: Direction,
+    // References already followed at this location, used to stop on recursive schemas such as `A: { allOf: [$ref: A] }`
+    visited: Vec<&'s str>,
+}
+
+impl<'s> Scope<'s> {
+    pub(crate) fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            visited: Vec::new(),
+        }
+    }
+
+    /// The scope of a nested value, where references can be followed again.
+    fn child(&self) -> Self {
+        Self::new(self.direction)
+    }
+}
+
+impl Default for Scope<'_> {
+    fn default() -> Self {
+        Self::new(Direction::Any)
+    }
+}
+
+fn validate_number(
+    schema: &SchemaObject,
+    number: f64,
+    pointer: &str,
+    report: &mut ValidationReport,
+) {
+    if let Some(multiple_of) = schema.multiple_of {
+        if (number / f64::from(multiple_of.get())).fract() != 0.0 {
+            report.error(pointer, ValidationErrorKind::MultipleOf(multiple_of.get()));
+        }
+    }
+
+    if let Some(maximum) = schema.maximum {
+        let exclusive = schema.exclusive_maximum.unwrap_or(false);
+        let maximum_f = f64::from(maximum);
+
+        if number > maximum_f || (exclusive && number == maximum_f) {
+            report.error(pointer, ValidationErrorKind::Maximum { maximum, exclusive });
+        }
+    }
+
+    if let Some(minimum) = schema.minimum {
+        let exclusive = schema.exclusive_minimum.unwrap_or(false);
+        let minimum_f = f64::from(minimum);
+
+        if number < minimum_f || (exclusive && number == minimum_f) {
+            report.error(pointer, ValidationErrorKind::Minimum { minimum, exclusive });
+        }
+    }
+}
+
+pub(crate) fn is_of_type(value: &Value, schema_type: SchemaType) -> bool {
+    match schema_type {
+        SchemaType::String => value.is_string(),
+        SchemaType::Number => value.is_number(),
+        SchemaType::Integer => match value {
+            Value::Number(number) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || number.as_f64().is_some_and(|n| n.fract() == 0.0)
+            }
+            _ => false,
+        },
+        SchemaType::Boolean => value.is_boolean(),
+        SchemaType::Array => value.is_array(),
+        SchemaType::Object => value.is_object(),
+    }
+}
+
+pub(crate) fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
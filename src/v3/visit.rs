@@ -0,0 +1,469 @@
+use indexmap::IndexMap;
+use std::fmt::Display;
+
+use super::{
+    AdditionalProperties, CallbackObject, ComponentKind, ComponentRef, ComponentsObject,
+    ContentMap, DiscriminatorObject, ExampleObject, HeaderObject, LinkObject, MediaTypeObject,
+    OpenAPIObject, OperationObject, ParameterObject, PathsItemObject, ReferenceObjectOr,
+    RequestBodyObject, ResponseObject, ResponsesObject, SchemaObject, SchemaOrContentObject,
+    SecuritySchemeObject,
+};
+use crate::utils::push_pointer;
+
+/// Objects that may hold references, directly or through nested objects.
+///
+/// Besides `$ref`s, the values of [discriminator mappings](DiscriminatorObject) are visited as references too.
+/// Mapping values that are bare schema names are visited as `#/components/schemas/{name}`, and written back as bare names if they still point to a schema.
+pub trait References {
+    /// Calls `visitor` with a [JSON Pointer](https://tools.ietf.org/html/rfc6901) to every reference, relative to `pointer`, and the reference itself.
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str));
+
+    /// Calls `visitor` with every reference, allowing to rewrite them.
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String));
+}
+
+impl<T: References> References for ReferenceObjectOr<T> {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        match self {
+            ReferenceObjectOr::ReferenceObject { reference } => {
+                visitor(&push_pointer(pointer, "$ref"), reference)
+            }
+            ReferenceObjectOr::Object(object) => object.visit_references(pointer, visitor),
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        match self {
+            ReferenceObjectOr::ReferenceObject { reference } => visitor(reference),
+            ReferenceObjectOr::Object(object) => object.visit_references_mut(visitor),
+        }
+    }
+}
+
+impl<T: References> References for Option<T> {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        if let Some(object) = self {
+            object.visit_references(pointer, visitor);
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        if let Some(object) = self {
+            object.visit_references_mut(visitor);
+        }
+    }
+}
+
+impl<T: References> References for Box<T> {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        (**self).visit_references(pointer, visitor);
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        (**self).visit_references_mut(visitor);
+    }
+}
+
+impl<T: References> References for Vec<T> {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        for (i, object) in self.iter().enumerate() {
+            object.visit_references(&push_pointer(pointer, &i.to_string()), visitor);
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        for object in self {
+            object.visit_references_mut(visitor);
+        }
+    }
+}
+
+impl<K: Display, T: References> References for IndexMap<K, T> {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        for (key, object) in self {
+            object.visit_references(&push_pointer(pointer, &key.to_string()), visitor);
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        for object in self.values_mut() {
+            object.visit_references_mut(visitor);
+        }
+    }
+}
+
+/// Implements [`References`] by visiting the listed fields, each under its name in the document.
+macro_rules! impl_references {
+    ($object:ty { $($field:ident: $name:literal),* $(,)? }) => {
+        impl References for $object {
+            fn visit_references(&self, _pointer: &str, _visitor: &mut dyn FnMut(&str, &str)) {
+                $(self.$field.visit_references(&push_pointer(_pointer, $name), _visitor);)*
+            }
+
+            fn visit_references_mut(&mut self, _visitor: &mut dyn FnMut(&mut String)) {
+                $(self.$field.visit_references_mut(_visitor);)*
+            }
+        }
+    };
+}
+
+impl_references!(OpenAPIObject {
+    paths: "paths",
+    components: "components",
+});
+impl_references!(ComponentsObject {
+    schemas: "schemas",
+    responses: "responses",
+    parameters: "parameters",
+    examples: "examples",
+    request_bodies: "requestBodies",
+    headers: "headers",
+    security_schemes: "securitySchemes",
+    links: "links",
+    callbacks: "callbacks",
+});
+impl_references!(PathsItemObject {
+    get: "get",
+    put: "put",
+    post: "post",
+    delete: "delete",
+    options: "options",
+    head: "head",
+    patch: "patch",
+    trace: "trace",
+    parameters: "parameters",
+});
+impl_references!(OperationObject {
+    parameters: "parameters",
+    request_body: "requestBody",
+    responses: "responses",
+    callbacks: "callbacks",
+});
+impl_references!(RequestBodyObject { content: "content" });
+impl_references!(ResponseObject {
+    headers: "headers",
+    content: "content",
+    links: "links",
+});
+impl_references!(MediaTypeObject { schema: "schema" });
+impl_references!(ExampleObject {});
+impl_references!(LinkObject {});
+impl_references!(SecuritySchemeObject {});
+
+impl References for ResponsesObject {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        self.default
+            .visit_references(&push_pointer(pointer, "default"), visitor);
+        self.responses.visit_references(pointer, visitor);
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        self.default.visit_references_mut(visitor);
+        self.responses.visit_references_mut(visitor);
+    }
+}
+
+impl References for ParameterObject {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        self.fixed_fields()
+            .schema
+            .visit_references(pointer, visitor);
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        self.fixed_fields_mut().schema.visit_references_mut(visitor);
+    }
+}
+
+impl References for HeaderObject {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        self.schema.visit_references(pointer, visitor);
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        self.schema.visit_references_mut(visitor);
+    }
+}
+
+impl References for SchemaOrContentObject {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        match self {
+            SchemaOrContentObject::Schema(schema) => {
+                schema.visit_references(&push_pointer(pointer, "schema"), visitor)
+            }
+            SchemaOrContentObject::Content(content) => {
+                content.visit_references(&push_pointer(pointer, "content"), visitor)
+            }
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        match self {
+            SchemaOrContentObject::Schema(schema) => schema.visit_references_mut(visitor),
+            SchemaOrContentObject::Content(content) => content.visit_references_mut(visitor),
+        }
+    }
+}
+
+impl_references!(SchemaObject {
+    discriminator: "discriminator",
+    all_of: "allOf",
+    one_of: "oneOf",
+    any_of: "anyOf",
+    not: "not",
+    items: "items",
+    properties: "properties",
+    additional_properties: "additionalProperties",
+});
+
+impl References for AdditionalProperties {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        if let AdditionalProperties::Schema(schema) = self {
+            schema.visit_references(pointer, visitor);
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        if let AdditionalProperties::Schema(schema) = self {
+            schema.visit_references_mut(visitor);
+        }
+    }
+}
+
+impl ComponentsObject {
+    /// Visits the references held by a single component, if it exists.
+    pub fn visit_component_references(
+        &self,
+        component: &ComponentRef,
+        visitor: &mut dyn FnMut(&str, &str),
+    ) {
+        fn visit<T: References>(
+            map: Option<&IndexMap<String, T>>,
+            component: &ComponentRef,
+            visitor: &mut dyn FnMut(&str, &str),
+        ) {
+            if let Some(object) = map.and_then(|map| map.get(&component.name)) {
+                object.visit_references(&component.to_pointer(), visitor);
+            }
+        }
+
+        match component.kind {
+            ComponentKind::Schemas => visit(self.schemas.as_ref(), component, visitor),
+            ComponentKind::Responses => visit(self.responses.as_ref(), component, visitor),
+            ComponentKind::Parameters => visit(self.parameters.as_ref(), component, visitor),
+            ComponentKind::Examples => visit(self.examples.as_ref(), component, visitor),
+            ComponentKind::RequestBodies => visit(self.request_bodies.as_ref(), component, visitor),
+            ComponentKind::Headers => visit(self.headers.as_ref(), component, visitor),
+            ComponentKind::SecuritySchemes => {
+                visit(Some(&self.security_schemes), component, visitor)
+            }
+            ComponentKind::Links => visit(self.links.as_ref(), component, visitor),
+            ComponentKind::Callbacks => visit(self.callbacks.as_ref(), component, visitor),
+        }
+    }
+}
+
+const SCHEMAS: &str = "#/components/schemas/";
+
+impl References for DiscriminatorObject {
+    fn visit_references(&self, pointer: &str, visitor: &mut dyn FnMut(&str, &str)) {
+        let pointer = push_pointer(pointer, "mapping");
+
+        for (value, reference) in &self.mapping {
+            let pointer = push_pointer(&pointer, value);
+
+            if is_schema_name(reference) {
+                visitor(&pointer, &format!("{}{}", SCHEMAS, escape(reference)));
+            } else {
+                visitor(&pointer, reference);
+            }
+        }
+    }
+
+    fn visit_references_mut(&mut self, visitor: &mut dyn FnMut(&mut String)) {
+        for reference in self.mapping.values_mut() {
+            if !is_schema_name(reference) {
+                visitor(reference);
+                continue;
+            }
+
+            let mut full = format!("{}{}", SCHEMAS, escape(reference));
+            visitor(&mut full);
+
+            match full.strip_prefix(SCHEMAS) {
+                Some(name) if !name.contains('/') => {
+                    *reference = name.replace("~1", "/").replace("~0", "~")
+                }
+                _ => *reference = full,
+            }
+        }
+    }
+}
+
+/// Mapping values that are neither JSON pointers nor URLs are names of schemas.
+fn is_schema_name(reference: &str) -> bool {
+    !reference.contains('/') && !reference.contains('#')
+}
+
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+type SchemaVisitor<'v> = dyn FnMut(&str, &mut ReferenceObjectOr<SchemaObject>) + 'v;
+
+/// Calls `visitor` with a [JSON Pointer](https://tools.ietf.org/html/rfc6901) to every schema of the document that is neither a component schema nor held by another schema, such as the schemas of parameters and media types, and the schema itself.
+pub(crate) fn visit_schemas_mut(document: &mut OpenAPIObject, visitor: &mut SchemaVisitor<'_>) {
+    for (template, path_item) in document.paths.iter_mut() {
+        if let ReferenceObjectOr::Object(path_item) = path_item {
+            path_item_schemas(path_item, &push_pointer("/paths", template), visitor);
+        }
+    }
+
+    let components = match &mut document.components {
+        Some(components) => components,
+        None => return,
+    };
+    for (name, response) in components.responses.iter_mut().flatten() {
+        if let ReferenceObjectOr::Object(response) = response {
+            response_schemas(
+                response,
+                &push_pointer("/components/responses", name),
+                visitor,
+            );
+        }
+    }
+    for (name, parameter) in components.parameters.iter_mut().flatten() {
+        if let ReferenceObjectOr::Object(parameter) = parameter {
+            let pointer = push_pointer("/components/parameters", name);
+            schema_or_content(&mut parameter.fixed_fields_mut().schema, &pointer, visitor);
+        }
+    }
+    for (name, request_body) in components.request_bodies.iter_mut().flatten() {
+        if let ReferenceObjectOr::Object(request_body) = request_body {
+            let pointer = push_pointer(&push_pointer("/components/requestBodies", name), "content");
+            content_schemas(&mut request_body.content, &pointer, visitor);
+        }
+    }
+    for (name, header) in components.headers.iter_mut().flatten() {
+        if let ReferenceObjectOr::Object(header) = header {
+            let pointer = push_pointer("/components/headers", name);
+            schema_or_content(&mut header.schema, &pointer, visitor);
+        }
+    }
+    for (name, callback) in components.callbacks.iter_mut().flatten() {
+        if let ReferenceObjectOr::Object(callback) = callback {
+            callback_schemas(
+                callback,
+                &push_pointer("/components/callbacks", name),
+                visitor,
+            );
+        }
+    }
+}
+
+fn path_item_schemas(
+    path_item: &mut PathsItemObject,
+    pointer: &str,
+    visitor: &mut SchemaVisitor<'_>,
+) {
+    parameter_schemas(
+        path_item.parameters.as_mut(),
+        &push_pointer(pointer, "parameters"),
+        visitor,
+    );
+
+    for (method, operation) in path_item.operations_mut() {
+        let pointer = push_pointer(pointer, &method.as_str().to_lowercase());
+
+        parameter_schemas(
+            operation.parameters.as_mut(),
+            &push_pointer(&pointer, "parameters"),
+            visitor,
+        );
+        if let Some(ReferenceObjectOr::Object(request_body)) = &mut operation.request_body {
+            let pointer = push_pointer(&push_pointer(&pointer, "requestBody"), "content");
+            content_schemas(&mut request_body.content, &pointer, visitor);
+        }
+
+        let responses_pointer = push_pointer(&pointer, "responses");
+        if let Some(ReferenceObjectOr::Object(response)) = &mut operation.responses.default {
+            response_schemas(
+                response,
+                &push_pointer(&responses_pointer, "default"),
+                visitor,
+            );
+        }
+        for (key, response) in operation.responses.responses.iter_mut() {
+            if let ReferenceObjectOr::Object(response) = response {
+                response_schemas(
+                    response,
+                    &push_pointer(&responses_pointer, &key.to_string()),
+                    visitor,
+                );
+            }
+        }
+
+        for (name, callback) in operation.callbacks.iter_mut().flatten() {
+            if let ReferenceObjectOr::Object(callback) = callback {
+                let pointer = push_pointer(&push_pointer(&pointer, "callbacks"), name);
+                callback_schemas(callback, &pointer, visitor);
+            }
+        }
+    }
+}
+
+fn callback_schemas(callback: &mut CallbackObject, pointer: &str, visitor: &mut SchemaVisitor<'_>) {
+    for (expression, path_item) in callback.iter_mut() {
+        path_item_schemas(path_item, &push_pointer(pointer, expression), visitor);
+    }
+}
+
+fn parameter_schemas(
+    parameters: Option<&mut Vec<ReferenceObjectOr<ParameterObject>>>,
+    pointer: &str,
+    visitor: &mut SchemaVisitor<'_>,
+) {
+    for (i, parameter) in parameters.into_iter().flatten().enumerate() {
+        if let ReferenceObjectOr::Object(parameter) = parameter {
+            let pointer = push_pointer(pointer, &i.to_string());
+            schema_or_content(&mut parameter.fixed_fields_mut().schema, &pointer, visitor);
+        }
+    }
+}
+
+fn response_schemas(response: &mut ResponseObject, pointer: &str, visitor: &mut SchemaVisitor<'_>) {
+    for (name, header) in response.headers.iter_mut().flatten() {
+        if let ReferenceObjectOr::Object(header) = header {
+            let pointer = push_pointer(&push_pointer(pointer, "headers"), name);
+            schema_or_content(&mut header.schema, &pointer, visitor);
+        }
+    }
+
+    if let Some(content) = &mut response.content {
+        content_schemas(content, &push_pointer(pointer, "content"), visitor);
+    }
+}
+
+fn schema_or_content(
+    schema: &mut SchemaOrContentObject,
+    pointer: &str,
+    visitor: &mut SchemaVisitor<'_>,
+) {
+    match schema {
+        SchemaOrContentObject::Schema(schema) => visitor(&push_pointer(pointer, "schema"), schema),
+        SchemaOrContentObject::Content(content) => {
+            content_schemas(content, &push_pointer(pointer, "content"), visitor)
+        }
+    }
+}
+
+fn content_schemas(content: &mut ContentMap, pointer: &str, visitor: &mut SchemaVisitor<'_>) {
+    for (media_type, object) in content.iter_mut() {
+        if let Some(schema) = &mut object.schema {
+            visitor(
+                &push_pointer(&push_pointer(pointer, &media_type.to_string()), "schema"),
+                schema,
+            );
+        }
+    }
+}
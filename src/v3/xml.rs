@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct XmlObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -0,0 +1,102 @@
+use http::Method;
+use oa::v3::{BuildError, OpenAPIObject, OperationObject, ParameterObject, SchemaObject};
+use serde_json::json;
+
+#[test]
+fn document() {
+    let user = SchemaObject::object()
+        .required_property("id", SchemaObject::integer().format("int64"))
+        .property("name", SchemaObject::string());
+    let document = OpenAPIObject::builder()
+        .title("Users")
+        .version("1.0.0")
+        .server("https://example.com")
+        .schema("User", user)
+        .get(
+            "/users",
+            OperationObject::builder()
+                .operation_id("listUsers")
+                .parameter(ParameterObject::query("limit").schema(SchemaObject::integer()))
+                .response(
+                    200,
+                    "OK",
+                    SchemaObject::array(SchemaObject::reference("User")),
+                ),
+        )
+        .post(
+            "/users",
+            OperationObject::builder()
+                .json_body(SchemaObject::reference("User"), true)
+                .empty_response(201, "Created"),
+        )
+        .build()
+        .unwrap();
+
+    let json = serde_json::to_value(&document).unwrap();
+    assert_eq!(json["openapi"], "3.0.3");
+    assert_eq!(json["info"], json!({"title": "Users", "version": "1.0.0"}));
+    assert_eq!(json["servers"], json!([{"url": "https://example.com"}]));
+
+    let user = &json["components"]["schemas"]["User"];
+    assert_eq!(user["required"], json!(["id"]));
+    assert_eq!(user["properties"]["id"]["format"], "int64");
+
+    let users = &json["paths"]["/users"];
+    assert_eq!(users["get"]["operationId"], "listUsers");
+    assert_eq!(users["get"]["parameters"][0]["name"], "limit");
+    assert_eq!(users["get"]["parameters"][0]["in"], "query");
+    assert_eq!(users["get"]["parameters"][0]["required"], false);
+    assert_eq!(
+        users["get"]["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+        "#/components/schemas/User"
+    );
+    assert_eq!(users["post"]["requestBody"]["required"], true);
+    assert_eq!(
+        users["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/User"
+    );
+    assert_eq!(
+        users["post"]["responses"]["201"],
+        json!({"description": "Created"})
+    );
+}
+
+#[test]
+fn required_properties() {
+    let schema = SchemaObject::object()
+        .required_property("id", SchemaObject::integer())
+        .required_property("id", SchemaObject::string());
+    assert_eq!(schema.required, Some(vec!["id".to_string()]));
+}
+
+#[test]
+fn errors() {
+    let operation = || OperationObject::builder().empty_response(204, "Deleted");
+
+    assert_eq!(
+        OpenAPIObject::builder().version("1").build(),
+        Err(BuildError::MissingField("info.title"))
+    );
+    assert_eq!(
+        OperationObject::builder().build(),
+        Err(BuildError::MissingField("responses"))
+    );
+    assert_eq!(
+        ParameterObject::query("limit").build(),
+        Err(BuildError::MissingField("schema"))
+    );
+    assert_eq!(
+        OperationObject::builder()
+            .empty_response(600, "Unknown")
+            .build(),
+        Err(BuildError::InvalidStatus(600))
+    );
+    assert_eq!(
+        OpenAPIObject::builder()
+            .title("Users")
+            .version("1")
+            .operation(Method::CONNECT, "/users", operation())
+            .build(),
+        Err(BuildError::UnsupportedMethod(Method::CONNECT))
+    );
+}
@@ -0,0 +1,254 @@
+use oa::v3::{
+    codegen::{ClientGenerator, ServerGenerator, TypesGenerator},
+    OpenAPIObject,
+};
+use std::{
+    env, fs,
+    future::Future,
+    path::Path,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+fn petstore() -> OpenAPIObject {
+    serde_yaml::from_str(include_str!("fixtures/petstore.yaml")).unwrap()
+}
+
+/// Compares `actual` with the snapshot at `path`, rewriting it instead when `UPDATE_SNAPSHOTS` is set.
+fn assert_snapshot(path: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(path);
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+    } else {
+        let expected = fs::read_to_string(&path).unwrap();
+        assert_eq!(expected, actual, "{} is outdated", path.display());
+    }
+}
+
+// The snapshots must compile too
+#[allow(dead_code)]
+mod types {
+    include!("snapshots/types.rs");
+}
+
+#[allow(dead_code)]
+mod client {
+    include!("snapshots/client.rs");
+}
+
+#[allow(dead_code)]
+mod server {
+    include!("snapshots/server.rs");
+}
+
+#[test]
+fn types() {
+    assert_snapshot("types.rs", &TypesGenerator::new(&petstore()).generate());
+}
+
+#[test]
+fn types_round_trip() {
+    let json = serde_json::json!({
+        "petType": "dog",
+        "name": "Rex",
+        "owner": {"name": "Ann", "email": null},
+    });
+
+    let pet: types::Pet = serde_json::from_value(json).unwrap();
+    match &pet {
+        types::Pet::Dog(dog) => assert_eq!(dog.owner.as_ref().unwrap().name, "Ann"),
+        types::Pet::Cat(_) => panic!("expected a dog"),
+    }
+    assert_eq!(serde_json::to_value(&pet).unwrap()["petType"], "dog");
+}
+
+/// Answers every request with the same response, keeping the requests.
+struct Canned {
+    status: u16,
+    body: &'static str,
+    requests: Mutex<Vec<http::Request<Vec<u8>>>>,
+}
+
+impl client::Transport for &Canned {
+    type Error = std::convert::Infallible;
+
+    fn send(&self, request: http::Request<Vec<u8>>) -> client::ResponseFuture<'_, Self::Error> {
+        self.requests.lock().unwrap().push(request);
+        let response = http::Response::builder()
+            .status(self.status)
+            .body(self.body.as_bytes().to_vec())
+            .unwrap();
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Runs `future`, which must not wait on anything.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn client() {
+    assert_snapshot("client.rs", &ClientGenerator::new(&petstore()).generate());
+}
+
+#[test]
+fn client_round_trip() {
+    let transport = Canned {
+        status: 503,
+        body: r#"{"code": 503, "message": "Try again later"}"#,
+        requests: Mutex::new(Vec::new()),
+    };
+    let client = client::Client::new("https://petstore.example.com/v1/", &transport);
+
+    let response = block_on(client.list_pets(Some(10), client::Status::Available)).unwrap();
+    match response {
+        client::ListPetsResponse::Default(status, error) => {
+            assert_eq!(status, 503);
+            assert_eq!(error.problem.message.as_deref(), Some("Try again later"));
+        }
+        response => panic!("unexpected response {:?}", response),
+    }
+
+    let upload = client.upload_photo(
+        1,
+        Some("a b".to_string()),
+        Some(vec![client::UploadPhotoTagsItem::Cute]),
+        None,
+    );
+    assert!(matches!(
+        block_on(upload),
+        Err(client::ClientError::UnexpectedStatus(_))
+    ));
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(
+        requests[0].uri(),
+        "https://petstore.example.com/v1/pets?limit=10&status=available"
+    );
+    assert_eq!(
+        requests[1].uri(),
+        "https://petstore.example.com/v1/pets/1/photo?tags=cute"
+    );
+    assert_eq!(requests[1].headers()["cookie"], "session=a%20b");
+}
+
+/// Knows a single dog.
+struct Kennel;
+
+impl server::Server for Kennel {
+    fn list_pets(
+        &self,
+        limit: Option<i32>,
+        status: server::Status,
+    ) -> server::HandlerFuture<'_, server::ListPetsResponse> {
+        Box::pin(async move {
+            assert_eq!(limit, Some(10));
+            assert_eq!(status, server::Status::Adopted);
+            server::ListPetsResponse::Ok(Vec::new())
+        })
+    }
+
+    fn create_pet(
+        &self,
+        body: server::Pet,
+    ) -> server::HandlerFuture<'_, server::CreatePetResponse> {
+        Box::pin(async move { server::CreatePetResponse::Created(body) })
+    }
+
+    fn show_pet_by_id(
+        &self,
+        _pet_id: i64,
+    ) -> server::HandlerFuture<'_, server::ShowPetByIdResponse> {
+        Box::pin(async move { server::ShowPetByIdResponse::NotFound })
+    }
+
+    fn delete_pet(
+        &self,
+        _pet_id: i64,
+        _x_request_id: Option<String>,
+    ) -> server::HandlerFuture<'_, server::DeletePetResponse> {
+        Box::pin(async move { server::DeletePetResponse::NoContent })
+    }
+
+    fn upload_photo(
+        &self,
+        _pet_id: i64,
+        session: Option<String>,
+        tags: Option<Vec<server::UploadPhotoTagsItem>>,
+        body: Option<Vec<u8>>,
+    ) -> server::HandlerFuture<'_, server::UploadPhotoResponse> {
+        Box::pin(async move {
+            assert_eq!(session.as_deref(), Some("abc"));
+            assert_eq!(
+                tags,
+                Some(vec![
+                    server::UploadPhotoTagsItem::Cute,
+                    server::UploadPhotoTagsItem::Funny
+                ])
+            );
+            server::UploadPhotoResponse::Success(
+                http::StatusCode::CREATED,
+                body.unwrap_or_default(),
+            )
+        })
+    }
+}
+
+fn serve(method: &str, uri: &str, headers: &[(&str, &str)], body: &str) -> http::Response<Vec<u8>> {
+    let mut request = http::Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    block_on(server::handle(
+        &Kennel,
+        request.body(body.as_bytes().to_vec()).unwrap(),
+    ))
+}
+
+#[test]
+fn server() {
+    assert_snapshot("server.rs", &ServerGenerator::new(&petstore()).generate());
+}
+
+#[test]
+fn server_round_trip() {
+    let response = serve("GET", "/pets?limit=10&status=adopted", &[], "");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert_eq!(response.body(), b"[]");
+
+    let dog = r#"{"petType":"dog","name":"Rex"}"#;
+    let response = serve("POST", "/pets", &[], dog);
+    assert_eq!(response.status(), 201);
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(),
+        serde_json::from_str::<serde_json::Value>(dog).unwrap()
+    );
+
+    let response = serve(
+        "PUT",
+        "/pets/1/photo?tags=cute&tags=funny",
+        &[("cookie", "session=abc")],
+        "png",
+    );
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.headers()["content-type"], "image/png");
+    assert_eq!(response.body(), b"png");
+
+    assert_eq!(serve("GET", "/pets/1", &[], "").status(), 404);
+    assert_eq!(serve("GET", "/pets?status=lost", &[], "").status(), 400);
+    assert_eq!(serve("GET", "/pets/rex", &[], "").status(), 400);
+    assert_eq!(serve("POST", "/pets", &[], "{}").status(), 400);
+    assert_eq!(serve("PATCH", "/pets", &[], "").status(), 405);
+    assert_eq!(serve("GET", "/owners", &[], "").status(), 404);
+}
@@ -0,0 +1,270 @@
+// The types are only described, never constructed
+#![allow(dead_code)]
+
+use oa::v3::{
+    ComponentsObject, OperationObject, Operations, PathsItemObject, ReferenceObjectOr,
+    SchemaObject, ToSchema,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// A pet in the store.
+#[derive(Serialize, oa_derive::ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct Pet {
+    /// The unique identifier.
+    pet_id: u64,
+    nick_name: Option<String>,
+    #[serde(rename = "kind")]
+    species: Species,
+    #[serde(skip)]
+    _secret: String,
+    #[serde(flatten)]
+    extra: Labels,
+}
+
+#[derive(Serialize, oa_derive::ToSchema)]
+struct Labels {
+    #[serde(default)]
+    labels: HashMap<String, String>,
+}
+
+#[derive(Serialize, oa_derive::ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum Species {
+    DomesticCat,
+    Dog,
+}
+
+#[derive(oa_derive::ToSchema)]
+#[serde(rename_all = "camelCase")]
+enum Dessert {
+    Éclair,
+    ApplePie,
+}
+
+#[derive(Serialize, oa_derive::ToSchema)]
+#[serde(tag = "type", content = "data")]
+enum Event {
+    Adopted(Pet),
+    Renamed { name: String },
+    Lost,
+}
+
+#[derive(Serialize, oa_derive::ToSchema)]
+#[serde(untagged)]
+enum Id {
+    Number(u32),
+    Pair(u32, u32),
+}
+
+fn schema(value: Value) -> ReferenceObjectOr<SchemaObject> {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn schemas() {
+    let mut components = ComponentsObject::default();
+    components.register::<Event>();
+    let schemas = components.schemas.unwrap();
+
+    assert_eq!(
+        schemas["Pet"],
+        schema(json!({
+            "allOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "petId": {
+                            "type": "integer",
+                            "format": "int64",
+                            "description": "The unique identifier."
+                        },
+                        "nickName": { "type": "string", "nullable": true },
+                        "kind": { "$ref": "#/components/schemas/Species" }
+                    },
+                    "required": ["petId", "kind"]
+                },
+                { "$ref": "#/components/schemas/Labels" }
+            ],
+            "description": "A pet in the store."
+        }))
+    );
+    assert_eq!(
+        schemas["Species"],
+        schema(json!({ "type": "string", "enum": ["domestic_cat", "dog"] }))
+    );
+    assert_eq!(
+        schemas["Labels"],
+        schema(json!({
+            "type": "object",
+            "properties": {
+                "labels": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" }
+                }
+            }
+        }))
+    );
+    assert_eq!(
+        schemas["Event"],
+        schema(json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": { "type": "string", "enum": ["Adopted"] },
+                        "data": { "$ref": "#/components/schemas/Pet" }
+                    },
+                    "required": ["type", "data"]
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": { "type": "string", "enum": ["Renamed"] },
+                        "data": {
+                            "type": "object",
+                            "properties": { "name": { "type": "string" } },
+                            "required": ["name"]
+                        }
+                    },
+                    "required": ["type", "data"]
+                },
+                {
+                    "type": "object",
+                    "properties": { "type": { "type": "string", "enum": ["Lost"] } },
+                    "required": ["type"]
+                }
+            ]
+        }))
+    );
+
+    assert_eq!(
+        ReferenceObjectOr::Object(Dessert::schema()),
+        schema(json!({ "type": "string", "enum": ["Éclair", "applePie"] }))
+    );
+
+    assert_eq!(
+        ReferenceObjectOr::Object(Id::schema()),
+        schema(json!({
+            "oneOf": [
+                { "type": "integer", "format": "int64" },
+                {
+                    "type": "array",
+                    "items": { "type": "integer", "format": "int64" },
+                    "minItems": 2,
+                    "maxItems": 2
+                }
+            ]
+        }))
+    );
+}
+
+/// Finds a pet.
+///
+/// Adopted pets are found too.
+#[oa_derive::operation(get, "/pets/{petId}", tag = "pets", operation_id = "findPet")]
+fn find_pet(
+    #[path("petId")] pet_id: u64,
+    #[header("X-Request-Id")] request_id: Option<String>,
+) -> Result<Pet, String> {
+    Err(format!("{} not found ({:?})", pet_id, request_id))
+}
+
+#[oa_derive::operation(post, "/pets/{petId}/events", tag = "pets", status = 201)]
+fn record_event(#[body] event: Event, #[query] notify: Option<bool>) {
+    let _ = (event, notify);
+}
+
+#[test]
+fn operations() {
+    let info = serde_json::from_value(json!({ "title": "Pets", "version": "1.0.0" })).unwrap();
+    let document = Operations::new()
+        .with::<find_pet>()
+        .with::<record_event>()
+        .into_document(info);
+
+    let operation = |template: &str| -> &OperationObject {
+        match &document.paths[template] {
+            ReferenceObjectOr::Object(PathsItemObject {
+                get: Some(operation),
+                ..
+            })
+            | ReferenceObjectOr::Object(PathsItemObject {
+                post: Some(operation),
+                ..
+            }) => operation,
+            path_item => panic!("{:?} has no operation", path_item),
+        }
+    };
+    let expected = |value: Value| -> OperationObject { serde_json::from_value(value).unwrap() };
+
+    assert_eq!(
+        *operation("/pets/{petId}"),
+        expected(json!({
+            "tags": ["pets"],
+            "summary": "Finds a pet.",
+            "description": "Adopted pets are found too.",
+            "operationId": "findPet",
+            "parameters": [
+                {
+                    "in": "path",
+                    "name": "petId",
+                    "required": true,
+                    "schema": { "type": "integer", "format": "int64" }
+                },
+                {
+                    "in": "header",
+                    "name": "X-Request-Id",
+                    "schema": { "type": "string" }
+                }
+            ],
+            "responses": {
+                "200": {
+                    "description": "OK",
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/Pet" }
+                        }
+                    }
+                }
+            }
+        }))
+    );
+    assert_eq!(
+        *operation("/pets/{petId}/events"),
+        expected(json!({
+            "tags": ["pets"],
+            "operationId": "record_event",
+            "parameters": [
+                {
+                    "in": "path",
+                    "name": "petId",
+                    "required": true,
+                    "schema": { "type": "string" }
+                },
+                {
+                    "in": "query",
+                    "name": "notify",
+                    "schema": { "type": "boolean" }
+                }
+            ],
+            "requestBody": {
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": { "$ref": "#/components/schemas/Event" }
+                    }
+                }
+            },
+            "responses": { "201": { "description": "Created" } }
+        }))
+    );
+
+    let schemas = document.components.unwrap().schemas.unwrap();
+    assert_eq!(
+        schemas.keys().collect::<Vec<_>>(),
+        ["Pet", "Species", "Labels", "Event"]
+    );
+}
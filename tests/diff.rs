@@ -0,0 +1,410 @@
+use http::Method;
+use oa::v3::{
+    diff::{diff, Change, ChangeKind, Diff, Severity},
+    OpenAPIObject, ParameterLocation, ResolveError, SchemaType, SecurityRequirementObject,
+};
+use serde_json::json;
+
+/// A document made of `fields`, with the required ones filled in.
+fn document(fields: &str) -> OpenAPIObject {
+    let source = format!(
+        "openapi: 3.0.3\ninfo: {{title: Pets, version: '1'}}\nservers: []\n{}",
+        fields
+    );
+    serde_yaml::from_str(&source).unwrap()
+}
+
+fn changes(old: &str, new: &str) -> Vec<(String, ChangeKind, Severity)> {
+    diff(&document(old), &document(new))
+        .changes
+        .into_iter()
+        .map(|change| (change.pointer, change.kind, change.severity))
+        .collect()
+}
+
+#[test]
+fn required_parameter_added() {
+    let old = r#"
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, schema: {type: integer}}
+      responses: {"200": {description: Pets}}
+"#;
+    let new = r#"
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, schema: {type: integer}}
+        - {name: owner, in: query, required: true, schema: {type: string}}
+        - {name: X-Trace, in: header, schema: {type: string}}
+      responses: {"200": {description: Pets}}
+"#;
+
+    let diff = diff(&document(old), &document(new));
+    assert_eq!(
+        diff.changes,
+        vec![
+            Change {
+                pointer: "/paths/~1pets/get/parameters/1".to_string(),
+                path: Some("/pets".to_string()),
+                method: Some(Method::GET),
+                kind: ChangeKind::ParameterAdded {
+                    location: ParameterLocation::Query,
+                    name: "owner".to_string(),
+                    required: true,
+                },
+                severity: Severity::Breaking,
+            },
+            Change {
+                pointer: "/paths/~1pets/get/parameters/2".to_string(),
+                path: Some("/pets".to_string()),
+                method: Some(Method::GET),
+                kind: ChangeKind::ParameterAdded {
+                    location: ParameterLocation::Header,
+                    name: "X-Trace".to_string(),
+                    required: false,
+                },
+                severity: Severity::NonBreaking,
+            },
+        ]
+    );
+    assert!(diff.is_breaking());
+}
+
+#[test]
+fn enum_narrowed() {
+    let source = |statuses: &str| {
+        format!(
+            r#"
+paths:
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: {{type: string, enum: [{statuses}]}}
+      responses:
+        "200":
+          description: A pet.
+          content:
+            application/json:
+              schema: {{type: string, enum: [{statuses}]}}
+"#,
+            statuses = statuses
+        )
+    };
+
+    // Fewer values are accepted in requests but fewer values are returned in responses
+    assert_eq!(
+        changes(&source("available, sold"), &source("available")),
+        vec![
+            (
+                "/paths/~1pets/post/requestBody/content/application~1json/schema".to_string(),
+                ChangeKind::EnumValuesRemoved(vec![json!("sold")]),
+                Severity::Breaking,
+            ),
+            (
+                "/paths/~1pets/post/responses/200/content/application~1json/schema".to_string(),
+                ChangeKind::EnumValuesRemoved(vec![json!("sold")]),
+                Severity::NonBreaking,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn response_removed() {
+    let old = r#"
+paths:
+  /pets/{id}:
+    delete:
+      responses:
+        "204": {description: Deleted}
+        "404": {description: Not found}
+"#;
+    let new = r#"
+paths:
+  /pets/{id}:
+    delete:
+      responses:
+        "204": {description: Deleted}
+        default: {description: An error}
+"#;
+
+    assert_eq!(
+        changes(old, new),
+        vec![
+            (
+                "/paths/~1pets~1{id}/delete/responses/404".to_string(),
+                ChangeKind::ResponseRemoved("404".to_string()),
+                Severity::Breaking,
+            ),
+            (
+                "/paths/~1pets~1{id}/delete/responses/default".to_string(),
+                ChangeKind::ResponseAdded("default".to_string()),
+                Severity::NonBreaking,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn security_added() {
+    let source = |security: &str| {
+        format!(
+            r#"
+security: {security}
+paths:
+  /pets:
+    get:
+      responses: {{"200": {{description: Pets}}}}
+    post:
+      responses: {{"201": {{description: Created}}}}
+  /stores:
+    get:
+      security: []
+      responses: {{"200": {{description: Stores}}}}
+"#,
+            security = security
+        )
+    };
+    let requirement = |name: &str| -> SecurityRequirementObject {
+        std::iter::once((name.to_string(), Vec::new())).collect()
+    };
+
+    // Securing public operations breaks their clients, the change is reported once for all the operations inheriting it
+    assert_eq!(
+        changes(&source("[]"), &source("[{apiKey: []}]")),
+        vec![(
+            "/security".to_string(),
+            ChangeKind::SecurityRequirementAdded(requirement("apiKey")),
+            Severity::Breaking,
+        )]
+    );
+    // Alternatives only offer a new way in
+    assert_eq!(
+        changes(
+            &source("[{apiKey: []}]"),
+            &source("[{apiKey: []}, {oauth: []}]")
+        ),
+        vec![(
+            "/security".to_string(),
+            ChangeKind::SecurityRequirementAdded(requirement("oauth")),
+            Severity::NonBreaking,
+        )]
+    );
+
+    let old = source("[]");
+    let new = source("[]").replace("      security: []\n", "      security: [{apiKey: []}]\n");
+    let diff = diff(&document(&old), &document(&new));
+    assert_eq!(diff.changes.len(), 1);
+    assert_eq!(diff.changes[0].pointer, "/paths/~1stores/get/security");
+    assert_eq!(diff.changes[0].path.as_deref(), Some("/stores"));
+    assert_eq!(diff.changes[0].severity, Severity::Breaking);
+}
+
+#[test]
+fn path_variable_renamed() {
+    let source = |variable: &str| {
+        format!(
+            r#"
+paths:
+  /pets/{{{variable}}}:
+    get:
+      parameters:
+        - {{name: {variable}, in: path, required: true, schema: {{type: integer}}}}
+      responses: {{"200": {{description: A pet.}}}}
+"#,
+            variable = variable
+        )
+    };
+
+    assert_eq!(changes(&source("id"), &source("petId")), vec![]);
+
+    let new = source("petId").replace("type: integer", "type: string");
+    assert_eq!(
+        changes(&source("id"), &new),
+        vec![(
+            "/paths/~1pets~1{id}/get/parameters/0/schema".to_string(),
+            ChangeKind::TypeChanged {
+                old: Some(SchemaType::Integer),
+                new: Some(SchemaType::String),
+            },
+            Severity::Breaking,
+        )]
+    );
+}
+
+#[test]
+fn unresolved_references() {
+    let old = r##"
+paths:
+  /pets:
+    get:
+      parameters:
+        - $ref: "#/components/parameters/limit"
+      responses:
+        "200":
+          description: Pets
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/Pets"}
+"##;
+    let new = old.replace("Pets\"}", "Pet\"}");
+
+    assert_eq!(
+        changes(old, &new),
+        vec![
+            (
+                "/paths/~1pets/get/parameters/0".to_string(),
+                ChangeKind::UnresolvedReference(ResolveError::NotFound(
+                    "#/components/parameters/limit".to_string()
+                )),
+                Severity::Unknown,
+            ),
+            (
+                "/paths/~1pets/get/responses/200/content/application~1json/schema".to_string(),
+                ChangeKind::UnresolvedReference(ResolveError::NotFound(
+                    "#/components/schemas/Pets".to_string()
+                )),
+                Severity::Unknown,
+            ),
+            (
+                "/paths/~1pets/get/responses/200/content/application~1json/schema".to_string(),
+                ChangeKind::UnresolvedReference(ResolveError::NotFound(
+                    "#/components/schemas/Pet".to_string()
+                )),
+                Severity::Unknown,
+            ),
+        ]
+    );
+}
+
+fn changelog_documents() -> (OpenAPIObject, OpenAPIObject) {
+    let old = document(
+        r#"
+tags: [{name: stores}, {name: pets}]
+paths:
+  /pets:
+    get:
+      tags: [pets]
+      summary: List pets
+      parameters:
+        - {name: limit, in: query, schema: {type: integer, maximum: 100}}
+      responses: {"200": {description: Pets}}
+    delete:
+      tags: [pets]
+      responses: {"204": {description: Deleted}}
+  /health:
+    get:
+      operationId: health
+      responses: {"200": {description: Healthy}}
+components:
+  securitySchemes:
+    apiKey: {type: apiKey, name: key, in: header}
+"#,
+    );
+    let new = document(
+        r#"
+tags: [{name: stores}, {name: pets}]
+paths:
+  /pets:
+    get:
+      tags: [pets]
+      summary: List pets
+      parameters:
+        - {name: limit, in: query, schema: {type: integer, maximum: 50}}
+        - {name: sort, in: query, schema: {type: string}}
+      responses: {"200": {description: Pets}}
+  /health:
+    get:
+      operationId: health
+      responses:
+        "200": {description: Healthy}
+        "503": {description: Unhealthy}
+  /stores/{id}:
+    get:
+      responses: {"200": {description: A store}}
+components:
+  securitySchemes:
+    apiKey: {type: apiKey, name: key, in: query}
+"#,
+    );
+    (old, new)
+}
+
+#[test]
+fn markdown_changelog() {
+    let (old, new) = changelog_documents();
+    let diff = diff(&old, &new);
+
+    assert_eq!(
+        diff.to_markdown(&old, &new),
+        "\
+## pets
+
+### List pets (`GET /pets`)
+
+- **Breaking:** `maximum` changed from 100 to 50 (`/parameters/0/schema`)
+- optional query parameter `sort` added (`/parameters/1`)
+
+### `DELETE /pets`
+
+- **Breaking:** operation removed
+
+## Other
+
+### health (`GET /health`)
+
+- response `503` added (`/responses/503`)
+
+### `/stores/{id}`
+
+- path added
+
+## General
+
+- **Possibly breaking:** security scheme `apiKey` changed
+"
+    );
+    assert_eq!(Diff::default().to_markdown(&old, &new), "No changes.\n");
+}
+
+#[test]
+fn text_changelog() {
+    let (old, new) = changelog_documents();
+    let diff = diff(&old, &new);
+
+    assert_eq!(
+        diff.to_text(&old, &new),
+        "\
+pets
+====
+
+List pets (GET /pets)
+  - [BREAKING] `maximum` changed from 100 to 50 at /parameters/0/schema
+  - optional query parameter `sort` added at /parameters/1
+
+DELETE /pets
+  - [BREAKING] operation removed
+
+Other
+=====
+
+health (GET /health)
+  - response `503` added at /responses/503
+
+/stores/{id}
+  - path added
+
+General
+=======
+
+  - [POSSIBLY BREAKING] security scheme `apiKey` changed
+"
+    );
+    assert_eq!(Diff::default().to_text(&old, &new), "No changes.\n");
+}
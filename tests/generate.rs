@@ -0,0 +1,104 @@
+use oa::v3::{generate::ExampleGenerator, validation::Validator, OpenAPIObject, SchemaObject};
+use serde_json::json;
+
+fn document() -> OpenAPIObject {
+    serde_yaml::from_str(
+        r##"
+openapi: 3.0.3
+info: {title: Examples, version: "1"}
+servers: []
+paths: {}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name: {type: string, minLength: 2, maxLength: 8}
+        age: {type: integer, minimum: 1, maximum: 30}
+        weight: {type: number, minimum: 1, maximum: 2, exclusiveMinimum: true, exclusiveMaximum: true}
+        tags: {type: array, items: {type: string, pattern: "^[a-z]{3}$"}, minItems: 2, uniqueItems: true}
+        owner: {$ref: "#/components/schemas/Owner"}
+    Owner:
+      type: object
+      minProperties: 3
+      properties:
+        email: {type: string, format: email}
+        pets: {type: array, items: {$ref: "#/components/schemas/Pet"}}
+    Labels:
+      type: object
+      minProperties: 2
+      maxProperties: 2
+      additionalProperties: {type: string, enum: [red, blue]}
+    Summary:
+      type: object
+      maxProperties: 1
+      properties:
+        first: {type: string}
+        second: {type: string}
+        third: {type: string}
+"##,
+    )
+    .unwrap()
+}
+
+fn schema(source: serde_json::Value) -> SchemaObject {
+    serde_json::from_value(source).unwrap()
+}
+
+#[test]
+fn valid_examples() {
+    let document = document();
+    let validator = Validator::new().with_document(&document);
+    let schemas = document.components.as_ref().unwrap().schemas.as_ref();
+
+    for (name, schema) in schemas.unwrap() {
+        let generators = std::iter::once(ExampleGenerator::new())
+            .chain((0..50).map(|seed| ExampleGenerator::new().with_seed(seed)));
+        for generator in generators {
+            let value = generator.with_document(&document).generate(schema);
+            let report = validator.validate(schema, &value);
+            assert!(report.is_valid(), "{}: {} {:?}", name, value, report.errors);
+        }
+    }
+}
+
+#[test]
+fn close_bounds() {
+    let weight = schema(json!({
+        "type": "number",
+        "minimum": 1,
+        "maximum": 2,
+        "exclusiveMinimum": true,
+        "exclusiveMaximum": true,
+    }));
+    assert_eq!(ExampleGenerator::new().generate_schema(&weight), json!(1.5));
+
+    let integer = schema(json!({"type": "number", "minimum": 1, "maximum": 2}));
+    assert_eq!(
+        ExampleGenerator::new().generate_schema(&integer),
+        json!(1.0)
+    );
+}
+
+#[test]
+fn property_counts() {
+    let document = document();
+    let generator = ExampleGenerator::new().with_document(&document);
+    let schemas = document
+        .components
+        .as_ref()
+        .unwrap()
+        .schemas
+        .as_ref()
+        .unwrap();
+
+    assert_eq!(
+        generator.generate(&schemas["Labels"]),
+        json!({"additionalProp1": "red", "additionalProp2": "red"})
+    );
+    assert_eq!(
+        generator.generate(&schemas["Summary"]),
+        json!({"first": "string"})
+    );
+}
@@ -0,0 +1,166 @@
+use oa::v3::{
+    merge::{merge, ConflictPolicy, MergeError, MergeSource},
+    OpenAPIObject, ReferenceObjectOr,
+};
+
+/// A document with a `GET` operation on `path` returning the `User` schema, defined with `name` of type `name_type`.
+fn document(path: &str, name_type: &str) -> OpenAPIObject {
+    let source = format!(
+        r##"
+openapi: 3.0.3
+info: {{title: "{path}", version: "1"}}
+servers: []
+paths:
+  {path}:
+    get:
+      responses:
+        "200":
+          description: A user.
+          content:
+            application/json:
+              schema: {{$ref: "#/components/schemas/User"}}
+components:
+  schemas:
+    Error: {{type: object, properties: {{message: {{type: string}}}}}}
+    User: {{type: object, properties: {{name: {{type: {name_type}}}}}}}
+"##,
+        path = path,
+        name_type = name_type
+    );
+    serde_yaml::from_str(&source).unwrap()
+}
+
+fn sources(documents: Vec<OpenAPIObject>) -> impl Iterator<Item = MergeSource> {
+    documents.into_iter().map(MergeSource::from)
+}
+
+fn name_type(document: &OpenAPIObject, schema: &str) -> serde_json::Value {
+    let schemas = document.components.as_ref().unwrap().schemas.as_ref();
+    let schema = serde_json::to_value(&schemas.unwrap()[schema]).unwrap();
+    schema["properties"]["name"]["type"].clone()
+}
+
+/// Sets the description of the `GET` operation of the only path of `document`.
+fn described(mut document: OpenAPIObject, description: &str) -> OpenAPIObject {
+    let path_item = document.paths.values_mut().next().unwrap();
+    if let ReferenceObjectOr::Object(path_item) = path_item {
+        path_item.get.as_mut().unwrap().description = Some(description.to_string());
+    }
+    document
+}
+
+#[test]
+fn error_policy() {
+    // Identical components are kept once
+    let merged = merge(
+        sources(vec![
+            document("/users", "string"),
+            document("/admins", "string"),
+        ]),
+        ConflictPolicy::Error,
+    )
+    .unwrap();
+    assert_eq!(merged.info.title, "/users");
+    assert_eq!(
+        merged.paths.keys().collect::<Vec<_>>(),
+        ["/users", "/admins"]
+    );
+    assert_eq!(merged.components.unwrap().schemas.unwrap().len(), 2);
+
+    assert_eq!(
+        merge(
+            sources(vec![
+                document("/users", "string"),
+                document("/admins", "integer"),
+            ]),
+            ConflictPolicy::Error,
+        ),
+        Err(MergeError::Conflict("/components/schemas/User".to_string()))
+    );
+    assert_eq!(
+        merge(
+            sources(vec![
+                document("/users", "string"),
+                described(document("/users", "string"), "Changed"),
+            ]),
+            ConflictPolicy::Error,
+        ),
+        Err(MergeError::Conflict("/paths/~1users/get".to_string()))
+    );
+    assert_eq!(
+        merge(sources(vec![]), ConflictPolicy::Error),
+        Err(MergeError::NoSources)
+    );
+}
+
+#[test]
+fn first_wins_policy() {
+    let merged = merge(
+        sources(vec![
+            document("/users", "string"),
+            document("/admins", "integer"),
+        ]),
+        ConflictPolicy::FirstWins,
+    )
+    .unwrap();
+
+    assert_eq!(merged.paths.len(), 2);
+    assert_eq!(name_type(&merged, "User"), "string");
+}
+
+#[test]
+fn prefix_policy() {
+    let merged = merge(
+        vec![
+            MergeSource::new(document("/users", "string")).with_namespace("accounts"),
+            MergeSource::new(document("/users", "integer"))
+                .with_namespace("billing")
+                .with_path_prefix("/billing"),
+        ],
+        ConflictPolicy::Prefix,
+    )
+    .unwrap();
+
+    let schemas = merged
+        .components
+        .as_ref()
+        .unwrap()
+        .schemas
+        .as_ref()
+        .unwrap();
+    assert_eq!(
+        schemas.keys().collect::<Vec<_>>(),
+        [
+            "accounts_Error",
+            "accounts_User",
+            "billing_Error",
+            "billing_User"
+        ]
+    );
+    assert_eq!(name_type(&merged, "billing_User"), "integer");
+
+    // References follow the renamed components
+    let paths = serde_json::to_value(&merged.paths).unwrap();
+    let schema = |path: &str| {
+        paths[path]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"]
+            .clone()
+    };
+    assert_eq!(schema("/users"), "#/components/schemas/accounts_User");
+    assert_eq!(
+        schema("/billing/users"),
+        "#/components/schemas/billing_User"
+    );
+
+    // Sources without a namespace still conflict
+    assert_eq!(
+        merge(
+            vec![
+                MergeSource::new(document("/users", "string")).with_namespace("accounts"),
+                MergeSource::new(document("/admins", "integer")),
+                MergeSource::new(document("/teams", "boolean")),
+            ],
+            ConflictPolicy::Prefix,
+        ),
+        Err(MergeError::Conflict("/components/schemas/User".to_string()))
+    );
+}
@@ -0,0 +1,296 @@
+use bytes::Bytes;
+use http::{Method, Request};
+use oa::v3::{
+    router::{RouteError, Router},
+    validation::{validate_request, ValidationErrorKind},
+    OpenAPIObject, SchemaType,
+};
+
+fn document() -> OpenAPIObject {
+    serde_yaml::from_str(
+        r#"
+openapi: 3.0.3
+info: {title: Files, version: "1"}
+servers:
+  - url: https://example.com/api/v1
+  - url: /api
+paths:
+  /files/{name}.{extension}:
+    get:
+      responses: {"200": {description: A file.}}
+  /files/{id}:
+    get:
+      responses: {"200": {description: A file.}}
+    delete:
+      responses: {"204": {description: Deleted.}}
+  /files/latest:
+    get:
+      operationId: latest
+      responses: {"200": {description: The latest file.}}
+  /files:
+    post:
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: {type: object, required: [name], properties: {name: {type: string}}}
+      responses: {"201": {description: Created.}}
+    put:
+      requestBody:
+        content:
+          application/json:
+            schema: {type: object}
+      responses: {"204": {description: Replaced.}}
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn precedence() {
+    let document = document();
+    let router = Router::new(&document);
+
+    // Concrete paths are preferred to templated ones, whatever their order in the document
+    let route = router.find(&Method::GET, "/api/v1/files/latest").unwrap();
+    assert_eq!(route.template, "/files/latest");
+    assert_eq!(route.operation.operation_id.as_deref(), Some("latest"));
+
+    let route = router.find(&Method::GET, "/api/v1/files/a%20b").unwrap();
+    assert_eq!(route.template, "/files/{id}");
+    assert_eq!(route.path_parameters["id"], "a b");
+
+    // The longest capture is tried first, splitting on the last dot
+    let route = router.find(&Method::GET, "/api/v1/files/a.tar.gz").unwrap();
+    assert_eq!(route.template, "/files/{name}.{extension}");
+    assert_eq!(route.path_parameters["name"], "a.tar");
+    assert_eq!(route.path_parameters["extension"], "gz");
+}
+
+#[test]
+fn base_paths() {
+    let document = document();
+    let router = Router::new(&document);
+
+    assert!(router.find(&Method::POST, "/api/v1/files").is_ok());
+    assert!(router.find(&Method::POST, "/api/files").is_ok());
+    assert_eq!(
+        router.find(&Method::POST, "/files").unwrap_err(),
+        RouteError::NotFound
+    );
+    // Prefixes only match whole segments
+    assert_eq!(
+        router.find(&Method::POST, "/apiv1/files").unwrap_err(),
+        RouteError::NotFound
+    );
+
+    assert_eq!(
+        router.find(&Method::PATCH, "/api/files/1").unwrap_err(),
+        RouteError::MethodNotAllowed {
+            allowed: vec![Method::GET, Method::DELETE]
+        }
+    );
+}
+
+#[test]
+fn empty_bodies() {
+    let document = document();
+    let request = |method: Method, content_type: Option<&str>| {
+        let mut request = Request::builder().method(method).uri("/api/files");
+        if let Some(content_type) = content_type {
+            request = request.header("Content-Type", content_type);
+        }
+        request.body(Bytes::new()).unwrap()
+    };
+
+    // A required body is missing, whether the request declares a type or not
+    for content_type in [None, Some("application/json")] {
+        let report = validate_request(&document, &request(Method::POST, content_type));
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].pointer, "/body");
+        assert_eq!(report.errors[0].kind, ValidationErrorKind::MissingBody);
+    }
+
+    // An optional one can be left out
+    assert!(validate_request(&document, &request(Method::PUT, None)).is_valid());
+
+    let request = Request::post("/api/files")
+        .header("Content-Type", "application/json")
+        .body(Bytes::from_static(b"{}"))
+        .unwrap();
+    let report = validate_request(&document, &request);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].pointer, "/body");
+    assert_eq!(
+        report.errors[0].kind,
+        ValidationErrorKind::Required("name".to_string())
+    );
+}
+
+fn users() -> OpenAPIObject {
+    serde_yaml::from_str(
+        r##"
+openapi: 3.0.3
+info: {title: Users, version: "1"}
+servers: []
+paths:
+  /users/{id}:
+    parameters:
+      - {name: id, in: path, required: true, schema: {type: integer}}
+    post:
+      parameters:
+        - {name: tenant, in: path, required: true, schema: {type: string}}
+        - {name: limit, in: query, required: true, schema: {type: integer, maximum: 100}}
+        - {name: X-Request-Id, in: header, required: true, schema: {type: string, format: uuid}}
+        - {name: session, in: cookie, required: true, schema: {type: string, minLength: 4}}
+        - {name: ids, in: query, schema: {type: array, items: {$ref: "#/components/schemas/Loop"}}}
+      requestBody:
+        required: true
+        content:
+          application/*:
+            schema: {type: object, required: [name]}
+          text/plain:
+            schema: {type: string, maxLength: 5}
+      responses: {"204": {description: Updated.}}
+components:
+  schemas:
+    Loop:
+      anyOf: [{$ref: "#/components/schemas/Loop"}]
+"##,
+    )
+    .unwrap()
+}
+
+fn validate(
+    document: &OpenAPIObject,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: &'static str,
+) -> Vec<(String, ValidationErrorKind)> {
+    let mut request = Request::post(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let request = request.body(Bytes::from_static(body.as_bytes())).unwrap();
+    validate_request(document, &request)
+        .errors
+        .into_iter()
+        .map(|error| (error.pointer, error.kind))
+        .collect()
+}
+
+#[test]
+fn parameters() {
+    let document = users();
+    let json = ("Content-Type", "application/json");
+
+    // `tenant` isn't part of the template, so it is always missing
+    assert_eq!(
+        validate(&document, "/users/1", &[json], r#"{"name": "a"}"#),
+        vec![
+            (
+                "/path/tenant".to_string(),
+                ValidationErrorKind::MissingParameter
+            ),
+            (
+                "/query/limit".to_string(),
+                ValidationErrorKind::MissingParameter
+            ),
+            (
+                "/header/X-Request-Id".to_string(),
+                ValidationErrorKind::MissingParameter
+            ),
+            (
+                "/cookie/session".to_string(),
+                ValidationErrorKind::MissingParameter
+            ),
+        ]
+    );
+
+    // The items of `ids` are typeless and recursive
+    let errors = validate(
+        &document,
+        "/users/abc?limit=500&ids=1&ids=2",
+        &[
+            json,
+            ("X-Request-Id", "nope"),
+            ("Cookie", "theme=dark; session=ab"),
+        ],
+        r#"{"name": "a"}"#,
+    );
+    assert_eq!(
+        errors,
+        vec![
+            (
+                "/path/id".to_string(),
+                ValidationErrorKind::Type {
+                    expected: SchemaType::Integer,
+                    found: "string"
+                }
+            ),
+            (
+                "/path/tenant".to_string(),
+                ValidationErrorKind::MissingParameter
+            ),
+            (
+                "/query/limit".to_string(),
+                ValidationErrorKind::Maximum {
+                    maximum: 100,
+                    exclusive: false
+                }
+            ),
+            (
+                "/header/X-Request-Id".to_string(),
+                ValidationErrorKind::Format("uuid".to_string())
+            ),
+            (
+                "/cookie/session".to_string(),
+                ValidationErrorKind::MinLength(4)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn bodies() {
+    let document = users();
+    let uri = "/users/1?limit=10";
+    let validate = |content_type: &str, body| {
+        let headers = [
+            ("Content-Type", content_type),
+            ("X-Request-Id", "3fa85f64-5717-4562-b3fc-2c963f66afa6"),
+            ("Cookie", "session=abcd"),
+        ];
+        validate(&document, uri, &headers, body)
+            .into_iter()
+            .filter(|(pointer, _)| pointer.starts_with("/body"))
+            .collect::<Vec<_>>()
+    };
+
+    // `application/*` covers every application type
+    assert!(validate("application/json", r#"{"name": "a"}"#).is_empty());
+    assert!(validate("application/vnd.users+json", r#"{"name": "a"}"#).is_empty());
+    assert_eq!(
+        validate("application/json", "{}"),
+        vec![(
+            "/body".to_string(),
+            ValidationErrorKind::Required("name".to_string())
+        )]
+    );
+    assert_eq!(
+        validate("text/plain", "too long"),
+        vec![("/body".to_string(), ValidationErrorKind::MaxLength(5))]
+    );
+
+    assert_eq!(
+        validate("image/png", "png"),
+        vec![(
+            "/body".to_string(),
+            ValidationErrorKind::UnsupportedMediaType("image/png".to_string())
+        )]
+    );
+    assert_eq!(
+        validate("application/json", ""),
+        vec![("/body".to_string(), ValidationErrorKind::MissingBody)]
+    );
+}
@@ -0,0 +1,400 @@
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DogOwner {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Dog {
+    pub name: String,
+    /// Whether he is.
+    ///
+    /// He is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub good_boy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<DogOwner>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Breed {
+    #[serde(rename = "siamese")]
+    Siamese,
+    #[serde(rename = "maine-coon")]
+    MaineCoon,
+    #[serde(rename = "")]
+    Empty,
+    #[serde(rename = "3legged")]
+    _3legged,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cat {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lives: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<Breed>,
+}
+
+/// A pet, told apart by its `petType`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "petType")]
+pub enum Pet {
+    #[serde(rename = "dog")]
+    Dog(Dog),
+    #[serde(rename = "Cat")]
+    Cat(Cat),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Status {
+    #[serde(rename = "available")]
+    Available,
+    #[serde(rename = "adopted")]
+    Adopted,
+}
+
+pub type Pets = Vec<Pet>;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Problem {
+    pub code: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Error {
+    #[serde(flatten)]
+    pub problem: Problem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum Id {
+    String(String),
+    Integer(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Category {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<Category>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<Category>>,
+    #[serde(flatten)]
+    pub additional_properties: std::collections::HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Tree(pub Vec<Tree>);
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum UploadPhotoTagsItem {
+    #[serde(rename = "cute")]
+    Cute,
+    #[serde(rename = "funny")]
+    Funny,
+}
+
+/// The future returned by [`Transport::send`].
+pub type ResponseFuture<'t, E> = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<http::Response<Vec<u8>>, E>> + Send + 't>,
+>;
+
+/// Sends the requests of a [`Client`], e.g. with `hyper` or `reqwest`.
+pub trait Transport {
+    type Error;
+
+    fn send(&self, request: http::Request<Vec<u8>>) -> ResponseFuture<'_, Self::Error>;
+}
+
+/// The error returned by the methods of [`Client`].
+#[derive(Debug)]
+pub enum ClientError<E> {
+    /// The transport failed to send the request.
+    Transport(E),
+    /// The request could not be built, e.g. because a header value is invalid.
+    Http(http::Error),
+    /// A body could not be serialized or deserialized.
+    Json(serde_json::Error),
+    /// The server answered with a status code the operation doesn't declare.
+    UnexpectedStatus(http::Response<Vec<u8>>),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ClientError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Transport(error) => write!(f, "failed to send the request: {}", error),
+            ClientError::Http(error) => write!(f, "invalid request: {}", error),
+            ClientError::Json(error) => write!(f, "invalid JSON body: {}", error),
+            ClientError::UnexpectedStatus(response) => {
+                write!(f, "unexpected status {}", response.status())
+            }
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for ClientError<E> {}
+
+impl<E> From<http::Error> for ClientError<E> {
+    fn from(error: http::Error) -> Self {
+        ClientError::Http(error)
+    }
+}
+
+impl<E> From<serde_json::Error> for ClientError<E> {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Json(error)
+    }
+}
+
+/// A client for the operations of the API.
+#[derive(Debug, Clone)]
+pub struct Client<T> {
+    base_url: String,
+    transport: T,
+}
+
+impl<T: Transport> Client<T> {
+    /// Creates a client sending requests to `base_url`, e.g. `https://example.com/v1`, with `transport`.
+    pub fn new(base_url: impl Into<String>, transport: T) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+
+        Self {
+            base_url,
+            transport,
+        }
+    }
+
+    pub async fn list_pets(
+        &self,
+        limit: Option<i32>,
+        status: Status,
+    ) -> Result<ListPetsResponse, ClientError<T::Error>> {
+        let mut query = Vec::new();
+        if let Some(limit) = &limit {
+            for value in parameter_values(limit)? {
+                query.push(format!("limit={}", encode(&value)));
+            }
+        }
+        for value in parameter_values(&status)? {
+            query.push(format!("status={}", encode(&value)));
+        }
+        let mut url = format!("{}/pets", self.base_url);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        let request = http::Request::builder()
+            .method(http::Method::GET)
+            .uri(url);
+        let request = request.body(Vec::new())?;
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(ClientError::Transport)?;
+        match response.status().as_u16() {
+            200 => Ok(ListPetsResponse::Ok(serde_json::from_slice(response.body())?)),
+            _ => Ok(ListPetsResponse::Default(response.status(), serde_json::from_slice(response.body())?)),
+        }
+    }
+
+    pub async fn create_pet(
+        &self,
+        body: &Pet,
+    ) -> Result<CreatePetResponse, ClientError<T::Error>> {
+        let url = format!("{}/pets", self.base_url);
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .uri(url);
+        let request = request
+            .header("content-type", "application/json")
+            .body(serde_json::to_vec(body)?)?;
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(ClientError::Transport)?;
+        match response.status().as_u16() {
+            201 => Ok(CreatePetResponse::Created(serde_json::from_slice(response.body())?)),
+            422 => Ok(CreatePetResponse::UnprocessableEntity(serde_json::from_slice(response.body())?)),
+            _ => Err(ClientError::UnexpectedStatus(response)),
+        }
+    }
+
+    pub async fn show_pet_by_id(
+        &self,
+        pet_id: i64,
+    ) -> Result<ShowPetByIdResponse, ClientError<T::Error>> {
+        let url = format!("{}/pets/{}", self.base_url, encode(&parameter_values(&pet_id)?.join(",")));
+        let request = http::Request::builder()
+            .method(http::Method::GET)
+            .uri(url);
+        let request = request.body(Vec::new())?;
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(ClientError::Transport)?;
+        match response.status().as_u16() {
+            200 => Ok(ShowPetByIdResponse::Ok(serde_json::from_slice(response.body())?)),
+            404 => Ok(ShowPetByIdResponse::NotFound),
+            _ => Err(ClientError::UnexpectedStatus(response)),
+        }
+    }
+
+    pub async fn delete_pet(
+        &self,
+        pet_id: i64,
+        x_request_id: Option<String>,
+    ) -> Result<DeletePetResponse, ClientError<T::Error>> {
+        let url = format!("{}/pets/{}", self.base_url, encode(&parameter_values(&pet_id)?.join(",")));
+        let mut request = http::Request::builder()
+            .method(http::Method::DELETE)
+            .uri(url);
+        if let Some(x_request_id) = &x_request_id {
+            request = request.header("X-Request-Id", parameter_values(x_request_id)?.join(","));
+        }
+        let request = request.body(Vec::new())?;
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(ClientError::Transport)?;
+        match response.status().as_u16() {
+            204 => Ok(DeletePetResponse::NoContent),
+            _ => Err(ClientError::UnexpectedStatus(response)),
+        }
+    }
+
+    pub async fn upload_photo(
+        &self,
+        pet_id: i64,
+        session: Option<String>,
+        tags: Option<Vec<UploadPhotoTagsItem>>,
+        body: Option<Vec<u8>>,
+    ) -> Result<UploadPhotoResponse, ClientError<T::Error>> {
+        let mut query = Vec::new();
+        if let Some(tags) = &tags {
+            for value in parameter_values(tags)? {
+                query.push(format!("tags={}", encode(&value)));
+            }
+        }
+        let mut url = format!("{}/pets/{}/photo", self.base_url, encode(&parameter_values(&pet_id)?.join(",")));
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        let mut request = http::Request::builder()
+            .method(http::Method::PUT)
+            .uri(url);
+        let mut cookies = Vec::new();
+        if let Some(session) = &session {
+            let values: Vec<String> = parameter_values(session)?
+                .iter()
+                .map(|value| encode(value))
+                .collect();
+            cookies.push(format!("session={}", values.join(",")));
+        }
+        if !cookies.is_empty() {
+            request = request.header("cookie", cookies.join("; "));
+        }
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "image/png")
+                .body(body)?,
+            None => request.body(Vec::new())?,
+        };
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(ClientError::Transport)?;
+        match response.status().as_u16() {
+            200..=299 => Ok(UploadPhotoResponse::Success(response.status(), response.into_body())),
+            _ => Err(ClientError::UnexpectedStatus(response)),
+        }
+    }
+}
+
+/// The responses of [`Client::list_pets`].
+#[derive(Debug)]
+pub enum ListPetsResponse {
+    /// A page of pets.
+    Ok(Pets),
+    /// Unexpected error.
+    Default(http::StatusCode, Error),
+}
+
+/// The responses of [`Client::create_pet`].
+#[derive(Debug)]
+pub enum CreatePetResponse {
+    /// The created pet.
+    Created(Pet),
+    /// Invalid pet.
+    UnprocessableEntity(Error),
+}
+
+/// The responses of [`Client::show_pet_by_id`].
+#[derive(Debug)]
+pub enum ShowPetByIdResponse {
+    /// The pet.
+    Ok(Pet),
+    /// No such pet.
+    NotFound,
+}
+
+/// The responses of [`Client::delete_pet`].
+#[derive(Debug)]
+pub enum DeletePetResponse {
+    /// Deleted.
+    NoContent,
+}
+
+/// The responses of [`Client::upload_photo`].
+#[derive(Debug)]
+pub enum UploadPhotoResponse {
+    /// The stored photo.
+    Success(http::StatusCode, Vec<u8>),
+}
+
+/// The values of a parameter: the items of an array, or the value itself.
+fn parameter_values<P: serde::Serialize>(value: &P) -> Result<Vec<String>, serde_json::Error> {
+    let values = match serde_json::to_value(value)? {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            serde_json::Value::String(value) => value,
+            value => value.to_string(),
+        })
+        .collect())
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,446 @@
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DogOwner {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Dog {
+    pub name: String,
+    /// Whether he is.
+    ///
+    /// He is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub good_boy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<DogOwner>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Breed {
+    #[serde(rename = "siamese")]
+    Siamese,
+    #[serde(rename = "maine-coon")]
+    MaineCoon,
+    #[serde(rename = "")]
+    Empty,
+    #[serde(rename = "3legged")]
+    _3legged,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cat {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lives: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<Breed>,
+}
+
+/// A pet, told apart by its `petType`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "petType")]
+pub enum Pet {
+    #[serde(rename = "dog")]
+    Dog(Dog),
+    #[serde(rename = "Cat")]
+    Cat(Cat),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Status {
+    #[serde(rename = "available")]
+    Available,
+    #[serde(rename = "adopted")]
+    Adopted,
+}
+
+pub type Pets = Vec<Pet>;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Problem {
+    pub code: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Error {
+    #[serde(flatten)]
+    pub problem: Problem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum Id {
+    String(String),
+    Integer(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Category {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<Category>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<Category>>,
+    #[serde(flatten)]
+    pub additional_properties: std::collections::HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Tree(pub Vec<Tree>);
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum UploadPhotoTagsItem {
+    #[serde(rename = "cute")]
+    Cute,
+    #[serde(rename = "funny")]
+    Funny,
+}
+
+/// The future returned by the methods of [`Server`].
+pub type HandlerFuture<'s, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 's>>;
+
+/// The operations of the API, called by [`handle`] for the requests it routes.
+pub trait Server {
+    fn list_pets(
+        &self,
+        limit: Option<i32>,
+        status: Status,
+    ) -> HandlerFuture<'_, ListPetsResponse>;
+
+    fn create_pet(
+        &self,
+        body: Pet,
+    ) -> HandlerFuture<'_, CreatePetResponse>;
+
+    fn show_pet_by_id(
+        &self,
+        pet_id: i64,
+    ) -> HandlerFuture<'_, ShowPetByIdResponse>;
+
+    fn delete_pet(
+        &self,
+        pet_id: i64,
+        x_request_id: Option<String>,
+    ) -> HandlerFuture<'_, DeletePetResponse>;
+
+    fn upload_photo(
+        &self,
+        pet_id: i64,
+        session: Option<String>,
+        tags: Option<Vec<UploadPhotoTagsItem>>,
+        body: Option<Vec<u8>>,
+    ) -> HandlerFuture<'_, UploadPhotoResponse>;
+}
+
+/// The responses of [`Server::list_pets`].
+#[derive(Debug)]
+pub enum ListPetsResponse {
+    /// A page of pets.
+    Ok(Pets),
+    /// Unexpected error.
+    Default(http::StatusCode, Error),
+}
+
+impl ListPetsResponse {
+    /// Converts the response to an HTTP response.
+    pub fn into_response(self) -> http::Response<Vec<u8>> {
+        match self {
+            ListPetsResponse::Ok(body) => json_response(200, "application/json", &body),
+            ListPetsResponse::Default(status, body) => json_response(status.as_u16(), "application/json", &body),
+        }
+    }
+}
+
+/// The responses of [`Server::create_pet`].
+#[derive(Debug)]
+pub enum CreatePetResponse {
+    /// The created pet.
+    Created(Pet),
+    /// Invalid pet.
+    UnprocessableEntity(Error),
+}
+
+impl CreatePetResponse {
+    /// Converts the response to an HTTP response.
+    pub fn into_response(self) -> http::Response<Vec<u8>> {
+        match self {
+            CreatePetResponse::Created(body) => json_response(201, "application/json", &body),
+            CreatePetResponse::UnprocessableEntity(body) => json_response(422, "application/json", &body),
+        }
+    }
+}
+
+/// The responses of [`Server::show_pet_by_id`].
+#[derive(Debug)]
+pub enum ShowPetByIdResponse {
+    /// The pet.
+    Ok(Pet),
+    /// No such pet.
+    NotFound,
+}
+
+impl ShowPetByIdResponse {
+    /// Converts the response to an HTTP response.
+    pub fn into_response(self) -> http::Response<Vec<u8>> {
+        match self {
+            ShowPetByIdResponse::Ok(body) => json_response(200, "application/json", &body),
+            ShowPetByIdResponse::NotFound => response(404, None, Vec::new()),
+        }
+    }
+}
+
+/// The responses of [`Server::delete_pet`].
+#[derive(Debug)]
+pub enum DeletePetResponse {
+    /// Deleted.
+    NoContent,
+}
+
+impl DeletePetResponse {
+    /// Converts the response to an HTTP response.
+    pub fn into_response(self) -> http::Response<Vec<u8>> {
+        match self {
+            DeletePetResponse::NoContent => response(204, None, Vec::new()),
+        }
+    }
+}
+
+/// The responses of [`Server::upload_photo`].
+#[derive(Debug)]
+pub enum UploadPhotoResponse {
+    /// The stored photo.
+    Success(http::StatusCode, Vec<u8>),
+}
+
+impl UploadPhotoResponse {
+    /// Converts the response to an HTTP response.
+    pub fn into_response(self) -> http::Response<Vec<u8>> {
+        match self {
+            UploadPhotoResponse::Success(status, body) => response(status.as_u16(), Some("image/png"), body),
+        }
+    }
+}
+
+/// Answers `request` with the method of `server` for its path and method.
+///
+/// Requests with invalid parameters or bodies are answered with `400 Bad Request`, those to unknown paths with `404 Not Found` and those with unknown methods with `405 Method Not Allowed`.
+pub async fn handle<S: Server + ?Sized>(
+    server: &S,
+    request: http::Request<Vec<u8>>,
+) -> http::Response<Vec<u8>> {
+    let segments: Vec<String> = request.uri().path().split('/').skip(1).map(decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let result = match segments.as_slice() {
+        ["pets"] => match request.method().as_str() {
+            "GET" => handle_list_pets(server, &request).await,
+            "POST" => handle_create_pet(server, &request).await,
+            _ => Ok(response(405, Some("text/plain"), b"Method Not Allowed".to_vec())),
+        },
+        ["pets", pet_id] => match request.method().as_str() {
+            "GET" => handle_show_pet_by_id(server, &request, pet_id).await,
+            "DELETE" => handle_delete_pet(server, &request, pet_id).await,
+            _ => Ok(response(405, Some("text/plain"), b"Method Not Allowed".to_vec())),
+        },
+        ["pets", pet_id, "photo"] => match request.method().as_str() {
+            "PUT" => handle_upload_photo(server, &request, pet_id).await,
+            _ => Ok(response(405, Some("text/plain"), b"Method Not Allowed".to_vec())),
+        },
+        _ => Ok(response(404, Some("text/plain"), b"Not Found".to_vec())),
+    };
+    result.unwrap_or_else(|message| response(400, Some("text/plain"), message.into_bytes()))
+}
+
+async fn handle_list_pets<S: Server + ?Sized>(
+    server: &S,
+    request: &http::Request<Vec<u8>>,
+) -> Result<http::Response<Vec<u8>>, String> {
+    let limit: Option<i32> = optional_parameter(query_values(request, "limit"), "limit")?;
+    let status: Status = parameter(query_values(request, "status"), "status")?;
+    Ok(server.list_pets(limit, status).await.into_response())
+}
+
+async fn handle_create_pet<S: Server + ?Sized>(
+    server: &S,
+    request: &http::Request<Vec<u8>>,
+) -> Result<http::Response<Vec<u8>>, String> {
+    let body: Pet = serde_json::from_slice(request.body())
+        .map_err(|error| format!("invalid body: {}", error))?;
+    Ok(server.create_pet(body).await.into_response())
+}
+
+async fn handle_show_pet_by_id<S: Server + ?Sized>(
+    server: &S,
+    _request: &http::Request<Vec<u8>>,
+    pet_id: &str,
+) -> Result<http::Response<Vec<u8>>, String> {
+    let pet_id: i64 = parameter(vec![pet_id.to_string()], "petId")?;
+    Ok(server.show_pet_by_id(pet_id).await.into_response())
+}
+
+async fn handle_delete_pet<S: Server + ?Sized>(
+    server: &S,
+    request: &http::Request<Vec<u8>>,
+    pet_id: &str,
+) -> Result<http::Response<Vec<u8>>, String> {
+    let pet_id: i64 = parameter(vec![pet_id.to_string()], "petId")?;
+    let x_request_id: Option<String> = optional_parameter(header_values(request, "X-Request-Id"), "X-Request-Id")?;
+    Ok(server.delete_pet(pet_id, x_request_id).await.into_response())
+}
+
+async fn handle_upload_photo<S: Server + ?Sized>(
+    server: &S,
+    request: &http::Request<Vec<u8>>,
+    pet_id: &str,
+) -> Result<http::Response<Vec<u8>>, String> {
+    let pet_id: i64 = parameter(vec![pet_id.to_string()], "petId")?;
+    let session: Option<String> = optional_parameter(cookie_values(request, "session"), "session")?;
+    let tags: Option<Vec<UploadPhotoTagsItem>> = optional_parameter(query_values(request, "tags"), "tags")?;
+    let body: Option<Vec<u8>> = if request.body().is_empty() {
+        None
+    } else {
+        Some(request.body().clone())
+    };
+    Ok(server.upload_photo(pet_id, session, tags, body).await.into_response())
+}
+
+/// Percent-decodes `value`.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A response with `status` and `body`, of `content_type` if there is one.
+fn response(status: u16, content_type: Option<&'static str>, body: Vec<u8>) -> http::Response<Vec<u8>> {
+    let mut response = http::Response::new(body);
+    *response.status_mut() =
+        http::StatusCode::from_u16(status).unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = content_type {
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static(content_type),
+        );
+    }
+    response
+}
+
+/// A response with `status` and `body` serialized to JSON, or `500 Internal Server Error` if it can't be.
+fn json_response<T: serde::Serialize>(
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) -> http::Response<Vec<u8>> {
+    match serde_json::to_vec(body) {
+        Ok(body) => response(status, Some(content_type), body),
+        Err(error) => response(500, Some("text/plain"), error.to_string().into_bytes()),
+    }
+}
+
+/// The values of the query parameter `name`.
+fn query_values(request: &http::Request<Vec<u8>>, name: &str) -> Vec<String> {
+    request
+        .uri()
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .filter(|(key, _)| decode(&key.replace('+', " ")) == name)
+        .map(|(_, value)| decode(&value.replace('+', " ")))
+        .collect()
+}
+
+/// The values of the header `name`.
+fn header_values(request: &http::Request<Vec<u8>>, name: &str) -> Vec<String> {
+    request
+        .headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The values of the cookie `name`.
+fn cookie_values(request: &http::Request<Vec<u8>>, name: &str) -> Vec<String> {
+    request
+        .headers()
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .filter(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .collect()
+}
+
+/// Deserializes the required parameter `name` from its values.
+fn parameter<T: serde::de::DeserializeOwned>(values: Vec<String>, name: &str) -> Result<T, String> {
+    optional_parameter(values, name)?.ok_or_else(|| format!("missing parameter `{}`", name))
+}
+
+/// Deserializes the parameter `name` from its values, each being either JSON or a plain string. Arrays may be comma-separated.
+fn optional_parameter<T: serde::de::DeserializeOwned>(
+    values: Vec<String>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    let json = |value: &str| {
+        serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()))
+    };
+
+    let mut candidates = Vec::new();
+    let items: Vec<String> = match values.as_slice() {
+        [] => return Ok(None),
+        [value] => {
+            candidates.push(json(value));
+            candidates.push(serde_json::Value::String(value.clone()));
+            value.split(',').map(str::to_string).collect()
+        }
+        _ => values,
+    };
+    candidates.push(serde_json::Value::Array(
+        items.iter().map(|item| json(item)).collect(),
+    ));
+    candidates.push(serde_json::Value::Array(
+        items.into_iter().map(serde_json::Value::String).collect(),
+    ));
+
+    let mut error = None;
+    for candidate in candidates {
+        match serde_json::from_value(candidate) {
+            Ok(value) => return Ok(Some(value)),
+            Err(candidate_error) => error = error.or(Some(candidate_error)),
+        }
+    }
+    Err(match error {
+        Some(error) => format!("invalid parameter `{}`: {}", name, error),
+        None => format!("invalid parameter `{}`", name),
+    })
+}
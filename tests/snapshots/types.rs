@@ -0,0 +1,95 @@
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DogOwner {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Dog {
+    pub name: String,
+    /// Whether he is.
+    ///
+    /// He is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub good_boy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<DogOwner>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Breed {
+    #[serde(rename = "siamese")]
+    Siamese,
+    #[serde(rename = "maine-coon")]
+    MaineCoon,
+    #[serde(rename = "")]
+    Empty,
+    #[serde(rename = "3legged")]
+    _3legged,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cat {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lives: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<Breed>,
+}
+
+/// A pet, told apart by its `petType`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "petType")]
+pub enum Pet {
+    #[serde(rename = "dog")]
+    Dog(Dog),
+    #[serde(rename = "Cat")]
+    Cat(Cat),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Status {
+    #[serde(rename = "available")]
+    Available,
+    #[serde(rename = "adopted")]
+    Adopted,
+}
+
+pub type Pets = Vec<Pet>;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Problem {
+    pub code: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Error {
+    #[serde(flatten)]
+    pub problem: Problem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<std::collections::HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum Id {
+    String(String),
+    Integer(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Category {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<Category>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<Category>>,
+    #[serde(flatten)]
+    pub additional_properties: std::collections::HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Tree(pub Vec<Tree>);
@@ -0,0 +1,234 @@
+use bytes::Bytes;
+use http::{Response, StatusCode};
+use oa::v3::{
+    validation::{
+        validate_examples, validate_response, ExampleError, ValidationErrorKind, ValidationReport,
+    },
+    OpenAPIObject, OperationObject, SchemaType,
+};
+
+fn document() -> OpenAPIObject {
+    serde_yaml::from_str(
+        r##"
+openapi: 3.0.3
+info: {title: Users, version: "1"}
+servers: []
+paths:
+  /users/{id}:
+    get:
+      responses:
+        "200":
+          description: A user.
+          headers:
+            X-Rate-Limit:
+              required: true
+              schema: {type: integer, minimum: 0}
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/User"}
+        "204":
+          description: No user.
+        4XX:
+          description: A client error.
+          content:
+            application/json:
+              schema: {type: object, required: [message]}
+components:
+  schemas:
+    User:
+      type: object
+      required: [name, password]
+      properties:
+        name: {type: string}
+        password: {type: string, writeOnly: true}
+"##,
+    )
+    .unwrap()
+}
+
+fn operation(document: &OpenAPIObject) -> &OperationObject {
+    document.paths["/users/{id}"]
+        .as_object()
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap()
+}
+
+fn response(status: u16, rate_limit: Option<&str>, body: &'static str) -> Response<Bytes> {
+    let mut response = Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json");
+    if let Some(rate_limit) = rate_limit {
+        response = response.header("X-Rate-Limit", rate_limit);
+    }
+    response.body(Bytes::from_static(body.as_bytes())).unwrap()
+}
+
+fn errors(report: ValidationReport) -> Vec<(String, ValidationErrorKind)> {
+    report
+        .errors
+        .into_iter()
+        .map(|error| (error.pointer, error.kind))
+        .collect()
+}
+
+#[test]
+fn responses() {
+    let document = document();
+    let operation = operation(&document);
+    let validate = |response| errors(validate_response(&document, operation, &response));
+
+    // `writeOnly` properties can be left out of responses even if they are required
+    assert!(validate(response(200, Some("10"), r#"{"name": "a"}"#)).is_empty());
+
+    assert_eq!(
+        validate(response(200, None, r#"{"name": 1}"#)),
+        vec![
+            (
+                "/header/X-Rate-Limit".to_string(),
+                ValidationErrorKind::MissingHeader
+            ),
+            (
+                "/body/name".to_string(),
+                ValidationErrorKind::Type {
+                    expected: SchemaType::String,
+                    found: "number"
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        validate(response(200, Some("-1"), "")),
+        vec![
+            (
+                "/header/X-Rate-Limit".to_string(),
+                ValidationErrorKind::Minimum {
+                    minimum: 0,
+                    exclusive: false
+                }
+            ),
+            ("/body".to_string(), ValidationErrorKind::MissingBody),
+        ]
+    );
+}
+
+#[test]
+fn statuses() {
+    let document = document();
+    let operation = operation(&document);
+    let validate = |response| errors(validate_response(&document, operation, &response));
+
+    assert!(validate(response(204, None, "")).is_empty());
+    assert_eq!(
+        validate(response(204, None, "{}")),
+        vec![("/body".to_string(), ValidationErrorKind::UnexpectedBody)]
+    );
+
+    // Ranges cover the statuses without a response of their own
+    assert!(validate(response(404, None, r#"{"message": "Not found"}"#)).is_empty());
+    assert_eq!(
+        validate(response(404, None, "{}")),
+        vec![(
+            "/body".to_string(),
+            ValidationErrorKind::Required("message".to_string())
+        )]
+    );
+
+    assert_eq!(
+        validate(response(500, None, "")),
+        vec![(
+            "/status".to_string(),
+            ValidationErrorKind::UndocumentedStatus(StatusCode::INTERNAL_SERVER_ERROR)
+        )]
+    );
+}
+
+#[test]
+fn examples() {
+    let document: OpenAPIObject = serde_yaml::from_str(
+        r##"
+openapi: 3.0.3
+info: {title: Users, version: "1"}
+servers: []
+paths:
+  /users/{id}:
+    get:
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema: {type: integer}
+          examples:
+            valid: {value: 1}
+            invalid: {value: one}
+        - name: filter
+          in: query
+          content:
+            application/json:
+              schema: {type: object, required: [name]}
+          example: {}
+      responses:
+        "200":
+          description: A user.
+          headers:
+            X-Rate-Limit:
+              schema: {type: integer}
+              example: 10
+              examples: {ten: {value: 10}}
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/User"}
+              example: {name: 1}
+components:
+  schemas:
+    User:
+      type: object
+      required: [name]
+      properties:
+        name: {type: string, example: 1}
+"##,
+    )
+    .unwrap();
+
+    let errors: Vec<_> = validate_examples(&document)
+        .into_iter()
+        .map(|error| match error {
+            ExampleError::Mismatch {
+                example,
+                schema,
+                errors,
+            } => (example, Some(schema), errors.len()),
+            ExampleError::Conflict { pointer } => (pointer, None, 0),
+        })
+        .collect();
+    let mismatch = |example: &str, schema: &str| (example.to_string(), Some(schema.to_string()), 1);
+    assert_eq!(
+        errors,
+        vec![
+            mismatch(
+                "/paths/~1users~1{id}/get/parameters/0/examples/invalid/value",
+                "/paths/~1users~1{id}/get/parameters/0/schema"
+            ),
+            // Parameters with content are validated against the schema of their media type
+            mismatch(
+                "/paths/~1users~1{id}/get/parameters/1/example",
+                "/paths/~1users~1{id}/get/parameters/1/content/application~1json/schema"
+            ),
+            (
+                "/paths/~1users~1{id}/get/responses/200/headers/X-Rate-Limit".to_string(),
+                None,
+                0
+            ),
+            // Local references are reported where they point to
+            mismatch(
+                "/paths/~1users~1{id}/get/responses/200/content/application~1json/example",
+                "/components/schemas/User"
+            ),
+            mismatch(
+                "/components/schemas/User/properties/name/example",
+                "/components/schemas/User/properties/name"
+            ),
+        ]
+    );
+}